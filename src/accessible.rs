@@ -0,0 +1,104 @@
+// --accessible: output a screen reader can follow. Gauges become text, lines and bars are blanked
+// out, the selection is marked with "-> " rather than reversed colors, and the footer's first line
+// says which popup is open and what the last action did.
+use ratatui::buffer::Buffer;
+use ratatui::style::Modifier;
+
+use crate::process_list::SortOrder;
+use crate::{App, InputMode};
+
+// Replaces the main table's ">> " and is added to lists that only had colors.
+const MARKER: &str = "-> ";
+
+impl InputMode {
+    fn name(&self) -> Option<&'static str> {
+        Some(match self {
+            InputMode::Normal | InputMode::Search | InputMode::Find => return None,
+            InputMode::KillMenu => "Signal menu",
+            InputMode::ConfirmKill => "Confirm signal",
+            InputMode::ConfirmBulkKill => "Confirm signal to all matching",
+            InputMode::ConfirmRestart => "Confirm restart",
+            InputMode::ActionMenu => "Process menu",
+            InputMode::Setup => "Setup",
+            InputMode::Detail => "Process details",
+            InputMode::VmStats => "VM statistics",
+            InputMode::Network => "Network interfaces",
+            InputMode::Disks => "Disks",
+            InputMode::Sensors => "Sensors",
+            InputMode::FilterBuilder => "Filter builder",
+            InputMode::Presets => "Filter presets",
+            InputMode::AlertHistory => "Alert history",
+            InputMode::Trace => "System call trace",
+            InputMode::Viewer => "Text viewer",
+            InputMode::Zombies => "Zombies",
+        })
+    }
+}
+
+impl App {
+    // `shown` normally, the explicit marker with --accessible.
+    pub fn selection_marker(&self, shown: &'static str) -> &'static str {
+        if self.accessible { MARKER } else { shown }
+    }
+
+    // Feedback for actions that otherwise only show in the layout, like a new sort order.
+    pub fn announce(&mut self, text: String) {
+        if self.accessible { self.message = Some(text); }
+    }
+
+    pub fn announce_sort(&mut self) {
+        let column = self.columns.iter().find(|c| c.column.sort_key() == Some(self.sort_by)).map(|c| c.column.header(self.cell_format));
+        let order = if self.sort_order == SortOrder::Asc { "lowest first" } else { "highest first" };
+        self.announce(format!("Sorted by {}, {}", column.unwrap_or("the sort column"), order));
+    }
+
+    // Goes ahead of the footer's message while a popup is open: "Disks open, Esc to close".
+    pub fn mode_announcement(&self) -> Option<&'static str> {
+        self.input_mode.name().filter(|_| self.accessible)
+    }
+}
+
+// Blanks out box-drawing and block characters, which readers spell out one by one, and drops
+// reversed video, which they can't see.
+pub fn plain_buffer(buf: &mut Buffer) {
+    for cell in &mut buf.content {
+        let graphic = cell.symbol().chars().next().is_some_and(|c| ('\u{2500}'..='\u{259f}').contains(&c));
+        if graphic { cell.set_symbol(" "); }
+        cell.modifier.remove(Modifier::REVERSED);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::widgets::{Block, Borders, Widget};
+    use crate::config::Config;
+    use crate::process_list::SortBy;
+
+    #[test]
+    fn borders_and_bars_are_blanked_and_actions_announced() {
+        let area = Rect::new(0, 0, 8, 3);
+        let mut buf = Buffer::empty(area);
+        Block::default().borders(Borders::ALL).title("Disks").render(area, &mut buf);
+        buf.set_string(1, 1, "█▓ 50%", ratatui::style::Style::default());
+        buf.get_mut(6, 1).modifier.insert(Modifier::REVERSED);
+        plain_buffer(&mut buf);
+        let text: Vec<String> = (0..3).map(|y| (0..8).map(|x| buf.get(x, y).symbol()).collect()).collect();
+        assert_eq!(text, [" Disks  ", "    50% ", "        "]);
+        assert!(!buf.get(6, 1).modifier.contains(Modifier::REVERSED));
+
+        let mut app = App::new(&Config::default());
+        app.announce_sort();
+        assert_eq!(app.message, None);
+        app.input_mode = InputMode::Disks;
+        assert_eq!(app.mode_announcement(), None);
+        app.accessible = true;
+        assert_eq!(app.mode_announcement(), Some("Disks"));
+        app.sort_by = SortBy::Mem;
+        app.sort_order = SortOrder::Desc;
+        app.announce_sort();
+        assert_eq!(app.message.as_deref(), Some("Sorted by MEM%, highest first"));
+        assert_eq!(app.selection_marker(">> "), "-> ");
+    }
+}
@@ -0,0 +1,233 @@
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState};
+use ratatui::Frame;
+
+use crate::app_group::{app_name, app_of};
+use crate::keymap::Action;
+use crate::platform::{copy_to_clipboard, process_identity};
+use crate::{contains, rect_near, App, InputMode};
+
+// One entry of the action menu.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Details,
+    Signal,
+    Stop,
+    Continue,
+    StopApp,
+    ContinueApp,
+    Restart,
+    SelectParent,
+    RevealInTree,
+    RevealInFinder,
+    OpenConsole,
+    TraceSyscalls,
+    SampleStacks,
+    SaveStackSample,
+    CopyCommand,
+    CopyPid,
+}
+
+impl Item {
+    // StopApp and ContinueApp are labelled with the app's name instead; see `ActionMenu::label`.
+    fn label(self) -> &'static str {
+        match self {
+            Item::Details => "Details",
+            Item::Signal => "Send signal…",
+            Item::Stop => "Stop (SIGSTOP)",
+            Item::Continue => "Continue (SIGCONT)",
+            Item::StopApp => "Stop all of the app",
+            Item::ContinueApp => "Continue all of the app",
+            Item::Restart => "Restart via launchd",
+            Item::SelectParent => "Select parent",
+            Item::RevealInTree => "Show in tree",
+            Item::RevealInFinder => "Show in Finder",
+            Item::OpenConsole => "Open Console",
+            Item::TraceSyscalls => "Trace system calls",
+            Item::SampleStacks => "Sample call stacks",
+            Item::SaveStackSample => "Save stack sample to file",
+            Item::CopyCommand => "Copy command line",
+            Item::CopyPid => "Copy PID",
+        }
+    }
+
+    // The main-screen action doing the same thing, whose key is shown next to the entry.
+    fn action(self) -> Option<Action> {
+        match self {
+            Item::Details => Some(Action::Details),
+            Item::Signal => Some(Action::Kill),
+            Item::Restart => Some(Action::Restart),
+            Item::RevealInTree => Some(Action::RevealInTree),
+            Item::RevealInFinder => Some(Action::RevealInFinder),
+            Item::OpenConsole => Some(Action::OpenConsole),
+            Item::TraceSyscalls => Some(Action::TraceSyscalls),
+            Item::SampleStacks => Some(Action::SampleStacks),
+            Item::SaveStackSample => Some(Action::SaveStackSample),
+            _ => None,
+        }
+    }
+}
+
+// Everything that can be done to one process, in one place (Enter or right-click). Only the
+// entries that apply to the process are listed.
+pub struct ActionMenu {
+    pid: u32,
+    items: Vec<Item>,
+    app: Option<String>, // Name of the .app bundle the process belongs to
+    at: Option<(u16, u16)>, // Opened by right-click here; None to center it
+    list: Rect, // Screen area of the entries, from the last draw
+    state: ListState,
+}
+
+impl ActionMenu {
+    fn label(&self, item: Item) -> String {
+        match (item, &self.app) {
+            (Item::StopApp, Some(app)) => format!("Stop all of {}", app),
+            (Item::ContinueApp, Some(app)) => format!("Continue all of {}", app),
+            _ => item.label().to_string(),
+        }
+    }
+}
+
+impl App {
+    pub fn open_action_menu(&mut self, at: Option<(u16, u16)>) {
+        let Some(pid) = self.selected_pid() else { return };
+        let Some(p) = self.processes.get(&pid) else { return };
+        let zombie = p.status == "Z";
+        let stopped = p.status == "T";
+        let has_parent = p.ppid != 0 && self.processes.contains_key(&p.ppid);
+        let app = app_of(&p.exe).map(|bundle| app_name(bundle).to_string());
+        let mut items = vec![Item::Details];
+        // A zombie has already exited: signals do nothing, its parent is what to act on.
+        if !self.read_only && !zombie {
+            items.extend([Item::Signal, if stopped { Item::Continue } else { Item::Stop }]);
+            // The members are looked up again when the entry is picked.
+            if app.is_some() { items.extend([Item::StopApp, Item::ContinueApp]); }
+            // Whether it is a launchd job is only looked up once picked: that spawns `launchctl`.
+            if self.replay.is_none() { items.push(Item::Restart); }
+        }
+        if has_parent && (zombie || self.tree_view) { items.push(Item::SelectParent); }
+        if !self.tree_view { items.push(Item::RevealInTree); }
+        // A recording's paths are those of another machine.
+        if self.replay.is_none() { items.push(Item::RevealInFinder); }
+        items.push(Item::OpenConsole);
+        if self.replay.is_none() && !zombie { items.extend([Item::TraceSyscalls, Item::SampleStacks, Item::SaveStackSample]); }
+        items.extend([Item::CopyCommand, Item::CopyPid]);
+        self.action_menu = Some(ActionMenu { pid, items, app, at, list: Rect::default(), state: ListState::default().with_selected(Some(0)) });
+        self.input_mode = InputMode::ActionMenu;
+    }
+
+    pub fn handle_action_menu_key(&mut self, code: KeyCode) {
+        let Some(menu) = self.action_menu.as_mut() else { return self.input_mode = InputMode::Normal };
+        let last = menu.items.len() - 1;
+        let selected = menu.state.selected().unwrap_or(0);
+        match code {
+            KeyCode::Down => menu.state.select(Some(if selected >= last { 0 } else { selected + 1 })),
+            KeyCode::Up => menu.state.select(Some(if selected == 0 { last } else { selected - 1 })),
+            KeyCode::Home => menu.state.select(Some(0)),
+            KeyCode::End => menu.state.select(Some(last)),
+            KeyCode::Enter => {
+                let menu = self.action_menu.take().unwrap();
+                self.input_mode = InputMode::Normal;
+                self.run_action(&menu, menu.items[selected]);
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.action_menu = None;
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+    }
+
+    // A click picks an entry, a double-click runs it.
+    pub fn click_action_menu(&mut self, x: u16, y: u16, double: bool) {
+        let Some(menu) = self.action_menu.as_mut() else { return };
+        if !contains(menu.list, x, y) { return; }
+        let idx = menu.state.offset() + (y - menu.list.y) as usize;
+        if idx >= menu.items.len() { return; }
+        menu.state.select(Some(idx));
+        if double { self.handle_action_menu_key(KeyCode::Enter); }
+    }
+
+    // The table may have moved on while the menu was open, so the process is selected again
+    // before handing over to the action's usual handler.
+    fn run_action(&mut self, menu: &ActionMenu, item: Item) {
+        let pid = menu.pid;
+        if !self.processes.contains_key(&pid) {
+            self.message = Some(format!("PID {} has exited", pid));
+            return;
+        }
+        self.select_pid(pid);
+        match item {
+            Item::Details => self.open_detail(),
+            Item::Signal => self.open_kill_menu(),
+            Item::Stop | Item::Continue => {
+                self.kill_target = process_identity(pid).map(|identity| (pid, identity));
+                self.request_kill(pid, if item == Item::Stop { libc::SIGSTOP } else { libc::SIGCONT }, false);
+            }
+            Item::StopApp | Item::ContinueApp => self.signal_app(pid, item == Item::StopApp),
+            Item::Restart => self.request_restart(),
+            Item::SelectParent => {
+                let ppid = self.processes[&pid].ppid;
+                self.show_pid(ppid);
+            }
+            Item::RevealInTree => self.reveal_selected(),
+            Item::RevealInFinder => self.reveal_in_finder(),
+            Item::OpenConsole => self.open_console(),
+            Item::TraceSyscalls => self.start_trace(),
+            Item::SampleStacks => self.start_sampling(false),
+            Item::SaveStackSample => self.start_sampling(true),
+            Item::CopyCommand => {
+                let command = self.processes[&pid].command.clone();
+                self.copy(pid, &command, "command line");
+            }
+            Item::CopyPid => self.copy(pid, &pid.to_string(), "PID"),
+        }
+    }
+
+    fn copy(&mut self, pid: u32, text: &str, what: &str) {
+        self.message = Some(match copy_to_clipboard(text) {
+            Ok(()) => format!("Copied the {} of {}", what, self.process_label(pid)),
+            Err(e) => format!("Could not copy the {}: {}", what, e),
+        });
+    }
+}
+
+pub fn draw_action_menu(f: &mut Frame, app: &mut App, area: Rect) {
+    let marker = app.selection_marker(">> ");
+    let Some(menu) = app.action_menu.as_mut() else { return };
+    let keymap = &app.keymap;
+    let label_width = menu.items.iter().map(|&i| menu.label(i).chars().count()).max().unwrap_or(0);
+    let items: Vec<ListItem> = menu.items.iter().map(|&item| {
+        let key = item.action().and_then(|a| keymap.label(a)).unwrap_or_default();
+        ListItem::new(Line::from(vec![
+            Span::raw(format!("{:<width$}  ", menu.label(item), width = label_width)),
+            Span::styled(key, app.theme.dim),
+        ]))
+    }).collect();
+    let title = match app.processes.get(&menu.pid) {
+        Some(p) => format!("{} ({})", p.name, menu.pid),
+        None => format!("PID {}", menu.pid),
+    };
+    let width = (label_width as u16 + 16).max(title.chars().count() as u16 + 4);
+    let height = menu.items.len() as u16 + 2;
+    let rect = match menu.at {
+        Some((x, y)) => rect_near(x, y, width, height, area),
+        None => {
+            let (width, height) = (width.min(area.width), height.min(area.height));
+            Rect { x: area.x + (area.width - width) / 2, y: area.y + (area.height - height) / 2, width, height }
+        }
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+    menu.list = block.inner(rect);
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(app.theme.selection)
+        .highlight_symbol(marker);
+    // As `clear_popup` does; the menu is borrowed from `app` here.
+    f.render_widget(Clear, rect);
+    app.popup_area.set(Some(rect));
+    f.render_stateful_widget(list, rect, &mut menu.state);
+}
@@ -0,0 +1,302 @@
+// Threshold alerts from the config: "load1 > cores", "swap > 4G", "memory_pressure = critical".
+// All of them are evaluated in one place, `App::check_alerts`, once per snapshot.
+use std::collections::VecDeque;
+use std::time::{Instant, SystemTime};
+
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+use serde::{Deserialize, Serialize};
+
+use crate::format::{format_bytes, format_clock};
+use crate::platform::MemoryPressure;
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// Trips kept for the history popup.
+const HISTORY_LEN: usize = 20;
+
+// How far a tripped value has to come back past the threshold before the alert clears, as a
+// fraction of the threshold. Keeps a value hovering at the boundary from re-alerting every refresh.
+const HYSTERESIS: f64 = 0.05;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Metric {
+    Load1,
+    Load5,
+    Load15,
+    Cpu, // Average over all cores, 0-100
+    Memory, // Used bytes
+    Swap, // Used bytes
+    MemoryPressure,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Op {
+    Above,
+    Below,
+    Equal,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Threshold {
+    Value(f64), // Plain number, or bytes with a K/M/G/T suffix
+    Percent(f64), // Of the total, for memory and swap; the same as Value for CPU
+    Cores, // The number of cores, for load averages
+    Pressure(MemoryPressure),
+}
+
+// One configured alert, written in the config as "<metric> <op> <threshold>".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AlertRule {
+    text: String, // As written, for messages and saving back
+    metric: Metric,
+    op: Op,
+    threshold: Threshold,
+}
+
+// What the rules are checked against, taken from one snapshot.
+pub struct Readings {
+    pub load: [f64; 3],
+    pub cpu: f64,
+    pub used_mem: u64,
+    pub total_mem: u64,
+    pub used_swap: u64,
+    pub total_swap: u64,
+    pub pressure: Option<MemoryPressure>,
+    pub cores: usize,
+}
+
+impl TryFrom<String> for AlertRule {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Self, String> {
+        let (at, op) = text.char_indices().find_map(|(i, c)| match c {
+            '>' => Some((i, Op::Above)),
+            '<' => Some((i, Op::Below)),
+            '=' => Some((i, Op::Equal)),
+            _ => None,
+        }).ok_or_else(|| format!("alert \"{}\": expected <metric> >, < or = <threshold>", text))?;
+        let (metric, threshold) = (text[..at].trim(), text[at + 1..].trim());
+        let metric = match metric {
+            "load1" => Metric::Load1,
+            "load5" => Metric::Load5,
+            "load15" => Metric::Load15,
+            "cpu" => Metric::Cpu,
+            "memory" => Metric::Memory,
+            "swap" => Metric::Swap,
+            "memory_pressure" => Metric::MemoryPressure,
+            _ => return Err(format!("alert \"{}\": unknown metric \"{}\" (load1, load5, load15, cpu, memory, swap, memory_pressure)", text, metric)),
+        };
+        let threshold = parse_threshold(threshold).ok_or_else(|| format!("alert \"{}\": bad threshold \"{}\"", text, threshold))?;
+        let fits = match threshold {
+            Threshold::Value(_) => metric != Metric::MemoryPressure,
+            Threshold::Percent(_) => matches!(metric, Metric::Cpu | Metric::Memory | Metric::Swap),
+            Threshold::Cores => matches!(metric, Metric::Load1 | Metric::Load5 | Metric::Load15),
+            Threshold::Pressure(_) => metric == Metric::MemoryPressure,
+        };
+        if !fits {
+            return Err(format!("alert \"{}\": that threshold doesn't apply to this metric", text));
+        }
+        Ok(AlertRule { text, metric, op, threshold })
+    }
+}
+
+impl From<AlertRule> for String {
+    fn from(rule: AlertRule) -> String {
+        rule.text
+    }
+}
+
+// "cores", "80%", "4G", "1.5", "critical".
+fn parse_threshold(s: &str) -> Option<Threshold> {
+    match s {
+        "cores" => return Some(Threshold::Cores),
+        "normal" => return Some(Threshold::Pressure(MemoryPressure::Normal)),
+        "warning" | "warn" => return Some(Threshold::Pressure(MemoryPressure::Warning)),
+        "critical" => return Some(Threshold::Pressure(MemoryPressure::Critical)),
+        _ => {}
+    }
+    if let Some(percent) = s.strip_suffix('%') {
+        return percent.trim().parse().ok().map(Threshold::Percent);
+    }
+    let (number, scale) = match s.char_indices().last()? {
+        (i, 'K' | 'k') => (&s[..i], 1u64 << 10),
+        (i, 'M' | 'm') => (&s[..i], 1 << 20),
+        (i, 'G' | 'g') => (&s[..i], 1 << 30),
+        (i, 'T' | 't') => (&s[..i], 1 << 40),
+        _ => (s, 1),
+    };
+    number.trim().parse::<f64>().ok().filter(|n| n.is_finite()).map(|n| Threshold::Value(n * scale as f64))
+}
+
+impl AlertRule {
+    // (current value, threshold) in the same unit; None when the value is unknown.
+    fn reading(&self, r: &Readings) -> Option<(f64, f64)> {
+        let level = |p: MemoryPressure| p as u8 as f64;
+        let value = match self.metric {
+            Metric::Load1 => r.load[0],
+            Metric::Load5 => r.load[1],
+            Metric::Load15 => r.load[2],
+            Metric::Cpu => r.cpu,
+            Metric::Memory => r.used_mem as f64,
+            Metric::Swap => r.used_swap as f64,
+            Metric::MemoryPressure => level(r.pressure?),
+        };
+        let limit = match self.threshold {
+            Threshold::Value(v) => v,
+            Threshold::Percent(p) => match self.metric {
+                Metric::Memory => r.total_mem as f64 * p / 100.0,
+                Metric::Swap => r.total_swap as f64 * p / 100.0,
+                _ => p,
+            },
+            Threshold::Cores => r.cores as f64,
+            Threshold::Pressure(p) => level(p),
+        };
+        Some((value, limit))
+    }
+
+    // Whether the alert is tripped now, given whether it was before.
+    fn tripped(&self, was: bool, value: f64, limit: f64) -> bool {
+        // Pressure levels are steps, not a continuous value; a margin would only mean "one level more".
+        let margin = if was && self.metric != Metric::MemoryPressure { limit.abs() * HYSTERESIS } else { 0.0 };
+        match self.op {
+            Op::Above => value > limit - margin,
+            Op::Below => value < limit + margin,
+            Op::Equal => value == limit,
+        }
+    }
+
+    fn describe(&self, value: f64) -> String {
+        match self.metric {
+            Metric::Load1 | Metric::Load5 | Metric::Load15 => format!("{:.2}", value),
+            Metric::Cpu => format!("{:.0}%", value),
+            Metric::Memory | Metric::Swap => format_bytes(value as u64),
+            Metric::MemoryPressure => match value as u8 {
+                0 => "normal".to_string(),
+                1 => "warning".to_string(),
+                _ => "critical".to_string(),
+            },
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Alerts {
+    tripped: Vec<bool>, // Per rule of `Config::alerts`
+    pub banner: Option<(String, Instant)>, // Latest trip until acknowledged, and when it happened
+    pub flash_drawn: Option<bool>, // Phase of the header flash in the last frame
+    history: VecDeque<(SystemTime, String)>, // Newest last
+    bell: bool, // A trip is waiting to ring the terminal bell
+}
+
+impl Alerts {
+    // The header is drawn red every other second until the banner is acknowledged.
+    pub fn flash_phase(&self) -> Option<bool> {
+        self.banner.as_ref().map(|(_, at)| at.elapsed().as_secs() % 2 == 0)
+    }
+
+    pub fn take_bell(&mut self) -> bool {
+        std::mem::take(&mut self.bell)
+    }
+}
+
+impl App {
+    pub fn check_alerts(&mut self) {
+        let rules = &self.config.alerts;
+        if rules.is_empty() { return; }
+        let readings = Readings {
+            load: [self.load_avg.one, self.load_avg.five, self.load_avg.fifteen],
+            cpu: if self.cpus.is_empty() { 0.0 } else { self.cpus.iter().map(|&c| c as f64).sum::<f64>() / self.cpus.len() as f64 },
+            used_mem: self.used_mem,
+            total_mem: self.total_mem,
+            used_swap: self.used_swap,
+            total_swap: self.total_swap,
+            pressure: self.memory_pressure,
+            cores: self.cpus.len(),
+        };
+        let alerts = &mut self.alerts;
+        alerts.tripped.resize(rules.len(), false);
+        for (rule, was) in rules.iter().zip(alerts.tripped.iter_mut()) {
+            let Some((value, limit)) = rule.reading(&readings) else { *was = false; continue };
+            let now = rule.tripped(*was, value, limit);
+            if now && !*was {
+                let text = format!("{} (now {})", rule.text, rule.describe(value));
+                if alerts.history.len() == HISTORY_LEN { alerts.history.pop_front(); }
+                alerts.history.push_back((SystemTime::now(), text.clone()));
+                alerts.banner = Some((text, Instant::now()));
+                alerts.bell |= self.config.alert_bell;
+            }
+            *was = now;
+        }
+    }
+
+    pub fn handle_alert_history_key(&mut self, code: KeyCode) {
+        if matches!(code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('!')) {
+            self.input_mode = InputMode::Normal;
+        }
+    }
+}
+
+pub fn draw_alert_history(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(60, 60, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Alerts");
+    let mut lines: Vec<Line> = if app.config.alerts.is_empty() {
+        vec![Line::from("No alerts configured; add some to `alerts` in the config file.")]
+    } else if app.alerts.history.is_empty() {
+        vec![Line::from("Nothing has tripped yet.")]
+    } else {
+        app.alerts.history.iter().rev().map(|(at, text)| {
+            Line::from(vec![Span::styled(format!("{}  ", format_clock(*at)), app.theme.label), Span::raw(text.as_str())])
+        }).collect()
+    };
+    lines.extend([Line::from(""), Line::from(Span::styled("Esc close", app.theme.dim))]);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(text: &str) -> AlertRule {
+        AlertRule::try_from(text.to_string()).unwrap()
+    }
+
+    fn readings() -> Readings {
+        Readings { load: [0.0; 3], cpu: 0.0, used_mem: 0, total_mem: 16 << 30, used_swap: 0, total_swap: 8 << 30, pressure: None, cores: 8 }
+    }
+
+    #[test]
+    fn parses_rules_and_rejects_mismatched_thresholds() {
+        let swap = rule("swap > 4G");
+        assert_eq!((swap.metric, swap.op, swap.threshold), (Metric::Swap, Op::Above, Threshold::Value((4u64 << 30) as f64)));
+        assert_eq!(rule("load1>cores").threshold, Threshold::Cores);
+        assert_eq!(rule("memory_pressure = critical").threshold, Threshold::Pressure(MemoryPressure::Critical));
+        assert_eq!(rule("memory > 90%").reading(&readings()).unwrap().1, (16u64 << 30) as f64 * 0.9);
+        assert!(AlertRule::try_from("load1 > critical".to_string()).is_err());
+        assert!(AlertRule::try_from("memory_pressure > 3".to_string()).is_err());
+        assert!(AlertRule::try_from("disk > 1".to_string()).is_err());
+        assert!(AlertRule::try_from("load1 9".to_string()).is_err());
+    }
+
+    #[test]
+    fn tripped_alert_only_clears_past_the_margin() {
+        let load = rule("load1 > cores");
+        let limit = 8.0;
+        assert!(!load.tripped(false, 8.0, limit));
+        assert!(load.tripped(false, 8.1, limit));
+        // Hovering just under the threshold doesn't clear it...
+        assert!(load.tripped(true, 7.9, limit));
+        assert!(load.tripped(true, 7.7, limit));
+        // ...coming back 5% does.
+        assert!(!load.tripped(true, 7.5, limit));
+
+        let pressure = rule("memory_pressure > normal");
+        let level = |p: MemoryPressure| p as u8 as f64;
+        assert!(pressure.tripped(false, level(MemoryPressure::Warning), level(MemoryPressure::Normal)));
+        assert!(!pressure.tripped(true, level(MemoryPressure::Normal), level(MemoryPressure::Normal)));
+    }
+}
@@ -0,0 +1,130 @@
+// Stopping and continuing every process of an app at once: a browser with its dozens of helpers,
+// say, frozen together so none of them keeps the others busy.
+use ratatui::style::Modifier;
+use ratatui::text::Span;
+
+use crate::platform::{process_identity, ProcessIdentity};
+use crate::process_list::ProcessInfo;
+use crate::{kill_process, App};
+
+// PIDs listed with their error before the message switches to "and N more".
+const FAILURES_LISTED: usize = 5;
+
+// The app an executable belongs to: "/Applications/Google Chrome.app/Contents/Frameworks/…/
+// Google Chrome Helper.app/Contents/MacOS/Google Chrome Helper" -> "/Applications/Google Chrome.app".
+// Unlike `reveal::app_bundle`, helpers nested in another bundle count as the outer app's.
+pub fn app_of(exe: &str) -> Option<&str> {
+    let end = exe.find(".app/")? + ".app".len();
+    Some(&exe[..end])
+}
+
+// "/Applications/Google Chrome.app" -> "Google Chrome".
+pub fn app_name(bundle: &str) -> &str {
+    let name = bundle.rsplit('/').next().unwrap_or(bundle);
+    name.strip_suffix(".app").unwrap_or(name)
+}
+
+impl App {
+    // The processes of `bundle` as of the last refresh, leaving out this one (suspending
+    // ourselves would leave nothing to resume with) and protected ones, which are counted.
+    fn app_members(&self, bundle: &str) -> (Vec<u32>, usize) {
+        let own_pid = std::process::id();
+        let mut members = Vec::new();
+        let mut protected = 0;
+        for (&pid, p) in &self.processes {
+            if pid == own_pid || p.status == "Z" || app_of(&p.exe) != Some(bundle) { continue; }
+            if self.is_protected(pid) { protected += 1; } else { members.push(pid); }
+        }
+        members.sort_unstable();
+        (members, protected)
+    }
+
+    // Action menu "Stop all of …" / "Continue all of …", for the app `pid` belongs to.
+    pub fn signal_app(&mut self, pid: u32, stop: bool) {
+        if self.read_only {
+            self.message = Some("read-only mode — actions disabled".to_string());
+            return;
+        }
+        let Some(bundle) = self.processes.get(&pid).and_then(|p| app_of(&p.exe)).map(str::to_string) else { return };
+        let (members, protected) = self.app_members(&bundle);
+        // Taken before anything is sent, and held against the name the table shows: a PID that
+        // went to another process since the last refresh is left alone.
+        let targets: Vec<(u32, Option<ProcessIdentity>)> = members.iter().map(|&member| {
+            let identity = process_identity(member).filter(|id| self.processes[&member].name.starts_with(&id.name));
+            (member, identity)
+        }).collect();
+        let signal = if stop { libc::SIGSTOP } else { libc::SIGCONT };
+        let mut failed = Vec::new();
+        let mut changed = 0;
+        for (member, identity) in &targets {
+            // Checked again right before sending, as the bulk kill does.
+            if identity.is_none() || process_identity(*member) != *identity {
+                changed += 1;
+            } else if let Err(e) = kill_process(*member, signal) {
+                // kill(1) says "kill: 123: Operation not permitted"; the reason is the last part.
+                failed.push(format!("{} ({})", member, e.trim().rsplit(": ").next().unwrap_or_default()));
+            }
+        }
+        if stop { self.stopped_apps.insert(bundle.clone()); } else { self.stopped_apps.remove(&bundle); }
+        let verb = if stop { "Stopped" } else { "Continued" };
+        let sent = members.len() - failed.len() - changed;
+        let mut summary = format!("{} {} of {} processes of {}", verb, sent, members.len(), app_name(&bundle));
+        if !failed.is_empty() {
+            let more = failed.len().saturating_sub(FAILURES_LISTED);
+            failed.truncate(FAILURES_LISTED);
+            summary.push_str(&format!("; failed: {}{}", failed.join(", "), if more > 0 { format!(" and {} more", more) } else { String::new() }));
+        }
+        if changed > 0 {
+            summary.push_str(&format!("; {} exited or changed", changed));
+        }
+        if protected > 0 {
+            summary.push_str(&format!("; skipped {} protected", protected));
+        }
+        self.message = Some(summary);
+    }
+
+    // Ahead of the command: "↑" for a possible leak, or "‖" for a process stopped along with the
+    // rest of its app.
+    pub fn command_marker(&self, p: &ProcessInfo) -> Span<'static> {
+        if p.leaking {
+            Span::styled("↑ ", self.theme.warning.add_modifier(Modifier::BOLD))
+        } else if p.status == "T" && app_of(&p.exe).is_some_and(|bundle| self.stopped_apps.contains(bundle)) {
+            Span::styled("‖ ", self.theme.dim.add_modifier(Modifier::BOLD))
+        } else {
+            Span::raw("")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn an_app_is_everything_under_its_outermost_bundle() {
+        let chrome = "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome";
+        let helper = "/Applications/Google Chrome.app/Contents/Frameworks/Google Chrome Framework.framework/Helpers/Google Chrome Helper.app/Contents/MacOS/Google Chrome Helper";
+        assert_eq!(app_of(helper), Some("/Applications/Google Chrome.app"));
+        assert_eq!(app_of("/usr/sbin/cfprefsd"), None);
+        assert_eq!(app_name("/Applications/Google Chrome.app"), "Google Chrome");
+
+        let mut config = Config::default();
+        config.protected_pids.push(40);
+        let own_pid = std::process::id();
+        let mut app = App::with_processes(&config, &[(10, 0, 0.0, ""), (20, 0, 0.0, ""), (30, 0, 0.0, ""), (40, 0, 0.0, ""), (50, 0, 0.0, ""), (own_pid, 0, 0.0, "")]);
+        for (pid, exe, status) in [(10, chrome, "S"), (20, helper, "S"), (30, "/usr/bin/python3", "S"), (40, helper, "S"), (50, helper, "Z"), (own_pid, helper, "R")] {
+            let p = app.processes.get_mut(&pid).unwrap();
+            (p.exe, p.status) = (exe.to_string(), status.to_string());
+        }
+        assert_eq!(app.app_members("/Applications/Google Chrome.app"), (vec![10, 20], 1));
+
+        // Made-up processes: whatever has those PIDs now isn't what the table shows.
+        app.signal_app(10, true);
+        assert_eq!(app.message.as_deref(), Some("Stopped 0 of 2 processes of Google Chrome; 2 exited or changed; skipped 1 protected"));
+        app.read_only = true;
+        app.signal_app(10, false);
+        assert_eq!(app.message.as_deref(), Some("read-only mode — actions disabled"));
+        assert!(app.stopped_apps.contains("/Applications/Google Chrome.app"));
+    }
+}
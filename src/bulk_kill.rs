@@ -0,0 +1,171 @@
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::platform::{process_identity, ProcessIdentity};
+use crate::{centered_rect, clear_popup, kill_process, App, InputMode};
+
+// Commands listed in the confirmation before it switches to "… and N more".
+const SAMPLE_SIZE: usize = 5;
+
+// A signal about to go to every process the filters leave in the list. The PIDs are captured when the
+// signal is chosen, so processes that start matching while the dialog is open are left alone.
+pub struct BulkKill {
+    signal: i32,
+    filter: String, // The filters as the footer shows them, for the dialog
+    pids: Vec<(u32, ProcessIdentity)>, // Checked again right before sending, in case a PID was reused
+    sample: Vec<(u32, String)>, // (PID, command) of the first few, for the dialog
+    protected_skipped: usize,
+    confirmed_once: bool, // Over the limit: the first 'y' only unlocks the second confirmation
+}
+
+impl App {
+    // 'K': pick a signal for everything matching the filters. Without one that would be every
+    // process on the machine, so it is refused outright.
+    pub fn open_bulk_kill_menu(&mut self) {
+        if self.active_filter.as_deref().is_none_or(|f| f.trim().is_empty()) && self.field_filter.is_none() {
+            self.message = Some("Kill all matching needs a filter ('/' or 'F') first".to_string());
+            return;
+        }
+        self.open_kill_menu();
+        self.kill_menu_bulk = self.input_mode == InputMode::KillMenu;
+    }
+
+    pub fn request_bulk_kill(&mut self, signal: i32) {
+        self.input_mode = InputMode::Normal;
+        let filter = match (&self.active_filter, &self.field_filter) {
+            (Some(text), Some(fields)) => format!("'{}' and {}", text, fields.summary()),
+            (Some(text), None) => format!("'{}'", text),
+            (None, Some(fields)) => fields.summary(),
+            (None, None) => return,
+        };
+        let own_pid = std::process::id();
+        let mut pids = Vec::new();
+        let mut protected_skipped = 0;
+        // Only what the list shows: --pid and "leaking only" narrow it as much as the filters do.
+        for pid in self.shown_matches() {
+            if pid == own_pid { continue; }
+            if self.is_protected(pid) {
+                protected_skipped += 1;
+            } else if let Some(identity) = process_identity(pid) {
+                pids.push((pid, identity));
+            }
+        }
+        if pids.is_empty() {
+            self.message = Some(format!("No processes to signal match {}", filter));
+            return;
+        }
+        let sample = pids.iter().take(SAMPLE_SIZE).map(|(pid, _)| (*pid, self.processes[pid].command.clone())).collect();
+        self.pending_bulk = Some(BulkKill { signal, filter, pids, sample, protected_skipped, confirmed_once: false });
+        self.input_mode = InputMode::ConfirmBulkKill;
+    }
+
+    pub fn confirm_bulk_kill(&mut self, confirmed: bool) {
+        self.input_mode = InputMode::Normal;
+        let Some(mut bulk) = self.pending_bulk.take() else { return };
+        if !confirmed {
+            self.message = Some("Cancelled".to_string());
+            return;
+        }
+        if bulk.pids.len() > self.config.bulk_kill_max && !bulk.confirmed_once {
+            bulk.confirmed_once = true;
+            self.pending_bulk = Some(bulk);
+            self.input_mode = InputMode::ConfirmBulkKill;
+            return;
+        }
+        let mut failed = Vec::new();
+        let mut changed = 0;
+        for (pid, identity) in &bulk.pids {
+            if process_identity(*pid).as_ref() != Some(identity) {
+                changed += 1;
+            } else if kill_process(*pid, bulk.signal).is_err() {
+                failed.push(*pid);
+            }
+        }
+        let sent = bulk.pids.len() - failed.len() - changed;
+        let mut summary = format!("Sent {} to {} of {} processes", self.signal_label(bulk.signal), sent, bulk.pids.len());
+        if !failed.is_empty() {
+            let listed: Vec<String> = failed.iter().take(SAMPLE_SIZE).map(u32::to_string).collect();
+            summary.push_str(&format!("; failed: {}{}", listed.join(", "), if failed.len() > SAMPLE_SIZE { ", …" } else { "" }));
+        }
+        if changed > 0 {
+            summary.push_str(&format!("; {} exited or changed", changed));
+        }
+        if bulk.protected_skipped > 0 {
+            summary.push_str(&format!("; skipped {} protected", bulk.protected_skipped));
+        }
+        self.message = Some(summary);
+    }
+}
+
+pub fn draw_bulk_confirm(f: &mut Frame, app: &App, area: Rect) {
+    let Some(bulk) = &app.pending_bulk else { return };
+    let dim = app.theme.dim;
+    let mut text = vec![
+        Line::from(format!("Send {} to {} processes matching {}:", app.signal_label(bulk.signal), bulk.pids.len(), bulk.filter)),
+        Line::from(""),
+    ];
+    for (pid, command) in &bulk.sample {
+        text.push(Line::from(format!("  {:>7}  {}", pid, command)));
+    }
+    if bulk.pids.len() > bulk.sample.len() {
+        text.push(Line::from(Span::styled(format!("  … and {} more", bulk.pids.len() - bulk.sample.len()), dim)));
+    }
+    if bulk.protected_skipped > 0 {
+        text.push(Line::from(Span::styled(format!("  ({} protected processes will be skipped)", bulk.protected_skipped), dim)));
+    }
+    text.push(Line::from(""));
+    if bulk.confirmed_once {
+        text.push(Line::from(Span::styled(
+            format!("That is more than the bulk limit of {}. Are you REALLY sure?", app.config.bulk_kill_max),
+            app.theme.warning)));
+    }
+    text.push(Line::from(Span::styled("y = send it, any other key = cancel", dim)));
+
+    let area = centered_rect(60, 40, area);
+    let dialog = Paragraph::new(text).wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL).title("Kill all matching").border_style(app.theme.warning));
+    clear_popup(f, app, area);
+    f.render_widget(dialog, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::filter_builder::Filter;
+
+    #[test]
+    fn only_processes_the_list_shows_are_targets() {
+        let list = [(1, 0, 0.0, "launchd"), (10, 1, 0.0, "zsh"), (11, 10, 0.0, "python3 a.py"), (12, 10, 0.0, "python3 b.py"), (20, 1, 0.0, "python3 c.py"), (21, 1, 0.0, "python3 d.py")];
+        let mut app = App::with_processes(&Config { sort_by: crate::SortBy::Pid, ..Config::default() }, &list);
+        for (pid, user, leaking) in [(11, "bob", true), (12, "bob", false), (20, "bob", true), (21, "alice", true)] {
+            let p = app.processes.get_mut(&pid).unwrap();
+            (p.user, p.leaking) = (user.to_string(), leaking);
+        }
+        // The filter builder alone is a filter too.
+        app.set_field_filter(Some(Filter { user: "bob".to_string(), ..Default::default() }));
+        app.open_bulk_kill_menu();
+        assert!(app.kill_menu_bulk);
+        assert_eq!(app.shown_matches(), [11, 12, 20]);
+        app.set_filter(Some("python".to_string()));
+        assert_eq!(app.shown_matches(), [11, 12, 20]);
+        // In the tree, the ancestors that place a match aren't targets.
+        app.tree_view = true;
+        assert_eq!(app.shown_matches(), [11, 12, 20]);
+        // --pid and "leaking only" leave out what they hide.
+        app.follow_pids.insert(10);
+        assert_eq!(app.shown_matches(), [11, 12]);
+        app.leaking_only = true;
+        assert_eq!(app.shown_matches(), [11]);
+    }
+
+    #[test]
+    fn kill_all_matching_needs_a_filter() {
+        let mut app = App::with_processes(&Config::default(), &[(1, 0, 0.0, "launchd")]);
+        app.open_bulk_kill_menu();
+        assert!(!app.kill_menu_bulk);
+        assert_eq!(app.message.as_deref(), Some("Kill all matching needs a filter ('/' or 'F') first"));
+    }
+}
@@ -0,0 +1,75 @@
+// Command-line flags. Kept deliberately small: anything long-lived belongs in the config file.
+use crate::theme::ColorDepth;
+
+pub struct Args {
+    pub read_only: bool, // Disable every action that could signal or modify a process
+    pub accessible: bool, // Plain output for screen readers
+    pub inline: bool, // Draw below the prompt instead of on the alternate screen
+    pub mouse: Option<bool>, // Overrides the default: on, except with --no-altscreen
+    pub set_title: Option<bool>, // Overrides the config's `set_title`
+    pub record: Option<String>, // Append every sample to this file
+    pub replay: Option<String>, // Show samples from this file instead of the live system
+    pub colors: Option<ColorDepth>, // Overrides what is detected from TERM/COLORTERM
+    pub pids: Vec<u32>, // Show only these processes and their children
+}
+
+pub const USAGE: &str = "\
+Usage: htop-macos [OPTIONS]
+
+Options:
+      --read-only      Disable killing and other process actions (for demos and shared screens)
+      --accessible     Plain text for screen readers: no gauges or lines, '->' marks the selection
+      --no-altscreen   Draw in the scrollback below the prompt (inline_rows tall, see the
+                       config) and leave the last screen there on exit; no mouse
+      --mouse          Use the mouse even with --no-altscreen
+      --set-title      Show CPU, memory and load in the terminal title (the default)
+      --no-title       Leave the terminal title alone
+      --record <FILE>  Save every refresh to FILE for later --replay; given a directory,
+                       a snapshot every few refreshes (see snapshot_every in the config)
+      --replay <FILE>  Step through a recording (',' and '.') instead of sampling; given a
+                       directory, its snapshots
+      --colors <N>     Colors the terminal supports: 8, 16 or 256 (default: detected)
+      --pid <PID>      Show only PID and its children; repeat for more processes
+  -h, --help           Print this help
+  -V, --version        Print version";
+
+pub enum ParseOutcome {
+    Run(Args),
+    Exit(String), // Informational output (help, version); print and exit successfully
+}
+
+impl Args {
+    pub fn parse<I: Iterator<Item = String>>(args: I) -> Result<ParseOutcome, String> {
+        let mut parsed = Args { read_only: false, accessible: false, inline: false, mouse: None, set_title: None, record: None, replay: None, colors: None, pids: Vec::new() };
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--read-only" => parsed.read_only = true,
+                "--accessible" => parsed.accessible = true,
+                "--no-altscreen" => parsed.inline = true,
+                "--mouse" => parsed.mouse = Some(true),
+                "--set-title" => parsed.set_title = Some(true),
+                "--no-title" => parsed.set_title = Some(false),
+                "--record" | "--replay" => {
+                    let file = args.next().ok_or_else(|| format!("{} needs a file name", arg))?;
+                    if arg == "--record" { parsed.record = Some(file) } else { parsed.replay = Some(file) }
+                }
+                "--colors" => {
+                    let value = args.next().ok_or("--colors needs 8, 16 or 256")?;
+                    parsed.colors = Some(ColorDepth::parse(&value).ok_or_else(|| format!("--colors: expected 8, 16 or 256, got '{}'", value))?);
+                }
+                "--pid" => {
+                    let value = args.next().ok_or("--pid needs a process ID")?;
+                    parsed.pids.push(value.parse().map_err(|_| format!("--pid: expected a process ID, got '{}'", value))?);
+                }
+                "-h" | "--help" => return Ok(ParseOutcome::Exit(USAGE.to_string())),
+                "-V" | "--version" => return Ok(ParseOutcome::Exit(format!("htop-macos {}", env!("CARGO_PKG_VERSION")))),
+                other => return Err(format!("unrecognized argument '{}'\n\n{}", other, USAGE)),
+            }
+        }
+        if parsed.record.is_some() && parsed.replay.is_some() {
+            return Err("--record and --replay can't be used together".to_string());
+        }
+        Ok(ParseOutcome::Run(parsed))
+    }
+}
@@ -0,0 +1,101 @@
+// Collapsing subtrees in tree view: a collapsed process keeps its row, marked with '+', and
+// its descendants are left out of the tree until it is expanded again.
+use std::collections::HashSet;
+
+use crate::keymap::Action;
+use crate::App;
+
+impl App {
+    // '+'/'-': collapses or expands the selected process's subtree.
+    pub fn toggle_collapse(&mut self) {
+        if !self.tree_only() { return; }
+        let Some(pid) = self.selected_pid() else { return };
+        if !self.collapsed.remove(&pid) {
+            if !self.processes.values().any(|p| p.ppid == pid && p.pid != pid) { return; }
+            self.collapsed.insert(pid);
+        }
+        self.rebuild_view();
+    }
+
+    // '*': leaves only the top-level trees, each folded up into its root.
+    pub fn collapse_all(&mut self) {
+        if !self.tree_only() { return; }
+        self.collapsed = self.processes.values().filter(|p| p.ppid != p.pid && self.processes.contains_key(&p.ppid)).map(|p| p.ppid).collect();
+        self.rebuild_view();
+    }
+
+    // '=': the whole tree again.
+    pub fn expand_all(&mut self) {
+        if !self.tree_only() { return; }
+        self.collapsed.clear();
+        self.rebuild_view();
+    }
+
+    fn tree_only(&mut self) -> bool {
+        if !self.tree_view {
+            self.message = Some(match self.keymap.label(Action::ToggleTree) {
+                Some(key) => format!("Subtrees only fold in tree view ({})", key),
+                None => "Subtrees only fold in tree view".to_string(),
+            });
+        }
+        self.tree_view
+    }
+
+    // Unfolds whatever hides `pid`, so it can be selected.
+    pub fn expand_ancestors(&mut self, pid: u32) {
+        let mut changed = false;
+        for ancestor in self.ancestors(pid) { changed |= self.collapsed.remove(&ancestor); }
+        if changed { self.rebuild_view(); }
+    }
+
+    // Selects the closest ancestor of `pid` that has a row, when a subtree folded up over it.
+    pub fn select_visible_ancestor(&mut self, pid: u32) -> bool {
+        self.ancestors(pid).into_iter().any(|ancestor| self.select_pid(ancestor))
+    }
+
+    // Parent first. Stops at a root, at a parent we can't see, or where parent links loop.
+    fn ancestors(&self, pid: u32) -> Vec<u32> {
+        let mut seen = HashSet::from([pid]);
+        let mut ancestors = Vec::new();
+        let mut pid = pid;
+        while let Some(p) = self.processes.get(&pid) {
+            if !seen.insert(p.ppid) || !self.processes.contains_key(&p.ppid) { break; }
+            pid = p.ppid;
+            ancestors.push(pid);
+        }
+        ancestors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn pids(app: &App) -> Vec<u32> {
+        app.view.iter().map(|&(_, pid)| pid).collect()
+    }
+
+    #[test]
+    fn collapse_all_leaves_the_roots_and_keeps_a_row_selected() {
+        let list = [(1, 0, 0.0, "p1"), (10, 1, 5.0, "p10"), (11, 10, 0.0, "p11"), (12, 11, 0.0, "p12"), (20, 1, 1.0, "p20")];
+        let mut app = App::with_processes(&Config { tree_view: true, ..Config::default() }, &list);
+        app.select_pid(12);
+
+        app.collapse_all();
+        assert_eq!(pids(&app), [1]);
+        assert_eq!(app.selected_pid(), Some(1));
+        app.expand_all();
+        assert_eq!(pids(&app), [1, 10, 11, 12, 20]);
+
+        app.select_pid(10);
+        app.toggle_collapse();
+        assert_eq!(pids(&app), [1, 10, 20]);
+        app.expand_ancestors(12);
+        assert_eq!(pids(&app), [1, 10, 11, 12, 20]);
+        // A leaf has nothing to fold.
+        app.select_pid(20);
+        app.toggle_collapse();
+        assert!(app.collapsed.is_empty());
+    }
+}
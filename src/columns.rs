@@ -0,0 +1,511 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::ops::Range;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::ColumnWidth;
+use crate::format::{format_bytes, write_start, write_time, write_time_compact};
+use crate::process_list::{ProcessInfo, SortBy, SortOrder};
+
+// Number of refreshes a column must want to be narrower before it actually shrinks.
+// Growing is immediate so values are never clipped; shrinking lazily avoids jitter.
+const SHRINK_AFTER: u8 = 3;
+
+// COMMAND keeps at least this many cells before any of `auto_columns` is added.
+const COMMAND_ROOM: u16 = 80;
+// Cells a table row takes besides its columns: the two borders and the selection gutter.
+const TABLE_CHROME: u16 = 5;
+
+// What the time column shows. Cycled with 'e'.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeDisplay {
+    #[default]
+    CpuTime, // TIME+: cumulative CPU time, htop format
+    CpuTimeCompact, // TIME: cumulative CPU time as "2.5h", "3d"
+    Elapsed, // ELAPSED: wall-clock time since the process started, compact
+}
+
+impl TimeDisplay {
+    pub fn next(self) -> Self {
+        match self {
+            TimeDisplay::CpuTime => TimeDisplay::CpuTimeCompact,
+            TimeDisplay::CpuTimeCompact => TimeDisplay::Elapsed,
+            TimeDisplay::Elapsed => TimeDisplay::CpuTime,
+        }
+    }
+}
+
+// Display options that change how cells are rendered (as opposed to which columns are shown).
+#[derive(Clone, Copy, Default)]
+pub struct CellFormat {
+    pub time: TimeDisplay,
+    pub percent_decimals: usize, // Places after the point in CPU%, MEM% and AVG%: 0 to MAX_PERCENT_DECIMALS
+    pub path_column: bool, // PATH is shown, so COMMAND leaves out the program (when the path is known)
+}
+
+pub const MAX_PERCENT_DECIMALS: usize = 2;
+
+// Serialized by `key`, as listed in the config's `columns`.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Column {
+    Pid,
+    User,
+    Virt,
+    State,
+    Cpu,
+    Mem,
+    #[serde(rename = "avg")]
+    CpuAvg,
+    Time,
+    Command,
+    Footprint, // Off by default: costs a syscall per process
+    Ports, // Off by default, likewise
+    MemDelta, // RSS growth over the last minute
+    Path, // Executable path
+    Pgrp, // Process group; off by default, costs a syscall per process
+    Sid, // Session, likewise
+    Label, // What an interpreter runs, or which app a helper belongs to
+    DiskRead, // Bytes read from disk per second, between the last two refreshes
+    DiskWrite, // Likewise, written
+    Ppid, // Parent PID
+    Res, // Resident memory, the RSS behind MEM%
+    Threads,
+    Started, // Wall-clock time the process started
+}
+
+impl Column {
+    // Shown unless the config lists its own.
+    pub const DEFAULT: [Column; 9] = [
+        Column::Pid, Column::User, Column::Virt, Column::State, Column::Cpu,
+        Column::Mem, Column::CpuAvg, Column::Time, Column::Command,
+    ];
+
+    pub fn header(self, fmt: CellFormat) -> &'static str {
+        match self {
+            Column::Time => match fmt.time {
+                TimeDisplay::CpuTime => "TIME+",
+                TimeDisplay::CpuTimeCompact => "TIME",
+                TimeDisplay::Elapsed => "ELAPSED",
+            },
+            Column::Pid => "PID",
+            Column::User => "USER",
+            Column::Virt => "VIRT",
+            Column::State => "S",
+            Column::Cpu => "CPU%",
+            Column::Mem => "MEM%",
+            Column::CpuAvg => "AVG%",
+            Column::Command => "COMMAND",
+            Column::Footprint => "FOOTPRINT",
+            Column::Ports => "PORTS",
+            Column::MemDelta => "ΔMEM",
+            Column::Path => "PATH",
+            Column::Pgrp => "PGRP",
+            Column::Sid => "SID",
+            Column::Label => "LABEL",
+            Column::DiskRead => "DR/s",
+            Column::DiskWrite => "DW/s",
+            Column::Ppid => "PPID",
+            Column::Res => "RES",
+            Column::Threads => "THR",
+            Column::Started => "START",
+        }
+    }
+
+    // What sorting by this column sorts on, if it can be sorted by.
+    pub fn sort_key(self) -> Option<SortBy> {
+        match self {
+            Column::Pid => Some(SortBy::Pid),
+            Column::User => Some(SortBy::User),
+            Column::Cpu => Some(SortBy::Cpu),
+            Column::Mem => Some(SortBy::Mem),
+            Column::CpuAvg => Some(SortBy::CpuAvg),
+            Column::Time => Some(SortBy::Time),
+            Column::Command => Some(SortBy::Command),
+            Column::Ports => Some(SortBy::Ports),
+            Column::MemDelta => Some(SortBy::MemDelta),
+            Column::Pgrp => Some(SortBy::Pgrp),
+            Column::Sid => Some(SortBy::Sid),
+            Column::DiskRead => Some(SortBy::DiskRead),
+            Column::DiskWrite => Some(SortBy::DiskWrite),
+            Column::Virt | Column::State | Column::Footprint | Column::Path | Column::Label => None,
+            Column::Ppid | Column::Res | Column::Threads | Column::Started => None,
+        }
+    }
+
+    // Header text, with an arrow on the column the table is sorted by.
+    pub fn header_with_arrow(self, fmt: CellFormat, sort_by: SortBy, order: SortOrder) -> Cow<'static, str> {
+        match (self.sort_key() == Some(sort_by), order) {
+            (false, _) => Cow::Borrowed(self.header(fmt)),
+            (true, SortOrder::Asc) => Cow::Owned(format!("{}▲", self.header(fmt))),
+            (true, SortOrder::Desc) => Cow::Owned(format!("{}▼", self.header(fmt))),
+        }
+    }
+
+    // Room the header needs, including the sort arrow of sortable columns, so the column
+    // doesn't change width when the sort moves to it.
+    fn header_width(self, fmt: CellFormat) -> usize {
+        self.header(fmt).chars().count() + self.sort_key().is_some() as usize
+    }
+
+    // Name used for this column in the config file.
+    pub fn key(self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::User => "user",
+            Column::Virt => "virt",
+            Column::State => "state",
+            Column::Cpu => "cpu",
+            Column::Mem => "mem",
+            Column::CpuAvg => "avg",
+            Column::Time => "time",
+            Column::Command => "command",
+            Column::Footprint => "footprint",
+            Column::Ports => "ports",
+            Column::MemDelta => "mem_delta",
+            Column::Path => "path",
+            Column::Pgrp => "pgrp",
+            Column::Sid => "sid",
+            Column::Label => "label",
+            Column::DiskRead => "disk_read",
+            Column::DiskWrite => "disk_write",
+            Column::Ppid => "ppid",
+            Column::Res => "res",
+            Column::Threads => "threads",
+            Column::Started => "started",
+        }
+    }
+
+    // Text shown in this column for `p`. String fields are borrowed; only numbers are formatted.
+    pub fn cell_text(self, p: &ProcessInfo, fmt: CellFormat) -> Cow<'_, str> {
+        match self {
+            Column::User => Cow::Borrowed(&p.user),
+            Column::State => Cow::Borrowed(&p.status),
+            Column::Command => Cow::Borrowed(command_text(p, fmt)),
+            Column::Path => path_text(p),
+            Column::Label => Cow::Borrowed(p.label.as_deref().unwrap_or("")),
+            _ => {
+                let mut text = String::new();
+                self.write_value(p, fmt, &mut text);
+                Cow::Owned(text)
+            }
+        }
+    }
+
+    // Display width of the cell text, formatting numbers into `scratch` rather than allocating.
+    fn content_width(self, p: &ProcessInfo, fmt: CellFormat, scratch: &mut String) -> usize {
+        match self {
+            Column::User => p.user.chars().count(),
+            Column::State => p.status.chars().count(),
+            Column::Command => command_text(p, fmt).chars().count(),
+            Column::Path => {
+                let (prefix, rest) = path_parts(p);
+                prefix.len() + rest.chars().count()
+            }
+            Column::Label => p.label.as_deref().map_or(0, |l| l.chars().count()),
+            _ => {
+                scratch.clear();
+                self.write_value(p, fmt, scratch);
+                scratch.len()
+            }
+        }
+    }
+
+    fn write_value(self, p: &ProcessInfo, fmt: CellFormat, out: &mut String) {
+        let _ = match self {
+            Column::Pid => write!(out, "{}", p.pid),
+            Column::Virt => write!(out, "{}M", p.virtual_mem / 1024 / 1024),
+            Column::Cpu => write!(out, "{:.*}", fmt.percent_decimals, p.cpu),
+            Column::Mem => write!(out, "{:.*}", fmt.percent_decimals, p.mem),
+            Column::CpuAvg => match p.cpu_avg {
+                Some(avg) => write!(out, "{:.*}", fmt.percent_decimals, avg),
+                None => write!(out, "-"),
+            },
+            Column::Time => match (fmt.time, p.cpu_time) {
+                (TimeDisplay::Elapsed, _) => write_time_compact(out, std::time::Duration::from_secs(p.run_time)),
+                (_, None) => write!(out, "-"),
+                (TimeDisplay::CpuTime, Some(t)) => write_time(out, t),
+                (TimeDisplay::CpuTimeCompact, Some(t)) => write_time_compact(out, t),
+            },
+            Column::Footprint => match p.footprint {
+                Some(bytes) => write!(out, "{}M", bytes / 1024 / 1024),
+                None => write!(out, "-"),
+            },
+            Column::Ports => match p.ports {
+                Some(ports) => write!(out, "{}", ports),
+                None => write!(out, "-"),
+            },
+            Column::Pgrp => match p.pgid {
+                Some(pgid) => write!(out, "{}", pgid),
+                None => write!(out, "-"),
+            },
+            Column::Sid => match p.sid {
+                Some(sid) => write!(out, "{}", sid),
+                None => write!(out, "-"),
+            },
+            // Blank until the process has been watched for the whole window.
+            Column::MemDelta => match p.mem_delta {
+                Some(delta) => write!(out, "{:+}M", delta / 1024 / 1024),
+                None => Ok(()),
+            },
+            // Blank for the first refresh a process is seen in.
+            Column::DiskRead => match p.read_rate {
+                Some(rate) => out.write_str(&format_bytes(rate)),
+                None => Ok(()),
+            },
+            Column::DiskWrite => match p.write_rate {
+                Some(rate) => out.write_str(&format_bytes(rate)),
+                None => Ok(()),
+            },
+            Column::Ppid => write!(out, "{}", p.ppid),
+            Column::Res => write!(out, "{}M", p.resident / 1024 / 1024),
+            Column::Threads => match p.threads {
+                Some(threads) => write!(out, "{}", threads),
+                None => write!(out, "-"),
+            },
+            Column::Started => {
+                let now = SystemTime::now();
+                write_start(out, now - Duration::from_secs(p.run_time), now)
+            }
+            Column::User | Column::State | Column::Command | Column::Path | Column::Label => Ok(()),
+        };
+    }
+
+    // Typical width, with the space after it. Deciding what fits on these rather than the fitted
+    // widths keeps columns from coming and going as the data changes.
+    fn nominal_width(self) -> u16 {
+        match self {
+            Column::Command => 0, // Gets `COMMAND_ROOM`
+            Column::State => 2,
+            Column::Threads => 5,
+            Column::Cpu | Column::Mem | Column::CpuAvg | Column::Ports | Column::Started => 6,
+            Column::Virt | Column::Res | Column::MemDelta | Column::DiskRead | Column::DiskWrite => 7,
+            Column::Pid | Column::Ppid | Column::Pgrp | Column::Sid => 8,
+            Column::User | Column::Time | Column::Footprint => 10,
+            Column::Label => 31,
+            Column::Path => 41,
+        }
+    }
+
+    fn default_width(self) -> ColumnWidth {
+        match self {
+            Column::User => ColumnWidth { min: None, max: Some(16), width: None },
+            Column::Command => ColumnWidth { min: Some(20), max: None, width: None },
+            Column::Path => ColumnWidth { min: None, max: Some(40), width: None },
+            Column::Label => ColumnWidth { min: None, max: Some(30), width: None },
+            _ => ColumnWidth::default(),
+        }
+    }
+}
+
+// With a PATH column the program is already on screen; COMMAND keeps just the arguments.
+fn command_text(p: &ProcessInfo, fmt: CellFormat) -> &str {
+    if fmt.path_column && !p.exe.is_empty() { &p.command[p.args_start..] } else { &p.command }
+}
+
+// Where the program's file name is in the COMMAND text: "node" in "/opt/homebrew/bin/node
+// server.js". None when the program isn't part of the text.
+pub fn base_name_range(p: &ProcessInfo, fmt: CellFormat) -> Option<Range<usize>> {
+    if fmt.path_column && !p.exe.is_empty() { return None; }
+    let program = p.command[..p.args_start].trim_end();
+    let start = program.rfind('/').map_or(0, |i| i + 1);
+    Some(start..program.len()).filter(|range| !range.is_empty())
+}
+
+// The executable path, with the home directory shown as "~": (prefix, rest of the path).
+fn path_parts(p: &ProcessInfo) -> (&'static str, &str) {
+    static HOME: OnceLock<Option<String>> = OnceLock::new();
+    let home = HOME.get_or_init(|| std::env::var("HOME").ok());
+    if p.exe.is_empty() { return ("", "-"); }
+    abbreviate_home(&p.exe, home.as_deref())
+}
+
+fn path_text(p: &ProcessInfo) -> Cow<'_, str> {
+    match path_parts(p) {
+        ("", path) => Cow::Borrowed(path),
+        (prefix, rest) => Cow::Owned(format!("{}{}", prefix, rest)),
+    }
+}
+
+// "/Users/me/bin/tool" -> ("~", "/bin/tool"). Only whole path components count: "/Users/meg" stays.
+fn abbreviate_home<'a>(path: &'a str, home: Option<&str>) -> (&'static str, &'a str) {
+    let home = home.map(|h| h.trim_end_matches('/')).filter(|h| !h.is_empty());
+    match home.and_then(|home| path.strip_prefix(home)) {
+        Some(rest) if rest.starts_with('/') => ("~", rest),
+        _ => ("", path),
+    }
+}
+
+// Which of `auto` to show next to the configured columns (`shown`) in a terminal `width` wide:
+// as many from the front as leave COMMAND its room, so narrowing drops them from the back.
+// Columns the user turned off never come back: those in `hidden`, and default columns that
+// `shown` leaves out.
+pub fn revealed(width: u16, shown: &[Column], auto: &[Column], hidden: &[Column]) -> Vec<Column> {
+    let used = TABLE_CHROME + shown.iter().map(|c| c.nominal_width()).sum::<u16>();
+    let mut room = width.saturating_sub(used).saturating_sub(COMMAND_ROOM);
+    let mut revealed = Vec::new();
+    for &column in auto {
+        if shown.contains(&column) || revealed.contains(&column) || hidden.contains(&column) || Column::DEFAULT.contains(&column) { continue; }
+        let Some(left) = room.checked_sub(column.nominal_width()) else { break };
+        room = left;
+        revealed.push(column);
+    }
+    revealed
+}
+
+// A displayed column and its current width.
+pub struct ColumnState {
+    pub column: Column,
+    limits: ColumnWidth,
+    pub width: u16,
+    shrink_streak: u8,
+    pub auto: bool, // Added for a wide terminal (`auto_columns`), not configured
+}
+
+impl ColumnState {
+    pub fn new(column: Column, configured: &BTreeMap<String, ColumnWidth>) -> Self {
+        let limits = configured.get(column.key()).cloned().unwrap_or_else(|| column.default_width());
+        let width = limits.width.unwrap_or(column.header_width(CellFormat::default()) as u16);
+        Self { column, limits, width, shrink_streak: 0, auto: false }
+    }
+
+    pub fn auto(column: Column, configured: &BTreeMap<String, ColumnWidth>) -> Self {
+        Self { auto: true, ..Self::new(column, configured) }
+    }
+
+    // COMMAND soaks up whatever is left; every other column gets exactly its width.
+    pub fn constraint(&self) -> ratatui::layout::Constraint {
+        use ratatui::layout::Constraint;
+        if self.column == Column::Command {
+            Constraint::Min(self.min_width())
+        } else {
+            Constraint::Length(self.width)
+        }
+    }
+
+    // Width COMMAND gets at the least (the fixed width, if one is configured).
+    pub fn min_width(&self) -> u16 {
+        self.limits.width.or(self.limits.min).unwrap_or(0)
+    }
+
+    pub fn set_min_width(&mut self, width: u16) -> &ColumnWidth {
+        if self.limits.width.is_some() { self.limits.width = Some(width); } else { self.limits.min = Some(width); }
+        &self.limits
+    }
+
+    // How the width came about, for the F12 overlay: fixed, or fitted to the content within its
+    // bounds, with how many refreshes a pending shrink has waited so far.
+    pub fn sizing(&self) -> String {
+        if self.column == Column::Command { return format!("rest, {} min", self.min_width()); }
+        if let Some(width) = self.limits.width { return format!("{} fixed", width); }
+        let mut text = format!("{} fit", self.width);
+        match (self.limits.min, self.limits.max) {
+            (Some(min), Some(max)) => text.push_str(&format!(" {}..{}", min, max)),
+            (Some(min), None) => text.push_str(&format!(" ≥{}", min)),
+            (None, Some(max)) => text.push_str(&format!(" ≤{}", max)),
+            (None, None) => {}
+        }
+        if self.shrink_streak > 0 { text.push_str(&format!(" ↓{}/{}", self.shrink_streak, SHRINK_AFTER)); }
+        if self.auto { text.push_str(", auto"); }
+        text
+    }
+
+    // Adjusts the width towards the widest value in `processes`, within the configured bounds.
+    // With `grow_only` (--accessible), it never narrows again, so the layout stays put.
+    pub fn fit<'a>(&mut self, processes: impl Iterator<Item = &'a ProcessInfo>, fmt: CellFormat, grow_only: bool, scratch: &mut String) {
+        if self.column == Column::Command || self.limits.width.is_some() {
+            return;
+        }
+        let content = processes.map(|p| self.column.content_width(p, fmt, scratch)).max().unwrap_or(0);
+        let mut target = content.max(self.column.header_width(fmt)) as u16;
+        if let Some(max) = self.limits.max { target = target.min(max); }
+        if let Some(min) = self.limits.min { target = target.max(min); }
+
+        if target > self.width {
+            self.width = target;
+            self.shrink_streak = 0;
+        } else if target < self.width && !grow_only {
+            self.shrink_streak += 1;
+            if self.shrink_streak >= SHRINK_AFTER {
+                self.width = target;
+                self.shrink_streak = 0;
+            }
+        } else {
+            self.shrink_streak = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn home_is_abbreviated_on_component_boundaries() {
+        assert_eq!(abbreviate_home("/Users/me/bin/tool", Some("/Users/me")), ("~", "/bin/tool"));
+        assert_eq!(abbreviate_home("/Users/me/bin/tool", Some("/Users/me/")), ("~", "/bin/tool"));
+        assert_eq!(abbreviate_home("/Users/meg/bin/tool", Some("/Users/me")), ("", "/Users/meg/bin/tool"));
+        assert_eq!(abbreviate_home("/usr/bin/python3", Some("/")), ("", "/usr/bin/python3"));
+        assert_eq!(abbreviate_home("/usr/bin/python3", None), ("", "/usr/bin/python3"));
+    }
+
+    #[test]
+    fn command_drops_the_program_when_the_path_is_shown() {
+        let p = ProcessInfo { command: "/opt/homebrew/bin/node server.js".to_string(), args_start: 23, exe: "/opt/homebrew/bin/node".to_string(), ..Default::default() };
+        let fmt = CellFormat { path_column: true, ..Default::default() };
+        assert_eq!(Column::Command.cell_text(&p, fmt), "server.js");
+        assert_eq!(Column::Command.cell_text(&p, CellFormat::default()), p.command);
+        // Without a known path the program stays, or it would be nowhere on screen.
+        let hidden = ProcessInfo { exe: String::new(), ..p };
+        assert_eq!(Column::Command.cell_text(&hidden, fmt), hidden.command);
+    }
+
+    #[test]
+    fn base_name_is_the_last_component_of_the_program() {
+        let p = ProcessInfo { command: "/opt/homebrew/bin/node server.js".to_string(), args_start: 23, exe: "/opt/homebrew/bin/node".to_string(), ..Default::default() };
+        assert_eq!(base_name_range(&p, CellFormat::default()), Some(18..22));
+        assert_eq!(base_name_range(&p, CellFormat { path_column: true, ..Default::default() }), None);
+        let bare = ProcessInfo { command: "launchd".to_string(), args_start: 7, ..Default::default() };
+        assert_eq!(base_name_range(&bare, CellFormat::default()), Some(0..7));
+    }
+
+    #[test]
+    fn only_the_sorted_column_shows_the_direction() {
+        let fmt = CellFormat::default();
+        assert_eq!(Column::Pid.header_with_arrow(fmt, SortBy::Pid, SortOrder::Asc), "PID▲");
+        assert_eq!(Column::Cpu.header_with_arrow(fmt, SortBy::Cpu, SortOrder::Desc), "CPU%▼");
+        assert_eq!(Column::Cpu.header_with_arrow(fmt, SortBy::Pid, SortOrder::Desc), "CPU%");
+        // The arrow's room is kept whether or not the column is sorted.
+        assert_eq!(Column::Cpu.header_width(fmt), 5);
+        assert_eq!(Column::Virt.header_width(fmt), 4);
+    }
+
+    #[test]
+    fn wide_terminals_reveal_auto_columns_in_order() {
+        let shown = Column::DEFAULT;
+        let auto = [Column::Ppid, Column::Res, Column::Threads, Column::Started, Column::Path];
+        // The defaults take 60 cells with the borders; COMMAND keeps 80 of what is left.
+        assert_eq!(revealed(120, &shown, &auto, &[]), []);
+        assert_eq!(revealed(148, &shown, &auto, &[]), [Column::Ppid]);
+        assert_eq!(revealed(166, &shown, &auto, &[]), [Column::Ppid, Column::Res, Column::Threads, Column::Started]);
+        assert_eq!(revealed(300, &shown, &auto, &[]), auto);
+        // Narrowing drops from the back: STARTED would still fit here, but not before THR.
+        assert_eq!(revealed(159, &shown, &auto, &[]), [Column::Ppid, Column::Res]);
+        // What is configured already isn't added twice, and the rest move up.
+        let with_ppid = [Column::Pid, Column::Ppid, Column::Command];
+        assert_eq!(revealed(140, &with_ppid, &auto, &[]), [Column::Res, Column::Threads, Column::Started]);
+        // Leaving a column out of `auto_columns` keeps it out.
+        assert_eq!(revealed(300, &shown, &[Column::Res], &[]), [Column::Res]);
+        assert_eq!(revealed(300, &shown, &[], &[]), []);
+        assert_eq!(revealed(0, &shown, &auto, &[]), []);
+        // Columns turned off stay off however wide the terminal: hidden ones, and defaults taken
+        // out of `columns`.
+        assert_eq!(revealed(300, &shown, &auto, &[Column::Res, Column::Path]), [Column::Ppid, Column::Threads, Column::Started]);
+        let without_time = [Column::Pid, Column::Cpu, Column::Command];
+        assert_eq!(revealed(300, &without_time, &[Column::Time, Column::Ppid], &[]), [Column::Ppid]);
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::AlertRule;
+use crate::columns::Column;
+use crate::keymap::{Action, KeyList};
+use crate::meters::Meter;
+use crate::process_list::{SortBy, SortOrder};
+use crate::presets::FilterPreset;
+use crate::screens::ScreenConfig;
+use crate::theme::ThemeName;
+
+// Refreshing faster than this costs more CPU than it shows.
+const MIN_REFRESH_MS: u64 = 200;
+
+// User settings read from ~/.config/htop-macos/config.toml. Every field has a default,
+// so a missing file or a partial file is fine. Settings changed from inside the app
+// (e.g. the setup screen) are written back with `save`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub kill_default_signal: i32, // Signal number pre-selected the first time the kill menu opens
+    pub protected_pids: Vec<u32>, // Signalling these needs an extra confirmation (or is refused)
+    pub protected_names: Vec<String>, // Same, matched against the executable name
+    pub block_protected: bool, // Refuse outright instead of asking for confirmation
+    pub bulk_kill_max: usize, // "Kill all matching" above this many processes asks twice
+    pub kill_escalate: bool, // Whether "escalate" starts out ticked in the kill menu
+    pub kill_escalate_secs: u64, // Grace period before an escalating kill follows up with SIGKILL
+    pub percent_decimals: usize, // Places after the point in CPU%/MEM%/AVG%, 0 to 2
+    pub smooth_cpu: bool, // Show (and sort by) each process's CPU% averaged over recent samples
+    pub smooth_cpu_samples: usize, // How many samples `smooth_cpu` averages
+    pub centered_cursor: bool, // Scroll the table to keep the selected row mid-screen
+    pub refresh_ms: u64, // Time between samples
+    pub process_count_warn: usize, // Past this many processes, a one-time note suggests a filter or `top_n`; 0 never
+    pub top_n: usize, // The flat, unfiltered list shows only this many from the top of the sort; 0 shows all
+    pub snapshot_every: u32, // --record to a directory: a snapshot every this many refreshes
+    pub snapshot_keep: usize, // --record to a directory: the newest this many snapshots are kept; 0 keeps all
+    pub inline_rows: u16, // Height with --no-altscreen, at most the terminal's
+    pub sort_by: SortBy, // Column the table starts sorted by
+    pub tree_view: bool, // Start in the tree rather than the flat list
+    pub tree_pid_order: bool, // Order siblings in the tree by PID, as pstree does, rather than by the sort column
+    pub theme: ThemeName,
+    pub highlight_base_name: bool, // Show the program's name in bold inside COMMAND
+    pub tall_rows: bool, // Start with two lines per process ('z' toggles)
+    pub memory_breakdown: bool, // Show wired/active/inactive/compressed instead of a single used figure
+    pub header_left: Vec<Meter>, // Meters in the left header column, top to bottom
+    pub header_right: Vec<Meter>, // Meters in the right header column, top to bottom
+    pub columns: Vec<Column>, // Process table columns, left to right
+    pub auto_columns: Vec<Column>, // Added ahead of COMMAND, in this order, as far as the terminal is wide enough
+    pub hidden_columns: Vec<Column>, // Never added by `auto_columns`, however wide the terminal
+    pub ports_warn: u32, // PORTS values above this are shown in red
+    pub mem_growth_warn_mb: u64, // ΔMEM growth above this many MiB is shown in red
+    pub leak_secs: u64, // Seconds of steady RSS growth before a process is marked as leaking; 0 turns it off
+    pub alerts: Vec<AlertRule>, // "load1 > cores", "swap > 4G", ...; see `alerts`
+    pub alert_bell: bool, // Ring the terminal bell when an alert trips
+    pub set_title: bool, // Keep the terminal title updated with CPU, memory and load
+    pub terminal_app: String, // Where 's' runs `sudo dtruss` when we aren't root: "Terminal" or "iTerm"
+    pub screens: Vec<ScreenConfig>, // Tabs after Main, which is `columns`, `sort_by` and `tree_view` above
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub filter_presets: Vec<FilterPreset>, // Saved and picked with 'B'
+    pub column_widths: BTreeMap<String, ColumnWidth>, // Keyed by column name ("pid", "user", ...)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub sort_order: BTreeMap<SortBy, SortOrder>, // Direction a column first sorts in, where not `SortBy::default_order`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<Action, KeyList>, // Key overrides for the main screen, by action
+}
+
+// Bounds for an auto-sized column, or a fixed `width` that disables auto-sizing.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ColumnWidth {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u16>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            kill_default_signal: 15, // SIGTERM: ask nicely first
+            protected_pids: vec![1],
+            protected_names: vec!["kernel_task".to_string()],
+            block_protected: false,
+            bulk_kill_max: 200,
+            kill_escalate: false,
+            kill_escalate_secs: 10,
+            percent_decimals: 1,
+            smooth_cpu: false,
+            smooth_cpu_samples: 3,
+            centered_cursor: false,
+            refresh_ms: 2000,
+            process_count_warn: 2000,
+            top_n: 0,
+            snapshot_every: 10,
+            snapshot_keep: 360,
+            inline_rows: 24,
+            sort_by: SortBy::Cpu,
+            tree_view: false,
+            tree_pid_order: false,
+            theme: ThemeName::Default,
+            highlight_base_name: false,
+            tall_rows: false,
+            memory_breakdown: false,
+            header_left: vec![Meter::CpuPerCore, Meter::CpuAverage, Meter::CpuSplit],
+            header_right: vec![Meter::Memory, Meter::Swap, Meter::Tasks, Meter::LoadAverage, Meter::Uptime],
+            columns: Column::DEFAULT.to_vec(),
+            auto_columns: vec![Column::Ppid, Column::Res, Column::Threads, Column::Started, Column::Path],
+            hidden_columns: Vec::new(),
+            ports_warn: 5000,
+            mem_growth_warn_mb: 100,
+            leak_secs: 60,
+            alerts: Vec::new(),
+            alert_bell: false,
+            set_title: true,
+            terminal_app: "Terminal".to_string(),
+            screens: ScreenConfig::defaults(),
+            filter_presets: Vec::new(),
+            column_widths: BTreeMap::new(),
+            sort_order: BTreeMap::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.refresh_ms.max(MIN_REFRESH_MS))
+    }
+
+    // RSS samples spanning `leak_secs` at the current refresh rate; 0 when the check is off.
+    pub fn leak_samples(&self) -> usize {
+        if self.leak_secs == 0 { return 0; }
+        (Duration::from_secs(self.leak_secs).as_millis() / self.refresh_interval().as_millis()) as usize + 1
+    }
+
+    // Direction `sort_by` starts out in when picked.
+    pub fn sort_order_for(&self, sort_by: SortBy) -> SortOrder {
+        self.sort_order.get(&sort_by).copied().unwrap_or(sort_by.default_order())
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        let home = std::env::var_os("HOME")?;
+        Some(PathBuf::from(home).join(".config").join("htop-macos").join("config.toml"))
+    }
+
+    // A missing file yields the defaults; a malformed one is reported so the user knows why
+    // their settings were ignored.
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::path() else { return Ok(Self::default()) };
+        match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("HOME is not set")?;
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        fs::write(&path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
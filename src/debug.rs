@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::format::format_bytes;
+use crate::App;
+
+// Timings behind the F12 overlay, for profiling htop-macos itself.
+#[derive(Default)]
+pub struct DebugStats {
+    pub shown: bool,
+    pub render: Duration, // The previous `terminal.draw`: building widgets plus writing the diff
+    pub frames: u64, // Frames drawn so far; idle frames are skipped, so this isn't a rate
+    pub sample: Duration, // The last `DataSource::refresh`, CPU sampling window included
+    pub update: Duration, // The last `update_data`, under the lock
+}
+
+impl App {
+    pub fn toggle_debug_overlay(&mut self) {
+        self.debug.shown = !self.debug.shown;
+    }
+}
+
+// Top right corner, over the header.
+pub fn draw_debug_overlay(f: &mut Frame, app: &App, area: Rect) {
+    let ms = |d: Duration| format!("{:.1} ms", d.as_secs_f64() * 1000.0);
+    let own = app.processes.get(&std::process::id()).map_or("-".to_string(), |p| format_bytes(p.resident));
+    let mut text = vec![
+        Line::from(format!("render   {}", ms(app.debug.render))),
+        Line::from(format!("frames   {}", app.debug.frames)),
+        Line::from(format!("sample   {}", ms(app.debug.sample))),
+        Line::from(format!("update   {}", ms(app.debug.update))),
+        Line::from(format!("procs    {}", app.processes.len())),
+        Line::from(format!("own RSS  {}", own)),
+        Line::from(""),
+    ];
+    // Column widths, and why: fixed in the config or fitted to the widest value.
+    text.extend(app.columns.iter().map(|c| Line::from(format!("{:<8} {}", c.column.header(app.cell_format), c.sizing()))));
+    let width = 32.min(area.width);
+    let rect = Rect { x: area.x + area.width - width, y: area.y, width, height: (text.len() as u16 + 2).min(area.height) };
+    let overlay = Paragraph::new(text).style(app.theme.input).block(Block::default().borders(Borders::ALL).title("Debug"));
+    f.render_widget(Clear, rect);
+    f.render_widget(overlay, rect);
+}
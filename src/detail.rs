@@ -0,0 +1,329 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+use crate::columns::Column;
+use crate::format::{format_bytes, write_time};
+use crate::platform::{copy_to_clipboard, CodeSigning, ProcessGroup, ProcessPaths, ResourceUsage, ThreadInfo};
+use crate::process_list::parent_chain;
+use crate::source::{status_meaning, DataSource};
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// Wakeups per second above which a process is likely costing battery life. Activity Monitor's
+// energy impact starts climbing steeply around here.
+const HIGH_WAKEUPS_PER_SEC: f64 = 100.0;
+
+// Threads scrolled per PageUp/PageDown.
+const THREAD_PAGE: usize = 10;
+
+// State of the detail pane (Enter on a process).
+#[derive(Default)]
+pub struct DetailState {
+    pid: u32,
+    chain: Vec<u32>, // Ancestry of `pid`, oldest first, ending with `pid` itself
+    cursor: usize, // Highlighted entry of `chain`
+    usage: Option<Option<ResourceUsage>>, // None until the next refresh samples it; Some(None) if the OS refused
+    previous: Option<(ResourceUsage, Duration)>, // The sample before `usage` and how long before it, for rates
+    sampled_at: Option<Instant>,
+    service: Option<String>, // launchd job, once a refresh has looked it up
+    threads: Option<Result<Vec<ThreadInfo>, &'static str>>, // Busiest first; None until the next refresh
+    thread_scroll: usize, // First thread shown
+    paths: Option<Option<ProcessPaths>>, // None until the next refresh; Some(None) for recordings
+    group: Option<Option<ProcessGroup>>, // Likewise; Some(None) also when the process is gone
+    signing: Option<Option<CodeSigning>>, // Likewise
+    pub chain_hits: Vec<(Rect, u32)>, // Screen area of each chain entry, from the last draw
+}
+
+impl App {
+    // The process whose details the next refresh should collect, while the pane is open.
+    pub fn detail_pid(&self) -> Option<u32> {
+        (self.input_mode == InputMode::Detail).then_some(self.detail.pid)
+    }
+
+    // The launchd job of `pid`, if the detail pane already knows it.
+    pub fn known_service(&self, pid: u32) -> Option<&str> {
+        self.detail.service.as_deref().filter(|_| self.detail.pid == pid)
+    }
+
+    pub fn open_detail(&mut self) {
+        let Some(pid) = self.selected_pid() else { return };
+        self.detail = DetailState { pid, ..Default::default() };
+        self.resolve_chain();
+        self.detail.cursor = self.detail.chain.len().saturating_sub(1);
+        self.input_mode = InputMode::Detail;
+    }
+
+    // Called after each data refresh while the pane is open. Only the shown process pays for
+    // the extra counters.
+    pub fn refresh_detail(&mut self, source: &dyn DataSource) {
+        let now = Instant::now();
+        let last = self.detail.usage.flatten().zip(self.detail.sampled_at);
+        self.detail.previous = last.map(|(usage, at)| (usage, now - at));
+        self.detail.usage = Some(source.resource_usage(self.detail.pid));
+        self.detail.sampled_at = Some(now);
+        self.detail.group = Some(source.process_group(self.detail.pid));
+        // Collected by the refresh that just ran, once it knew the pane was open.
+        if let Some(mut sample) = source.detail(self.detail.pid) {
+            if let Ok(threads) = &mut sample.threads {
+                threads.sort_by(|a, b| b.cpu.unwrap_or(0.0).total_cmp(&a.cpu.unwrap_or(0.0)).then(b.cpu_time.cmp(&a.cpu_time)));
+            }
+            self.detail.threads = Some(sample.threads);
+            self.detail.paths = Some(sample.paths);
+            self.detail.signing = Some(sample.signing);
+            self.detail.service = sample.service;
+        }
+        self.resolve_chain();
+    }
+
+    // Re-resolves the ancestry; processes in it may have exited.
+    fn resolve_chain(&mut self) {
+        let highlighted = self.detail.chain.get(self.detail.cursor).copied();
+        self.detail.chain = parent_chain(&self.processes, self.detail.pid);
+        self.detail.cursor = highlighted.and_then(|pid| self.detail.chain.iter().position(|&p| p == pid))
+            .unwrap_or(self.detail.chain.len().saturating_sub(1));
+    }
+
+    pub fn handle_detail_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Up => self.detail.cursor = self.detail.cursor.saturating_sub(1),
+            KeyCode::Down => self.detail.cursor = (self.detail.cursor + 1).min(self.detail.chain.len().saturating_sub(1)),
+            KeyCode::Enter => {
+                if let Some(&pid) = self.detail.chain.get(self.detail.cursor) { self.jump_to_ancestor(pid); }
+            }
+            // Zombies: the parent is the process to act on.
+            KeyCode::Char('p') => {
+                if let Some(p) = self.processes.get(&self.detail.pid).filter(|p| p.status == "Z" && p.ppid != 0) {
+                    let ppid = p.ppid;
+                    self.jump_to_ancestor(ppid);
+                }
+            }
+            KeyCode::Char('R') if self.detail.service.is_some() => self.request_restart(),
+            KeyCode::Char('c') => self.copy_path("working directory", |p| p.cwd.as_deref()),
+            KeyCode::Char('e') => self.copy_path("executable path", |p| p.exe.as_deref()),
+            // Browsers have hundreds of threads; the list is clamped to its end when drawn.
+            KeyCode::PageDown => self.detail.thread_scroll += THREAD_PAGE,
+            KeyCode::PageUp => self.detail.thread_scroll = self.detail.thread_scroll.saturating_sub(THREAD_PAGE),
+            KeyCode::Esc | KeyCode::Char('q') => self.input_mode = InputMode::Normal,
+            _ => {}
+        }
+    }
+
+    // Clicking an entry of the parent chain jumps straight to it.
+    pub fn click_detail(&mut self, x: u16, y: u16) {
+        let hit = self.detail.chain_hits.iter().find(|(r, _)| x >= r.x && x < r.x + r.width && y == r.y).map(|&(_, pid)| pid);
+        if let Some(pid) = hit { self.jump_to_ancestor(pid); }
+    }
+
+    fn copy_path(&mut self, what: &str, get: fn(&ProcessPaths) -> Option<&str>) {
+        let Some(path) = self.detail.paths.as_ref().and_then(|p| p.as_ref()).and_then(get) else {
+            self.message = Some(format!("No {} to copy", what));
+            return;
+        };
+        self.message = Some(match copy_to_clipboard(path) {
+            Ok(()) => format!("Copied {}", path),
+            Err(e) => format!("Could not copy the {}: {}", what, e),
+        });
+    }
+
+    fn jump_to_ancestor(&mut self, pid: u32) {
+        self.input_mode = InputMode::Normal;
+        self.show_pid(pid);
+    }
+}
+
+// Draws the pane and returns where each parent chain entry ended up, for mouse clicks.
+pub fn draw_detail(f: &mut Frame, app: &App, area: Rect) -> Vec<(Rect, u32)> {
+    let area = centered_rect(80, 80, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title(format!("Process {}", app.detail.pid));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [inner, threads_area] = Layout::vertical([Constraint::Fill(3), Constraint::Fill(2)]).areas(inner);
+    draw_threads(f, app, threads_area);
+
+    let Some(p) = app.processes.get(&app.detail.pid) else {
+        f.render_widget(Paragraph::new("The process has exited.  Esc to close"), inner);
+        return Vec::new();
+    };
+    let label = app.theme.label;
+    let field = |name: &'static str, value: String| Line::from(vec![Span::styled(format!("{:<11}", name), label), Span::raw(value)]);
+    let time = Column::Time.cell_text(p, app.cell_format).into_owned();
+
+    // The chain comes first so its position doesn't depend on how the command line wraps.
+    let mut chain_line = vec![Span::styled(format!("{:<11}", "Parents"), label)];
+    let mut hits = Vec::new();
+    let mut x = inner.x + 11;
+    if app.processes.get(&app.detail.chain.first().copied().unwrap_or(0)).is_some_and(|root| root.ppid != 0) {
+        // The oldest ancestor we know of has a parent we can't see (it exited).
+        chain_line.push(Span::styled("… → ", app.theme.dim));
+        x += 4;
+    }
+    for (i, &pid) in app.detail.chain.iter().enumerate() {
+        if i > 0 {
+            chain_line.push(Span::raw(" → "));
+            x += 3;
+        }
+        let name = app.processes.get(&pid).map(|p| p.name.as_str()).unwrap_or("?");
+        let marker = if i == app.detail.cursor { app.selection_marker("") } else { "" };
+        let text = format!("{}{}({})", marker, name, pid);
+        let width = text.chars().count() as u16;
+        let style = if i == app.detail.cursor { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() };
+        if x + width <= inner.x + inner.width { hits.push((Rect { x, y: inner.y, width, height: 1 }, pid)); }
+        chain_line.push(Span::styled(text, style));
+        x += width;
+    }
+
+    let mut lines = vec![Line::from(chain_line), Line::from("")];
+    if p.status == "Z" {
+        let red = app.theme.warning;
+        lines.push(Line::from(Span::styled(format!("Zombie — {}", app.reap_hint(p.ppid)), red)));
+        lines.push(Line::from(Span::styled(format!(
+            "It has exited, but its parent {} hasn't collected its exit status yet. Signalling it does nothing; SIGCHLD to the parent may get it reaped.", app.process_label(p.ppid)), red)));
+        lines.push(Line::from(Span::styled("p select the parent", app.theme.dim)));
+        lines.push(Line::from(""));
+    }
+    lines.extend([
+        field("PID", p.pid.to_string()),
+        field("PPID", p.ppid.to_string()),
+        field("PGRP", match app.detail.group {
+            Some(Some(g)) if g.pgid == p.pid => format!("{} (group leader)", g.pgid),
+            Some(Some(g)) => g.pgid.to_string(),
+            Some(None) => "-".to_string(),
+            None => "…".to_string(),
+        }),
+        field("Session", match app.detail.group {
+            Some(Some(ProcessGroup { sid: Some(sid), .. })) if sid == p.pid => format!("{} (session leader)", sid),
+            Some(Some(ProcessGroup { sid: Some(sid), .. })) => sid.to_string(),
+            Some(_) => "-".to_string(),
+            None => "…".to_string(),
+        }),
+        field("User", match p.uid { Some(uid) => format!("{} ({})", p.user, uid), None => p.user.clone() }),
+        field("State", match status_meaning(&p.status) {
+            Some(meaning) => format!("{} ({})", p.status, meaning),
+            None => p.status.clone(),
+        }),
+        field("CPU%", match app.config.smooth_cpu {
+            true => format!("{:.*} (the table shows {:.*}, averaged over {} samples)", app.cell_format.percent_decimals, p.cpu_now(),
+                            app.cell_format.percent_decimals, p.cpu, p.cpu_history.len()),
+            false => Column::Cpu.cell_text(p, app.cell_format).into_owned(),
+        }),
+        field("MEM%", Column::Mem.cell_text(p, app.cell_format).into_owned()),
+        field("VIRT", format_bytes(p.virtual_mem)),
+        field("RSS", format_bytes(p.resident)),
+        field("Footprint", match app.detail.usage {
+            Some(Some(usage)) => format!("{} (peak {})", format_bytes(usage.phys_footprint), format_bytes(usage.peak_footprint)),
+            Some(None) => "-".to_string(),
+            None => "…".to_string(),
+        }),
+    ]);
+    // Only shown where the OS lets us see it, rather than as a misleading zero.
+    if let Some(compressed) = app.detail.usage.flatten().and_then(|u| u.compressed) {
+        lines.push(field("Compressed", format_bytes(compressed)));
+    }
+    lines.push(Line::from(Span::styled(
+        "  RSS counts every resident page, shared ones included. Footprint is what Activity Monitor shows as Memory: \
+         pages only this process dirtied, including what has been compressed or swapped out.",
+        app.theme.dim)));
+    if let Some(usage) = app.detail.usage.flatten() {
+        // Totals since the process started, and the rate over the last refresh interval.
+        let previous = app.detail.previous;
+        let rate = |get: fn(&ResourceUsage) -> u64| {
+            previous.filter(|(_, dt)| !dt.is_zero()).map(|(old, dt)| get(&usage).saturating_sub(get(&old)) as f64 / dt.as_secs_f64())
+        };
+        let counter = |name: &'static str, get: fn(&ResourceUsage) -> u64| {
+            let mut value = get(&usage).to_string();
+            if let Some(rate) = rate(get) { value.push_str(&format!("  ({:.0}/s)", rate)); }
+            field(name, value)
+        };
+        let wakeups = counter("Wakeups", |u| u.wakeups);
+        lines.push(if rate(|u| u.wakeups).is_some_and(|r| r > HIGH_WAKEUPS_PER_SEC) {
+            wakeups.style(app.theme.warning.add_modifier(Modifier::BOLD))
+        } else {
+            wakeups
+        });
+        lines.push(counter("Ctx switch", |u| u.context_switches));
+        lines.push(counter("Faults", |u| u.faults));
+        lines.push(counter("Page-ins", |u| u.pageins));
+    }
+    lines.extend([
+        field("Threads", p.threads.map_or("-".to_string(), |t| t.to_string())),
+        field(Column::Time.header(app.cell_format), time),
+        field("Command", p.command.clone()),
+    ]);
+    if let Some(label) = &p.label { lines.push(field("Label", label.clone())); }
+    // Paths are wrapped rather than cut: the end is usually the part that matters.
+    let path = |name: &'static str, key: &'static str, get: fn(&ProcessPaths) -> &Option<String>| {
+        let value = match &app.detail.paths {
+            Some(Some(paths)) => get(paths).clone(),
+            Some(None) => return field(name, "(not in recordings)".to_string()),
+            None => return field(name, "…".to_string()),
+        };
+        match value {
+            Some(value) => Line::from(vec![Span::styled(format!("{:<11}", name), label), Span::raw(value), Span::styled(key, app.theme.dim)]),
+            None => field(name, "(not accessible — run as root)".to_string()),
+        }
+    };
+    lines.push(path("Cwd", "  c copy", |p| &p.cwd));
+    lines.push(path("Executable", "  e copy", |p| &p.exe));
+    match &app.detail.signing {
+        Some(Some(signing)) if signing.signed => {
+            let mut by = match (&signing.authority, signing.adhoc) {
+                (Some(authority), _) => authority.clone(),
+                (None, true) => "ad-hoc (no certificate)".to_string(),
+                (None, false) => "-".to_string(),
+            };
+            if let Some(team) = &signing.team { by.push_str(&format!(" (team {})", team)); }
+            lines.push(field("Signed by", by));
+            if let Some(identifier) = &signing.identifier { lines.push(field("Identifier", identifier.clone())); }
+            lines.push(field("Sandboxed", if signing.sandboxed { "yes" } else { "no" }.to_string()));
+        }
+        Some(Some(_)) => lines.push(field("Signed by", "not signed".to_string()).style(app.theme.warning)),
+        Some(None) => lines.push(field("Signed by", "-".to_string())),
+        None => lines.push(field("Signed by", "…".to_string())),
+    }
+    if let Some(service) = &app.detail.service {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<11}", "Service"), label),
+            Span::raw(service.clone()),
+            Span::styled("  R restart", app.theme.dim),
+        ]));
+    }
+    lines.extend([
+        Line::from(""),
+        Line::from(Span::styled("↑/↓ pick a parent   Enter go to it   PgUp/PgDn scroll threads   Esc close", app.theme.dim)),
+    ]);
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+    hits
+}
+
+fn draw_threads(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::TOP);
+    let threads = match &app.detail.threads {
+        None => return f.render_widget(Paragraph::new("Threads: …").block(block), area),
+        Some(Err(why)) => return f.render_widget(Paragraph::new(format!("Threads: {}", why)).block(block), area),
+        Some(Ok(threads)) => threads,
+    };
+    let height = area.height.saturating_sub(2) as usize; // Rule and header row
+    let first = app.detail.thread_scroll.min(threads.len().saturating_sub(height));
+    let mut time = String::new();
+    let rows = threads[first..].iter().take(height).map(|t| {
+        time.clear();
+        let _ = write_time(&mut time, t.cpu_time);
+        Row::new([
+            Cell::from(t.tid.to_string()),
+            Cell::from(t.state),
+            Cell::from(t.cpu.map_or("-".to_string(), |c| format!("{:.*}", app.cell_format.percent_decimals, c))),
+            Cell::from(time.clone()),
+            Cell::from(t.name.as_str()),
+        ])
+    });
+    let title = format!(" Threads: {} (busiest first), {}-{} ", threads.len(), (first + 1).min(threads.len()), (first + height).min(threads.len()));
+    let widths = [Constraint::Length(10), Constraint::Length(2), Constraint::Length(6), Constraint::Length(10), Constraint::Fill(1)];
+    let header = Row::new(["TID", "S", "CPU%", "TIME+", "NAME"]).style(app.theme.column_header);
+    f.render_widget(Table::new(rows, widths).header(header).block(block.title(title)), area);
+}
@@ -0,0 +1,202 @@
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use sysinfo::Disks;
+
+use crate::format::format_bytes;
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// Free space changes slowly; no need to ask on every refresh.
+const DISK_INTERVAL: Duration = Duration::from_secs(10);
+// Volumes fuller than this are shown in red.
+const FULL_PERCENT: f64 = 90.0;
+
+// One mounted filesystem.
+#[derive(Clone, Default)]
+pub struct Volume {
+    pub device: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total: u64,
+    pub available: u64,
+    pub container: Option<char>, // Shared with other volumes of the same APFS container; see `mark_shared`
+}
+
+impl Volume {
+    fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    fn percent(&self) -> f64 {
+        if self.total == 0 { 0.0 } else { self.used() as f64 / self.total as f64 * 100.0 }
+    }
+}
+
+// The disks screen ('D'). Sampled when it opens and every `DISK_INTERVAL` while it stays open,
+// on a thread of its own: statfs on a stale network mount can block indefinitely.
+#[derive(Default)]
+pub struct DiskScreen {
+    volumes: Vec<Volume>,
+    receiver: Option<Receiver<Vec<Volume>>>, // Dropping it stops the sampling thread
+    sampled: bool, // False until the first volumes are in
+    selected: usize,
+    by_mount_point: bool, // Instead of fullest first
+}
+
+impl App {
+    pub fn open_disks(&mut self) {
+        self.input_mode = InputMode::Disks;
+        if self.disks.receiver.is_some() { return; }
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || loop {
+            let mut volumes: Vec<Volume> = Disks::new_with_refreshed_list().iter().map(|d| Volume {
+                device: d.name().to_string_lossy().into_owned(),
+                mount_point: d.mount_point().display().to_string(),
+                file_system: d.file_system().to_string_lossy().into_owned(),
+                total: d.total_space(),
+                available: d.available_space(),
+                container: None,
+            }).collect();
+            mark_shared(&mut volumes);
+            if sender.send(volumes).is_err() { return; }
+            std::thread::sleep(DISK_INTERVAL);
+        });
+        self.disks.receiver = Some(receiver);
+    }
+
+    // Called on every refresh: a closed screen lets its thread go.
+    pub fn sample_disks(&mut self) {
+        if self.input_mode != InputMode::Disks { self.disks = DiskScreen::default(); }
+    }
+
+    // Called from the UI loop. True when the open screen has new volumes to show.
+    pub fn poll_disks(&mut self) -> bool {
+        let Some(volumes) = self.disks.receiver.as_ref().and_then(|r| r.try_iter().last()) else { return false };
+        self.disks.volumes = volumes;
+        self.disks.sampled = true;
+        self.sort_disks();
+        true
+    }
+
+    fn sort_disks(&mut self) {
+        let selected = self.disks.volumes.get(self.disks.selected).map(|v| v.mount_point.clone());
+        if self.disks.by_mount_point {
+            self.disks.volumes.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+        } else {
+            self.disks.volumes.sort_by(|a, b| b.percent().total_cmp(&a.percent()).then_with(|| a.mount_point.cmp(&b.mount_point)));
+        }
+        let found = selected.and_then(|m| self.disks.volumes.iter().position(|v| v.mount_point == m));
+        self.disks.selected = found.unwrap_or(0).min(self.disks.volumes.len().saturating_sub(1));
+    }
+
+    pub fn handle_disks_key(&mut self, code: KeyCode) {
+        let len = self.disks.volumes.len();
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('D') => self.input_mode = InputMode::Normal,
+            KeyCode::Down if len > 0 => self.disks.selected = (self.disks.selected + 1).min(len - 1),
+            KeyCode::Up => self.disks.selected = self.disks.selected.saturating_sub(1),
+            KeyCode::Char('s') => {
+                self.disks.by_mount_point = !self.disks.by_mount_point;
+                self.sort_disks();
+            }
+            _ => {}
+        }
+    }
+}
+
+// APFS volumes in one container draw on the same free space, so they all report the same
+// total and available bytes. Those that do get a common letter rather than looking like
+// separate disks that happen to be equally full.
+fn mark_shared(volumes: &mut [Volume]) {
+    let mut next = 'A';
+    for i in 0..volumes.len() {
+        if volumes[i].container.is_some() || !volumes[i].file_system.eq_ignore_ascii_case("apfs") { continue; }
+        let key = (volumes[i].total, volumes[i].available);
+        let same: Vec<usize> = (i + 1..volumes.len())
+            .filter(|&j| volumes[j].file_system.eq_ignore_ascii_case("apfs") && (volumes[j].total, volumes[j].available) == key)
+            .collect();
+        if same.is_empty() { continue; }
+        for j in std::iter::once(i).chain(same) { volumes[j].container = Some(next); }
+        next = char::from_u32(next as u32 + 1).unwrap_or('?');
+    }
+}
+
+// "[██████░░░░]", filled in proportion to `percent`.
+fn usage_bar(percent: f64, width: usize) -> String {
+    let filled = ((percent / 100.0 * width as f64).round() as usize).min(width);
+    format!("[{}{}]", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+pub fn draw_disks(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(85, 70, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Disks");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [list_area, notes_area] = Layout::vertical([Constraint::Min(3), Constraint::Length(3)]).areas(inner);
+
+    let volumes = &app.disks.volumes;
+    let rows: Vec<Row> = volumes.iter().map(|v| {
+        let percent = v.percent();
+        let row = Row::new([
+            Cell::from(v.mount_point.as_str()),
+            Cell::from(v.device.as_str()),
+            Cell::from(v.file_system.as_str()),
+            Cell::from(v.container.map_or(String::new(), String::from)),
+            Cell::from(format_bytes(v.total)),
+            Cell::from(format_bytes(v.used())),
+            Cell::from(format_bytes(v.available)),
+            Cell::from(format!("{} {:>3.0}%", usage_bar(percent, 10), percent)),
+        ]);
+        if percent > FULL_PERCENT { row.style(app.theme.warning) } else { row }
+    }).collect();
+    let header = Row::new(["MOUNTED ON", "DEVICE", "TYPE", "", "SIZE", "USED", "AVAIL", "USE"]).style(app.theme.column_header);
+    let widths = [
+        Constraint::Min(16), Constraint::Length(14), Constraint::Length(6), Constraint::Length(1), Constraint::Length(7),
+        Constraint::Length(7), Constraint::Length(7), Constraint::Length(17),
+    ];
+    let table = Table::new(rows, widths).header(header).highlight_style(app.theme.selection).highlight_symbol(app.selection_marker(""));
+    let mut state = TableState::default().with_selected((!volumes.is_empty()).then_some(app.disks.selected));
+    f.render_stateful_widget(table, list_area, &mut state);
+
+    let mut notes = Vec::new();
+    if !app.disks.sampled { notes.push(Line::from("Reading volumes…")); }
+    if volumes.iter().any(|v| v.container.is_some()) {
+        notes.push(Line::from("Volumes with the same letter share one APFS container, and so the same free space."));
+    }
+    notes.resize(2, Line::default());
+    let sort = if app.disks.by_mount_point { "fullest first" } else { "by mount point" };
+    notes.push(Line::from(Span::styled(format!("↑/↓ select   s sort {}   Esc close", sort), app.theme.dim)));
+    f.render_widget(Paragraph::new(notes), notes_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn volume(mount_point: &str, file_system: &str, total: u64, available: u64) -> Volume {
+        Volume { mount_point: mount_point.to_string(), file_system: file_system.to_string(), total, available, ..Default::default() }
+    }
+
+    #[test]
+    fn apfs_volumes_sharing_a_container_get_one_letter() {
+        let mut volumes = vec![
+            volume("/", "apfs", 500, 200),
+            volume("/Volumes/USB", "msdos", 64, 60),
+            volume("/System/Volumes/Data", "apfs", 500, 200),
+            volume("/Volumes/Backup", "apfs", 2000, 900),
+            volume("/System/Volumes/VM", "apfs", 500, 200),
+            volume("/Volumes/Stick", "msdos", 64, 60),
+        ];
+        mark_shared(&mut volumes);
+        let letters: Vec<Option<char>> = volumes.iter().map(|v| v.container).collect();
+        assert_eq!(letters, [Some('A'), None, Some('A'), None, Some('A'), None]);
+        assert_eq!(usage_bar(60.0, 10), "[██████░░░░]");
+        assert_eq!(usage_bar(120.0, 4), "[████]");
+    }
+}
@@ -0,0 +1,116 @@
+use std::time::{Duration, Instant};
+
+use crate::keymap::Action;
+use crate::platform::{process_identity, ProcessIdentity};
+use crate::{kill_process, App};
+
+// A process sent a signal from the kill menu with "escalate" ticked: if it is still around once
+// the grace period is over, it gets SIGKILL. Lives on the App rather than in an input mode, so
+// closing the menu or opening other popups doesn't drop it.
+pub struct Escalation {
+    pid: u32,
+    identity: ProcessIdentity, // As it was when the first signal went out; a recycled PID is left alone
+    signal: i32, // The signal it ignored, for the report
+    deadline: Instant,
+}
+
+impl App {
+    // 'e' in the kill menu. Stays as set for the rest of the session.
+    pub fn toggle_escalate(&mut self) {
+        self.escalate = !self.escalate;
+    }
+
+    // Right after `signal` reached the process. SIGKILL can't be escalated.
+    pub fn schedule_escalation(&mut self, pid: u32, identity: ProcessIdentity, signal: i32) {
+        if signal == libc::SIGKILL { return; }
+        self.escalations.retain(|e| e.pid != pid);
+        let deadline = Instant::now() + Duration::from_secs(self.config.kill_escalate_secs);
+        self.escalations.push(Escalation { pid, identity, signal, deadline });
+    }
+
+    // Called on every refresh. The identity is checked again right before SIGKILL: the process
+    // may have exited and its PID gone to something else in the meantime.
+    pub fn check_escalations(&mut self) {
+        let now = Instant::now();
+        let mut report = None;
+        let mut pending = std::mem::take(&mut self.escalations);
+        pending.retain(|e| {
+            if process_identity(e.pid).as_ref() != Some(&e.identity) {
+                report = Some(format!("{} (PID {}) exited after {}", e.identity.name, e.pid, self.signal_label(e.signal)));
+                return false;
+            }
+            if now < e.deadline { return true; }
+            report = Some(match kill_process(e.pid, libc::SIGKILL) {
+                Ok(_) => format!("{} (PID {}) ignored {} for {}s; sent SIGKILL", e.identity.name, e.pid, self.signal_label(e.signal), self.config.kill_escalate_secs),
+                Err(err) => format!("SIGKILL to PID {} failed: {}", e.pid, err),
+            });
+            false
+        });
+        self.escalations = pending;
+        if report.is_some() { self.message = report; }
+    }
+
+    pub fn cancel_escalations(&mut self) {
+        self.message = Some(match self.escalations.len() {
+            0 => "No SIGKILL pending".to_string(),
+            1 => format!("SIGKILL to PID {} cancelled", self.escalations[0].pid),
+            n => format!("SIGKILL to {} processes cancelled", n),
+        });
+        self.escalations.clear();
+    }
+
+    // Footer text while a SIGKILL is pending: who gets it, when, and how to stop it.
+    pub fn escalation_hint(&self) -> Option<String> {
+        let next = self.escalations.iter().min_by_key(|e| e.deadline)?;
+        let secs = next.deadline.saturating_duration_since(Instant::now()).as_secs_f64().ceil();
+        let target = match self.escalations.len() {
+            1 => format!("{} (PID {})", next.identity.name, next.pid),
+            n => format!("{} processes", n),
+        };
+        let cancel = self.keymap.label(Action::CancelKill).map(|key| format!(" — {} cancels", key)).unwrap_or_default();
+        Some(format!("[SIGKILL to {} in {:.0}s{}]", target, secs, cancel))
+    }
+}
+
+// They need a real process identity, which only macOS provides.
+#[cfg(all(test, target_os = "macos"))]
+mod tests {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::{Command, Stdio};
+
+    use super::*;
+    use crate::config::Config;
+
+    // A shell that ignores SIGTERM, the way a hung process effectively does.
+    fn stubborn_child() -> std::process::Child {
+        Command::new("sh").args(["-c", "trap '' TERM; while :; do sleep 1; done"]).stdin(Stdio::null()).spawn().unwrap()
+    }
+
+    #[test]
+    fn kills_once_the_grace_period_is_over() {
+        let mut child = stubborn_child();
+        let pid = child.id();
+        let mut app = App::new(&Config { kill_escalate_secs: 0, ..Config::default() });
+        app.schedule_escalation(pid, process_identity(pid).unwrap(), libc::SIGTERM);
+        assert!(app.escalation_hint().is_some());
+        app.check_escalations();
+        assert!(app.escalations.is_empty());
+        assert!(app.message.as_deref().is_some_and(|m| m.contains("sent SIGKILL")), "{:?}", app.message);
+        assert_eq!(child.wait().unwrap().signal(), Some(libc::SIGKILL));
+    }
+
+    #[test]
+    fn recycled_pid_is_left_alone() {
+        let mut child = stubborn_child();
+        let pid = child.id();
+        let mut app = App::new(&Config { kill_escalate_secs: 0, ..Config::default() });
+        let mut identity = process_identity(pid).unwrap();
+        identity.start += 1; // As if another process had held this PID when the first signal went out
+        app.schedule_escalation(pid, identity, libc::SIGTERM);
+        app.check_escalations();
+        assert!(app.escalations.is_empty());
+        assert_eq!(child.try_wait().unwrap(), None);
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
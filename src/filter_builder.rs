@@ -0,0 +1,190 @@
+// Filters built from fields rather than typed: command and user, CPU% and MEM% floors, and a
+// status, all of which have to hold. Applies on top of the '/' filter.
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+use serde::{Deserialize, Serialize};
+
+use crate::process_list::ProcessInfo;
+use crate::{clear_popup, App, InputMode};
+
+// The status field's choices, as (STATE letter, name). "Any" comes before the first.
+const STATUSES: &[(&str, &str)] = &[("R", "running"), ("S", "sleeping"), ("I", "idle"), ("Z", "zombie"), ("T", "stopped")];
+
+const LABELS: [&str; 5] = ["Command contains", "User", "CPU% at least", "MEM% at least", "Status"];
+const STATUS_FIELD: usize = 4;
+
+// As kept in filter presets, blank fields left out.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Filter {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub command: String, // Substring, ignoring case
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub user: String, // Whole name, ignoring case
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_cpu: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_mem: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>, // A name from `STATUSES`: "running", ...
+}
+
+impl Filter {
+    pub fn matches(&self, p: &ProcessInfo) -> bool {
+        (self.command.is_empty() || p.command.to_lowercase().contains(&self.command.to_lowercase()))
+            && (self.user.is_empty() || p.user.eq_ignore_ascii_case(&self.user))
+            && self.min_cpu.is_none_or(|min| p.cpu >= min)
+            && self.min_mem.is_none_or(|min| p.mem >= min)
+            && self.status.as_deref().is_none_or(|name| STATUSES.iter().any(|&(letter, n)| n == name && p.status == letter))
+    }
+
+    // For the footer: "command ~ chrome AND CPU ≥ 10%".
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.command.is_empty() { parts.push(format!("command ~ {}", self.command)); }
+        if !self.user.is_empty() { parts.push(format!("user = {}", self.user)); }
+        if let Some(min) = self.min_cpu { parts.push(format!("CPU ≥ {}%", min)); }
+        if let Some(min) = self.min_mem { parts.push(format!("MEM ≥ {}%", min)); }
+        if let Some(name) = &self.status { parts.push(format!("status {}", name)); }
+        parts.join(" AND ")
+    }
+}
+
+// The popup's fields as typed. Thresholds stay text until applied.
+#[derive(Default)]
+pub struct FilterForm {
+    text: [String; 4], // Command, user, CPU%, MEM%
+    status: Option<usize>,
+    focus: usize,
+}
+
+impl FilterForm {
+    fn from_filter(filter: &Filter) -> Self {
+        let number = |n: Option<f32>| n.map_or(String::new(), |n| n.to_string());
+        FilterForm {
+            text: [filter.command.clone(), filter.user.clone(), number(filter.min_cpu), number(filter.min_mem)],
+            status: filter.status.as_deref().and_then(|name| STATUSES.iter().position(|&(_, n)| n == name)),
+            focus: 0,
+        }
+    }
+
+    // None when every field is blank. A threshold that doesn't parse is an error naming it.
+    fn to_filter(&self) -> Result<Option<Filter>, String> {
+        let number = |i: usize| -> Result<Option<f32>, String> {
+            let text = self.text[i].trim();
+            if text.is_empty() { return Ok(None); }
+            text.parse::<f32>().map(Some).map_err(|_| format!("{}: \"{}\" isn't a number", LABELS[i], text))
+        };
+        let filter = Filter {
+            command: self.text[0].trim().to_string(),
+            user: self.text[1].trim().to_string(),
+            min_cpu: number(2)?,
+            min_mem: number(3)?,
+            status: self.status.map(|i| STATUSES[i].1.to_string()),
+        };
+        Ok((filter != Filter::default()).then_some(filter))
+    }
+
+    // Left/Right on the status field: Any, then each of `STATUSES`, round again.
+    fn cycle_status(&mut self, forward: bool) {
+        let count = STATUSES.len() + 1;
+        let at = self.status.map_or(0, |i| i + 1);
+        let at = if forward { (at + 1) % count } else { (at + count - 1) % count };
+        self.status = at.checked_sub(1);
+    }
+}
+
+impl App {
+    // 'F': the form, filled in with the filter in force.
+    pub fn open_filter_builder(&mut self) {
+        self.filter_form = FilterForm::from_filter(&self.field_filter.clone().unwrap_or_default());
+        self.input_mode = InputMode::FilterBuilder;
+    }
+
+    pub fn set_field_filter(&mut self, filter: Option<Filter>) {
+        self.field_filter = filter;
+        self.rebuild_view();
+        self.state.select(Some(0));
+    }
+
+    pub fn handle_filter_builder_key(&mut self, code: KeyCode) {
+        let form = &mut self.filter_form;
+        match code {
+            KeyCode::Esc => self.input_mode = InputMode::Normal,
+            KeyCode::Enter => match form.to_filter() {
+                Ok(filter) => {
+                    self.input_mode = InputMode::Normal;
+                    self.set_field_filter(filter);
+                }
+                Err(e) => self.message = Some(e),
+            },
+            KeyCode::Tab | KeyCode::Down => form.focus = (form.focus + 1) % LABELS.len(),
+            KeyCode::BackTab | KeyCode::Up => form.focus = (form.focus + LABELS.len() - 1) % LABELS.len(),
+            KeyCode::Left | KeyCode::Right if form.focus == STATUS_FIELD => form.cycle_status(code == KeyCode::Right),
+            KeyCode::Char(' ') if form.focus == STATUS_FIELD => form.cycle_status(true),
+            KeyCode::Backspace if form.focus == STATUS_FIELD => form.status = None,
+            KeyCode::Backspace => { form.text[form.focus].pop(); }
+            // Thresholds only take what a number can be made of.
+            KeyCode::Char(c) if form.focus < 2 || c.is_ascii_digit() || c == '.' => form.text[form.focus].push(c),
+            _ => {}
+        }
+    }
+}
+
+pub fn draw_filter_builder(f: &mut Frame, app: &App, area: Rect) {
+    let form = &app.filter_form;
+    let label_width = LABELS.iter().map(|l| l.len()).max().unwrap_or(0);
+    let (width, height) = (60.min(area.width), (LABELS.len() as u16 + 5).min(area.height));
+    let rect = Rect { x: area.x + (area.width - width) / 2, y: area.y + (area.height - height) / 2, width, height };
+    let mut lines: Vec<Line> = LABELS.iter().enumerate().map(|(i, label)| {
+        let value = if i == STATUS_FIELD {
+            format!("‹ {} ›", form.status.map_or("any", |s| STATUSES[s].1))
+        } else {
+            form.text[i].clone()
+        };
+        let focused = i == form.focus;
+        let cursor = if focused && i != STATUS_FIELD { "_" } else { "" };
+        Line::from(vec![
+            Span::styled(format!("{:<w$}  ", label, w = label_width), if focused { app.theme.selection } else { app.theme.label }),
+            Span::styled(format!("{}{}", value, cursor), if focused { app.theme.input } else { Default::default() }),
+        ])
+    }).collect();
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled("All fields must match. Blank fields match anything.", app.theme.dim)));
+    lines.push(Line::from(Span::styled("Tab/↑/↓ field   ←/→ status   Enter apply   Esc cancel", app.theme.dim)));
+    clear_popup(f, app, rect);
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Filter builder")), rect);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_filled_in_field_has_to_match() {
+        let mut form = FilterForm::default();
+        assert_eq!(form.to_filter(), Ok(None));
+        form.text = ["Chrome".to_string(), "ROOT".to_string(), "10".to_string(), String::new()];
+        form.cycle_status(true);
+        let filter = form.to_filter().unwrap().unwrap();
+        assert_eq!(filter.summary(), "command ~ Chrome AND user = ROOT AND CPU ≥ 10% AND status running");
+
+        let p = ProcessInfo { command: "/Applications/Google Chrome.app".to_string(), user: "root".to_string(), cpu: 12.0, status: "R".to_string(), ..Default::default() };
+        assert!(filter.matches(&p));
+        assert!(!filter.matches(&ProcessInfo { cpu: 9.5, ..p.clone() }));
+        assert!(!filter.matches(&ProcessInfo { status: "S".to_string(), ..p.clone() }));
+        // Reopening the form shows the same filter.
+        assert_eq!(FilterForm::from_filter(&filter).to_filter(), Ok(Some(filter)));
+
+        form.cycle_status(false);
+        form.cycle_status(false);
+        assert_eq!(form.status, Some(STATUSES.len() - 1));
+        form.text[3] = "5.".to_string();
+        assert!(form.to_filter().is_ok());
+        form.text[3] = "..".to_string();
+        assert_eq!(form.to_filter(), Err("MEM% at least: \"..\" isn't a number".to_string()));
+    }
+}
@@ -0,0 +1,137 @@
+// Text formatting for durations and sizes, shared by the columns and the header meters.
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// htop's TIME+ format for CPU time: "M:SS.cc" under an hour, "H:MM:SS" under a day,
+// "Nd HH:MM" beyond that. Wall-clock durations use `format_uptime` instead.
+#[cfg(test)]
+pub fn format_time(t: Duration) -> String {
+    let mut out = String::new();
+    let _ = write_time(&mut out, t);
+    out
+}
+
+pub fn write_time(out: &mut String, t: Duration) -> std::fmt::Result {
+    let secs = t.as_secs();
+    let (mins, hours, days) = (secs / 60, secs / 3600, secs / 86400);
+    if hours == 0 { write!(out, "{}:{:02}.{:02}", mins, secs % 60, t.subsec_millis() / 10) }
+    else if days == 0 { write!(out, "{}:{:02}:{:02}", hours, mins % 60, secs % 60) }
+    else { write!(out, "{}d {:02}:{:02}", days, hours % 24, mins % 60) }
+}
+
+// Short form for narrow columns: "45s", "12m", "2.5h", "3.2d", "41d".
+pub fn write_time_compact(out: &mut String, t: Duration) -> std::fmt::Result {
+    let secs = t.as_secs();
+    if secs < 60 { write!(out, "{}s", secs) }
+    else if secs < 3600 { write!(out, "{}m", secs / 60) }
+    else if secs < 86_400 { write!(out, "{:.1}h", secs as f64 / 3600.0) }
+    else if secs < 10 * 86_400 { write!(out, "{:.1}d", secs as f64 / 86_400.0) }
+    else { write!(out, "{}d", secs / 86_400) }
+}
+
+pub fn format_uptime(secs: u64) -> String {
+    let days = secs / 86400;
+    let hours = (secs % 86400) / 3600;
+    let mins = (secs % 3600) / 60;
+    format!("{} days, {:02}:{:02}", days, hours, mins)
+}
+
+// Compact size for header meters: "512K", "730M", "2.1G".
+pub fn format_bytes(bytes: u64) -> String {
+    const K: u64 = 1024;
+    match bytes {
+        b if b >= K * K * K * K => format!("{:.1}T", b as f64 / (K * K * K * K) as f64),
+        b if b >= K * K * K => format!("{:.1}G", b as f64 / (K * K * K) as f64),
+        b if b >= K * K => format!("{}M", b / (K * K)),
+        b => format!("{}K", b / K),
+    }
+}
+
+// Local wall-clock time of day, "14:05:09".
+pub fn format_clock(t: SystemTime) -> String {
+    match local_time(t) {
+        Some(tm) => format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec),
+        None => "--:--:--".to_string(),
+    }
+}
+
+// Local date and time that sorts as text, for file names: "20240131-170502".
+pub fn format_timestamp(t: SystemTime) -> String {
+    match local_time(t) {
+        Some(tm) => format!("{}{:02}{:02}-{:02}{:02}{:02}", tm.tm_year + 1900, tm.tm_mon + 1, tm.tm_mday, tm.tm_hour, tm.tm_min, tm.tm_sec),
+        None => t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()).to_string(),
+    }
+}
+
+// When a process started, as htop's START column shows it: the time of day, "14:05", within the
+// last day, else the date, "Oct14".
+pub fn write_start(out: &mut String, started: SystemTime, now: SystemTime) -> std::fmt::Result {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let Some(tm) = local_time(started) else { return out.write_str("-") };
+    if now.duration_since(started).is_ok_and(|age| age.as_secs() < 86_400) {
+        write!(out, "{:02}:{:02}", tm.tm_hour, tm.tm_min)
+    } else {
+        write!(out, "{}{:02}", MONTHS[tm.tm_mon.clamp(0, 11) as usize], tm.tm_mday)
+    }
+}
+
+fn local_time(t: SystemTime) -> Option<libc::tm> {
+    let secs = t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    (!unsafe { libc::localtime_r(&secs, &mut tm) }.is_null()).then_some(tm)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_time_under_an_hour_shows_hundredths() {
+        assert_eq!(format_time(Duration::ZERO), "0:00.00");
+        assert_eq!(format_time(Duration::from_millis(59_990)), "0:59.99");
+        assert_eq!(format_time(Duration::from_millis(3_599_990)), "59:59.99");
+    }
+
+    #[test]
+    fn format_time_switches_at_one_hour_and_one_day() {
+        assert_eq!(format_time(Duration::from_secs(3600)), "1:00:00");
+        assert_eq!(format_time(Duration::from_secs(86_399)), "23:59:59");
+        assert_eq!(format_time(Duration::from_secs(86_400)), "1d 00:00");
+        assert_eq!(format_time(Duration::from_secs(100 * 86_400 + 5 * 3600 + 7 * 60)), "100d 05:07");
+    }
+
+    #[test]
+    fn compact_time_picks_the_largest_sensible_unit() {
+        let compact = |secs| { let mut out = String::new(); write_time_compact(&mut out, Duration::from_secs(secs)).unwrap(); out };
+        assert_eq!(compact(45), "45s");
+        assert_eq!(compact(12 * 60), "12m");
+        assert_eq!(compact(9000), "2.5h");
+        assert_eq!(compact(3 * 86_400), "3.0d");
+        assert_eq!(compact(41 * 86_400), "41d");
+    }
+
+    #[test]
+    fn start_shows_the_time_within_a_day_and_the_date_before() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let (mut recent, mut old) = (String::new(), String::new());
+        write_start(&mut recent, now - Duration::from_secs(3600), now).unwrap();
+        assert!(recent.len() == 5 && recent.as_bytes()[2] == b':', "{}", recent);
+        write_start(&mut old, now - Duration::from_secs(3 * 86_400), now).unwrap();
+        assert!(old.len() == 5 && old[..3].chars().all(|c| c.is_ascii_alphabetic()), "{}", old);
+    }
+
+    #[test]
+    fn uptime_always_shows_days() {
+        assert_eq!(format_uptime(0), "0 days, 00:00");
+        assert_eq!(format_uptime(3 * 86_400 + 4 * 3600 + 5 * 60 + 59), "3 days, 04:05");
+    }
+
+    #[test]
+    fn bytes_use_binary_units() {
+        assert_eq!(format_bytes(1023), "0K");
+        assert_eq!(format_bytes(512 * 1024), "512K");
+        assert_eq!(format_bytes(730 * 1024 * 1024), "730M");
+        assert_eq!(format_bytes(2_254_857_830), "2.1G");
+        assert_eq!(format_bytes(3 << 40), "3.0T");
+    }
+}
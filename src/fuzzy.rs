@@ -0,0 +1,77 @@
+// Fuzzy matching for the filter: "chrmhlpr" finds "Google Chrome Helper". A simplified take on
+// fzf's v1 algorithm: the pattern must appear in order (a subsequence), and the score rewards
+// matches at word starts and runs of adjacent matches while charging for the characters skipped.
+
+const MATCH: i32 = 16;
+const BOUNDARY: i32 = 8; // Match at the start of a word, path component or camelCase hump
+const CONSECUTIVE: i32 = 4; // Match right after the previous one
+const GAP: i32 = 1; // Per unmatched character between the first and last match
+
+// Score of `text` against `pattern`, which must already be lowercase; None if it doesn't match.
+// Higher is better. An empty pattern matches everything with 0.
+pub fn score(pattern: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().collect();
+    let matched = positions(pattern, &text)?;
+    let (Some(&first), Some(&last)) = (matched.first(), matched.last()) else { return Some(0) };
+    let mut score = -GAP * (last - first + 1 - matched.len()) as i32;
+    for (n, &i) in matched.iter().enumerate() {
+        score += MATCH;
+        if i == 0 || is_boundary(text[i - 1], text[i]) { score += BOUNDARY; }
+        if n > 0 && matched[n - 1] == i - 1 { score += CONSECUTIVE; }
+    }
+    Some(score)
+}
+
+// Indices into `text` of the characters matching `pattern` (lowercase), in the tightest window:
+// the first place the whole pattern has been seen left to right, then back from there to the
+// latest start.
+pub fn positions(pattern: &str, text: &[char]) -> Option<Vec<usize>> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    if pattern.is_empty() { return Some(Vec::new()); }
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let mut matched = 0;
+    let end = text.iter().position(|&c| {
+        if lower(c) == pattern[matched] { matched += 1; }
+        matched == pattern.len()
+    })?;
+    let mut left = pattern.len();
+    let start = (0..=end).rev().find(|&i| {
+        if lower(text[i]) == pattern[left - 1] { left -= 1; }
+        left == 0
+    })?;
+    let mut next = 0;
+    Some((start..=end).filter(|&i| {
+        let hit = next < pattern.len() && lower(text[i]) == pattern[next];
+        next += hit as usize;
+        hit
+    }).collect())
+}
+
+fn is_boundary(before: char, c: char) -> bool {
+    !before.is_alphanumeric() || (before.is_lowercase() && c.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_subsequences_ignoring_case() {
+        assert!(score("chrmhlpr", "/Applications/Google Chrome.app/Contents/Frameworks/Google Chrome Helper").is_some());
+        assert!(score("wndsrv", "/System/Library/PrivateFrameworks/SkyLight.framework/Resources/WindowServer -daemon").is_some());
+        assert!(score("", "anything") == Some(0));
+        // Order matters.
+        assert!(score("hlprchrm", "Google Chrome Helper").is_none());
+        assert!(score("zsh", "bash").is_none());
+    }
+
+    #[test]
+    fn word_starts_and_runs_rank_higher() {
+        // "ws" at the two word starts beats the same letters in the middle of words.
+        assert!(score("ws", "WindowServer").unwrap() > score("ws", "shadowsocks").unwrap());
+        // An exact run beats the letters scattered.
+        assert!(score("node", "node server.js").unwrap() > score("node", "notification daemon").unwrap());
+        // The tightest window is scored, not the first letter seen.
+        assert_eq!(score("ab", "a----ab"), score("ab", "ab"));
+    }
+}
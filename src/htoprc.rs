@@ -0,0 +1,260 @@
+// One-shot import of htop's own settings for people coming from it. On the first run, when
+// there is no config.toml yet but there is an htoprc, the options that mean something here are
+// carried over and written out as config.toml; from then on that file is the one that counts.
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::process_list::{SortBy, SortOrder};
+use crate::theme::ThemeName;
+
+// Where htop keeps it: $XDG_CONFIG_HOME/htop/htoprc, ~/.config by default.
+pub fn path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("htop").join("htoprc"))
+}
+
+// The config to start with and the line to show about it, when this is the first run and there
+// is an htoprc to import.
+pub fn first_run_import() -> Option<(Config, String)> {
+    if Config::path()?.exists() { return None; }
+    let path = path()?;
+    let text = fs::read_to_string(&path).ok()?;
+    let mut config = Config::default();
+    let imported = apply(&text, &mut config);
+    let mut note = imported.summary(&path.display().to_string());
+    if let Err(e) = config.save() { note.push_str(&format!(" (not saved, so this happens again next time: {})", e)); }
+    Some((config, note))
+}
+
+// What an import carried over, by name, and how many htop options were left behind.
+#[derive(Debug, PartialEq)]
+pub struct Imported {
+    pub applied: Vec<&'static str>,
+    pub ignored: usize,
+}
+
+impl Imported {
+    fn summary(&self, from: &str) -> String {
+        let skipped = match self.ignored {
+            0 => String::new(),
+            1 => "; 1 other setting doesn't apply here".to_string(),
+            n => format!("; {} other settings don't apply here", n),
+        };
+        match self.applied.as_slice() {
+            [] => format!("Nothing to import from {}{}", from, skipped),
+            [only] => format!("Imported {} from {}{}", only, from, skipped),
+            [rest @ .., last] => format!("Imported {} and {} from {}{}", rest.join(", "), last, from, skipped),
+        }
+    }
+}
+
+// Applies htoprc `text` on top of `config`. Lines are `key=value`. Since htop 3.2 the
+// per-screen options follow a `screen:<name>=<columns>` line with a leading dot; only the
+// first screen's (the main one's) are used. Anything not understood is counted, never an error.
+pub fn apply(text: &str, config: &mut Config) -> Imported {
+    let mut applied = Vec::new();
+    let mut ignored = 0;
+    let mut note = |name: &'static str| if !applied.contains(&name) { applied.push(name) };
+    // htop 3.1 started writing `config_reader_min_version`, and also changed what
+    // sort_direction means (see `sort_order`).
+    let absolute_direction = text.lines().any(|line| line.starts_with("config_reader_min_version="));
+    let (mut sort_key, mut direction) = (None, None);
+    let mut screens = 0;
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let Some((key, value)) = line.split_once('=') else { ignored += 1; continue };
+        if key.starts_with("screen:") { screens += 1; continue; }
+        let key = match key.strip_prefix('.') {
+            Some(_) if screens != 1 => continue, // Another screen's
+            Some(key) => key,
+            None => key,
+        };
+        match key {
+            "sort_key" => match sort_field(value) {
+                Some(field) => sort_key = Some(field),
+                None => ignored += 1,
+            },
+            "sort_direction" => direction = value.parse::<i32>().ok(),
+            "tree_view" => {
+                config.tree_view = value == "1";
+                note("tree view");
+            }
+            "highlight_base_name" => {
+                config.highlight_base_name = value == "1";
+                note("base name highlighting");
+            }
+            // Tenths of a second.
+            "delay" => match value.parse::<u64>() {
+                Ok(tenths) => {
+                    config.refresh_ms = tenths * 100;
+                    note("refresh delay");
+                }
+                Err(_) => ignored += 1,
+            },
+            // htop's schemes, nearest first: only Monochromatic has a counterpart that isn't
+            // the default palette.
+            "color_scheme" => {
+                config.theme = if value == "1" { ThemeName::Monochrome } else { ThemeName::Default };
+                note("color scheme");
+            }
+            // Includes hide_kernel_threads: macOS lists no kernel threads, only kernel_task.
+            _ => ignored += 1,
+        }
+    }
+    if let Some(field) = sort_key {
+        config.sort_by = field;
+        let order = sort_order(field, direction.unwrap_or(1), absolute_direction);
+        if order != field.default_order() { config.sort_order.insert(field, order); }
+        note("sort column");
+    }
+    Imported { applied, ignored }
+}
+
+// Up to htop 3.0, 1 meant the column's natural order (most CPU first, lowest PID first) and -1
+// its reverse; from 3.1 on, 1 is ascending and -1 descending whatever the column.
+fn sort_order(field: SortBy, direction: i32, absolute: bool) -> SortOrder {
+    let natural = field.default_order();
+    match (absolute, direction == 1) {
+        (true, true) => SortOrder::Asc,
+        (true, false) => SortOrder::Desc,
+        (false, true) => natural,
+        (false, false) => natural.reversed(),
+    }
+}
+
+// htop's column, by name (3.2's screens) or by number (one less than htop's field ID, for
+// compatibility with its older versions). Columns we can't sort by give None.
+fn sort_field(value: &str) -> Option<SortBy> {
+    let name = match value.parse::<u32>() {
+        Ok(number) => match number + 1 {
+            1 => "PID",
+            2 => "COMM",
+            5 => "PGRP",
+            6 => "SESSION",
+            40 => "M_RESIDENT",
+            47 => "PERCENT_CPU",
+            48 => "PERCENT_MEM",
+            49 => "USER",
+            50 => "TIME",
+            53 => "PERCENT_NORM_CPU",
+            124 => "PROC_COMM",
+            125 => "PROC_EXE",
+            _ => return None,
+        },
+        Err(_) => value,
+    };
+    Some(match name {
+        "PID" => SortBy::Pid,
+        "COMM" | "PROC_COMM" | "PROC_EXE" => SortBy::Command,
+        "PGRP" => SortBy::Pgrp,
+        "SESSION" => SortBy::Sid,
+        "PERCENT_CPU" | "PERCENT_NORM_CPU" => SortBy::Cpu,
+        "PERCENT_MEM" | "M_RESIDENT" => SortBy::Mem,
+        "USER" => SortBy::User,
+        "TIME" => SortBy::Time,
+        "IO_READ_RATE" => SortBy::DiskRead,
+        "IO_WRITE_RATE" => SortBy::DiskWrite,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // As written by htop 2.2 on Linux, after switching to the tree and sorting by memory.
+    const HTOP_2: &str = "\
+# Beware! This file is rewritten by htop when settings are changed in the interface.
+# The parser is also very primitive, and not human-friendly.
+fields=0 48 17 18 38 39 40 2 46 47 49 1
+sort_key=47
+sort_direction=1
+hide_threads=0
+hide_kernel_threads=1
+hide_userland_threads=0
+shadow_other_users=0
+show_thread_names=0
+show_program_path=1
+highlight_base_name=1
+highlight_megabytes=1
+highlight_threads=1
+tree_view=1
+header_margin=1
+detailed_cpu_time=0
+cpu_count_from_zero=0
+update_process_names=0
+account_guest_in_cpu_meter=0
+color_scheme=0
+delay=15
+left_meters=AllCPUs Memory Swap
+left_meter_modes=1 1 1
+right_meters=Tasks LoadAverage Uptime
+right_meter_modes=2 2 2
+";
+
+    // htop 3.3 on macOS: global options, then one block per screen.
+    const HTOP_3: &str = "\
+# Beware! This file is rewritten by htop when settings are changed in the interface.
+# The parser is also very primitive, and not human-friendly.
+htop_version=3.3.0
+config_reader_min_version=3
+fields=0 48 17 18 38 39 40 2 46 47 49 1
+hide_kernel_threads=1
+hide_userland_threads=0
+highlight_base_name=0
+color_scheme=1
+delay=20
+tree_view=0
+sort_key=46
+sort_direction=-1
+screen:Main=PID USER PRIORITY NICE M_VIRT M_RESIDENT STATE PERCENT_CPU PERCENT_MEM TIME Command
+.sort_key=PID
+.tree_sort_key=PID
+.tree_view=0
+.sort_direction=1
+screen:I/O=PID USER IO_PRIORITY IO_RATE IO_READ_RATE IO_WRITE_RATE PERCENT_SWAP_DELAY PERCENT_IO_DELAY Command
+.sort_key=IO_RATE
+.tree_view=1
+.sort_direction=-1
+";
+
+    #[test]
+    fn htop_2_settings_carry_over() {
+        let mut config = Config::default();
+        let imported = apply(HTOP_2, &mut config);
+        assert_eq!(imported.applied, ["base name highlighting", "tree view", "color scheme", "refresh delay", "sort column"]);
+        assert_eq!(imported.ignored, 18);
+        assert!(config.sort_by == SortBy::Mem && config.sort_order.is_empty()); // Most memory first, as in htop
+        assert!(config.tree_view && config.highlight_base_name);
+        assert_eq!(config.theme, ThemeName::Default);
+        assert_eq!(config.refresh_ms, 1500);
+    }
+
+    #[test]
+    fn htop_3_takes_the_main_screen_and_absolute_directions() {
+        let mut config = Config::default();
+        apply(HTOP_3, &mut config);
+        // The Main screen's options win over the global ones; the I/O screen's are left out.
+        assert!(config.sort_by == SortBy::Pid && config.sort_order.is_empty());
+        assert!(!config.tree_view && !config.highlight_base_name);
+        assert_eq!(config.theme, ThemeName::Monochrome);
+        assert_eq!(config.refresh_ms, 2000);
+        // Without the screen block, the global `sort_direction=-1` means descending.
+        let mut config = Config::default();
+        apply(HTOP_3.split("screen:").next().unwrap(), &mut config);
+        assert!(config.sort_by == SortBy::Cpu && config.sort_order.is_empty());
+        assert!(matches!(sort_order(SortBy::Pid, -1, true), SortOrder::Desc));
+    }
+
+    #[test]
+    fn unknown_columns_and_junk_are_only_counted() {
+        let mut config = Config::default();
+        let imported = apply("sort_key=3\nnot an option\nfuture_option=7\n", &mut config);
+        assert_eq!(imported, Imported { applied: Vec::new(), ignored: 3 });
+        assert!(config.sort_by == SortBy::Cpu);
+        assert_eq!(imported.summary("htoprc"), "Nothing to import from htoprc; 3 other settings don't apply here");
+        let two = Imported { applied: vec!["tree view", "color scheme", "refresh delay"], ignored: 1 };
+        assert_eq!(two.summary("htoprc"), "Imported tree view, color scheme and refresh delay from htoprc; 1 other setting doesn't apply here");
+    }
+}
@@ -0,0 +1,314 @@
+// Keys of the main screen, mapped to the actions they trigger. Defaults can be overridden per
+// action from the `[keys]` table of the config file. Popups and the search bar keep their own
+// fixed keys.
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Search,
+    Find, // Jump to matches instead of filtering
+    FindNext,
+    FindPrevious,
+    ClearFilter, // Also dismisses the footer message
+    FilterBuilder, // Popup building a filter from fields
+    FilterPresets, // Saved filters, to apply or save the current one
+    ToggleLeaking, // Show only processes marked as leaking
+    InvertSort,
+    SortPid,
+    SortUser,
+    SortCpu,
+    SortMem,
+    SortTime,
+    SortCpuAvg,
+    SortCommand,
+    SortPorts,
+    SortMemDelta,
+    SortPgrp,
+    SortSid,
+    SortDiskRead,
+    SortDiskWrite,
+    CycleTimeDisplay,
+    CyclePercentDecimals,
+    NarrowCommand, // Minimum width of the COMMAND column
+    WidenCommand,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    Setup,
+    ToggleTree,
+    ToggleTallRows, // Two lines per process
+    ToggleCpuView, // Per-core CPU gauges or one for all cores
+    ExpandRow, // The selected row's whole command, wrapped
+    ToggleCollapse, // Tree view: fold the selected subtree
+    CollapseAll,
+    ExpandAll,
+    NextScreen, // Screens: tabs over the table
+    PreviousScreen,
+    RevealInTree,
+    Details,
+    ProcessMenu, // Everything that can be done to the selected process
+    Kill,
+    KillMatching, // Every process matching the filter
+    CancelKill, // Call off pending SIGKILL escalations
+    Restart, // launchd jobs only
+    RevealInFinder,
+    OpenConsole,
+    TraceSyscalls, // dtruss on the selected process
+    SampleStacks, // `sample`/`spindump` on the selected process
+    SaveStackSample, // The same, straight to a file
+    VmStats, // Popup with the system's VM counters
+    NetworkInterfaces, // Addresses and traffic of each interface
+    Disks, // Space on each mounted volume
+    Sensors, // Temperatures, fans and power rails
+    AlertHistory, // The last alerts that tripped
+    Zombies, // Zombies grouped by the parent that has to reap them
+    DebugOverlay, // Render and refresh timings
+    NextFrame, // --replay only
+    PreviousFrame,
+}
+
+// One key or several, as written in the config: `quit = "q"` or `quit = ["q", "F10"]`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> &[String] {
+        match self {
+            KeyList::One(key) => std::slice::from_ref(key),
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+// Built-in bindings, in the order they are listed (the first key of an action is the one the
+// footer shows). Letters work in either case, as they always have.
+const DEFAULTS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["F10", "q"]),
+    (Action::Search, &["/"]),
+    (Action::Find, &["F3"]),
+    (Action::FindNext, &["n"]),
+    (Action::FindPrevious, &["N"]),
+    (Action::ClearFilter, &["Esc"]),
+    (Action::FilterBuilder, &["F"]),
+    (Action::FilterPresets, &["B"]),
+    (Action::ToggleLeaking, &["L"]),
+    (Action::InvertSort, &["I", "i"]),
+    (Action::SortPid, &["P", "p"]),
+    (Action::SortUser, &["U", "u"]),
+    (Action::SortCpu, &[]),
+    (Action::SortMem, &["M", "m"]),
+    (Action::SortTime, &["T", "t"]),
+    (Action::SortCpuAvg, &["A", "a"]),
+    (Action::SortCommand, &["C", "c"]),
+    (Action::SortPorts, &[]),
+    (Action::SortMemDelta, &[]),
+    (Action::SortPgrp, &[]),
+    (Action::SortSid, &[]),
+    (Action::SortDiskRead, &[]),
+    (Action::SortDiskWrite, &[]),
+    (Action::CycleTimeDisplay, &["E", "e"]),
+    (Action::CyclePercentDecimals, &["%"]),
+    (Action::NarrowCommand, &["<"]),
+    (Action::WidenCommand, &[">"]),
+    (Action::Up, &["Up"]),
+    (Action::Down, &["Down"]),
+    (Action::PageUp, &["PageUp"]),
+    (Action::PageDown, &["PageDown"]),
+    (Action::Home, &["Home"]),
+    (Action::End, &["End"]),
+    (Action::Setup, &["F2"]),
+    (Action::ToggleTree, &["F5"]),
+    (Action::ToggleTallRows, &["z"]),
+    (Action::ToggleCpuView, &["g"]),
+    (Action::ExpandRow, &["Right"]),
+    (Action::ToggleCollapse, &["+", "-"]),
+    (Action::CollapseAll, &["*"]),
+    (Action::ExpandAll, &["="]),
+    (Action::NextScreen, &["Tab"]),
+    (Action::PreviousScreen, &["Shift+Tab"]),
+    (Action::RevealInTree, &["o"]),
+    (Action::Details, &["d"]),
+    (Action::ProcessMenu, &["Enter"]),
+    (Action::Kill, &["F9"]),
+    (Action::KillMatching, &["K"]),
+    (Action::CancelKill, &["x"]),
+    (Action::Restart, &["R"]),
+    (Action::RevealInFinder, &["f"]),
+    (Action::OpenConsole, &["l"]),
+    (Action::TraceSyscalls, &["s"]),
+    (Action::SampleStacks, &["S"]),
+    (Action::SaveStackSample, &[]),
+    (Action::VmStats, &["v"]),
+    (Action::NetworkInterfaces, &["w"]),
+    (Action::Disks, &["D"]),
+    (Action::Sensors, &["H"]),
+    (Action::AlertHistory, &["!"]),
+    (Action::Zombies, &["Z"]),
+    (Action::DebugOverlay, &["F12"]),
+    (Action::NextFrame, &["."]),
+    (Action::PreviousFrame, &[","]),
+];
+
+type Key = (KeyCode, KeyModifiers);
+
+pub struct Keymap {
+    bindings: Vec<(Key, Action)>, // Searched front to back; user bindings come first
+}
+
+impl Keymap {
+    // Defaults with the config's overrides applied. An action listed in the config loses its
+    // default keys. Keys that can't be parsed are skipped and reported.
+    pub fn new(overrides: &BTreeMap<Action, KeyList>) -> (Self, Vec<String>) {
+        let mut bindings = Vec::new();
+        let mut errors = Vec::new();
+        for (&action, keys) in overrides {
+            for name in keys.keys() {
+                match parse_key(name) {
+                    Some(key) => bindings.push((key, action)),
+                    None => errors.push(format!("unknown key '{}'", name)),
+                }
+            }
+        }
+        for &(action, keys) in DEFAULTS {
+            if overrides.contains_key(&action) { continue; }
+            bindings.extend(keys.iter().filter_map(|name| parse_key(name)).map(|key| (key, action)));
+        }
+        (Keymap { bindings }, errors)
+    }
+
+    pub fn action(&self, event: KeyEvent) -> Option<Action> {
+        let key = normalize(event.code, event.modifiers);
+        self.bindings.iter().find(|(k, _)| *k == key).map(|&(_, action)| action)
+    }
+
+    // How to show the first key of `action` in the footer, e.g. "F9" or "'/'".
+    pub fn label(&self, action: Action) -> Option<String> {
+        let ((code, modifiers), _) = self.bindings.iter().find(|(_, a)| *a == action)?;
+        let mut label = String::new();
+        if modifiers.contains(KeyModifiers::CONTROL) { label.push_str("Ctrl+"); }
+        if modifiers.contains(KeyModifiers::ALT) { label.push_str("Alt+"); }
+        if modifiers.contains(KeyModifiers::SHIFT) { label.push_str("Shift+"); }
+        match code {
+            KeyCode::Char(c) if modifiers.is_empty() => label.push_str(&format!("'{}'", c)),
+            KeyCode::Char(c) => label.push(*c),
+            KeyCode::F(n) => label.push_str(&format!("F{}", n)),
+            other => label.push_str(&format!("{:?}", other)),
+        }
+        Some(label)
+    }
+}
+
+// Shift is part of the character already ('P' vs 'p'), and terminals disagree on whether they
+// report it for letters, so it's dropped for character keys.
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Key {
+    let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+    match code {
+        KeyCode::Char(_) => (code, modifiers - KeyModifiers::SHIFT),
+        // Terminals send Shift+Tab as a key of its own.
+        KeyCode::BackTab => (KeyCode::Tab, modifiers | KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+// "q", "F10", "Ctrl+c", "Alt+Enter", "PageDown", ...
+fn parse_key(name: &str) -> Option<Key> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = name;
+    // A lone "+" is a key, not a separator.
+    while let Some((prefix, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty()) {
+        modifiers |= match prefix.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "option" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+        rest = tail;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.to_ascii_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            f => KeyCode::F(f.strip_prefix('f')?.parse().ok().filter(|n| (1..=24).contains(n))?),
+        },
+    };
+    Some(normalize(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_names_and_modifiers() {
+        assert_eq!(parse_key("q"), Some((KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert_eq!(parse_key("F10"), Some((KeyCode::F(10), KeyModifiers::NONE)));
+        assert_eq!(parse_key("ctrl+c"), Some((KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert_eq!(parse_key("Alt+Shift+Enter"), Some((KeyCode::Enter, KeyModifiers::ALT | KeyModifiers::SHIFT)));
+        assert_eq!(parse_key("+"), Some((KeyCode::Char('+'), KeyModifiers::NONE)));
+        assert_eq!(parse_key("Ctrl++"), Some((KeyCode::Char('+'), KeyModifiers::CONTROL)));
+        assert_eq!(parse_key("F25"), None);
+        assert_eq!(parse_key("Hyper+x"), None);
+        assert_eq!(parse_key("nonsense"), None);
+    }
+
+    #[test]
+    fn defaults_match_the_classic_keys() {
+        let (keymap, errors) = Keymap::new(&BTreeMap::new());
+        assert!(errors.is_empty());
+        assert!(keymap.action(press(KeyCode::Char('q'), KeyModifiers::NONE)) == Some(Action::Quit));
+        // Terminals may or may not report Shift along with an uppercase letter.
+        assert!(keymap.action(press(KeyCode::Char('P'), KeyModifiers::SHIFT)) == Some(Action::SortPid));
+        assert!(keymap.action(press(KeyCode::Char('P'), KeyModifiers::NONE)) == Some(Action::SortPid));
+        assert!(keymap.action(press(KeyCode::Char('q'), KeyModifiers::CONTROL)).is_none());
+        // Shift+Tab arrives as BackTab, with or without Shift.
+        assert!(keymap.action(press(KeyCode::BackTab, KeyModifiers::SHIFT)) == Some(Action::PreviousScreen));
+        assert!(keymap.action(press(KeyCode::BackTab, KeyModifiers::NONE)) == Some(Action::PreviousScreen));
+        assert!(keymap.action(press(KeyCode::Tab, KeyModifiers::NONE)) == Some(Action::NextScreen));
+        assert_eq!(keymap.label(Action::Kill).as_deref(), Some("F9"));
+        assert_eq!(keymap.label(Action::Search).as_deref(), Some("'/'"));
+    }
+
+    #[test]
+    fn overrides_replace_an_actions_defaults() {
+        let overrides = BTreeMap::from([
+            (Action::SortCpu, KeyList::One("P".to_string())),
+            (Action::SortPid, KeyList::Many(vec!["#".to_string(), "Bogus".to_string()])),
+        ]);
+        let (keymap, errors) = Keymap::new(&overrides);
+        assert_eq!(errors, vec!["unknown key 'Bogus'".to_string()]);
+        assert!(keymap.action(press(KeyCode::Char('P'), KeyModifiers::NONE)) == Some(Action::SortCpu));
+        assert!(keymap.action(press(KeyCode::Char('p'), KeyModifiers::NONE)).is_none());
+        assert!(keymap.action(press(KeyCode::Char('#'), KeyModifiers::NONE)) == Some(Action::SortPid));
+    }
+}
@@ -0,0 +1,145 @@
+// Friendly names for processes whose command says little at a glance: an interpreter gets the
+// script it runs ("Python: manage.py"), a browser or Electron helper the app it belongs to and
+// its job ("Chrome: GPU"). Heuristics on the command line; None when nothing is recognised.
+
+// Script names too generic to tell projects apart; shown with their directory.
+const GENERIC_SCRIPTS: &[&str] = &[
+    "index.js", "main.js", "cli.js", "app.js", "server.js", "index.mjs", "index.ts", "main.ts",
+    "main.py", "__main__.py", "app.py", "run.py", "main.rb",
+];
+
+// `program` is argv[0], `args` the rest of the command line.
+pub fn describe(program: &str, args: &str, exe: &str) -> Option<String> {
+    let args: Vec<&str> = args.split_whitespace().collect();
+    let base = program.rsplit('/').next().unwrap_or(program);
+    if let Some(label) = webkit(base) { return Some(label.to_string()); }
+    if let Some(role) = helper_role(&args) {
+        // The outermost bundle is the app; nested helper bundles are named after it anyway.
+        let app = app_name(exe).or_else(|| app_name(program)).unwrap_or(base);
+        return Some(format!("{}: {}", app.strip_prefix("Google ").unwrap_or(app), role));
+    }
+    let (language, script) = interpreter(base, &args)?;
+    Some(format!("{}: {}", language, script?))
+}
+
+// Safari's (and every WebKit view's) out-of-process parts.
+fn webkit(base: &str) -> Option<&'static str> {
+    match base.strip_prefix("com.apple.WebKit.")? {
+        "WebContent" => Some("WebKit: Web content"),
+        "Networking" => Some("WebKit: Networking"),
+        "GPU" => Some("WebKit: GPU"),
+        _ => None,
+    }
+}
+
+// Chromium (Chrome, Edge, Brave, every Electron app) passes `--type=`; Firefox puts the kind
+// of content process last.
+fn helper_role(args: &[&str]) -> Option<String> {
+    if let Some(kind) = args.iter().find_map(|a| a.strip_prefix("--type=")) {
+        let sub = args.iter().find_map(|a| a.strip_prefix("--utility-sub-type="));
+        return Some(match (kind, sub) {
+            ("renderer", _) if args.contains(&"--extension-process") => "Extension",
+            ("renderer", _) => "Renderer",
+            ("gpu-process", _) => "GPU",
+            ("utility", Some(sub)) if sub.starts_with("network.") => "Network",
+            ("utility", Some(sub)) if sub.starts_with("storage.") => "Storage",
+            ("utility", Some(sub)) if sub.starts_with("audio.") => "Audio",
+            ("utility", Some(sub)) if sub.starts_with("video_capture.") => "Video capture",
+            ("utility", _) => "Utility",
+            ("crashpad-handler", _) => "Crash reporter",
+            ("zygote", _) => "Zygote",
+            ("ppapi", _) => "Plugin",
+            (other, _) => return Some(other.to_string()),
+        }.to_string());
+    }
+    if !args.contains(&"-contentproc") { return None; }
+    Some(match *args.last()? {
+        "tab" => "Tab",
+        "gpu" => "GPU",
+        "socket" => "Network",
+        "rdd" => "Media decoder",
+        "utility" => "Utility",
+        "extension" => "Extension",
+        other => return Some(other.to_string()),
+    }.to_string())
+}
+
+// "/Applications/Google Chrome.app/Contents/…" -> "Google Chrome".
+fn app_name(path: &str) -> Option<&str> {
+    let end = path.find(".app/")?;
+    Some(path[..end].rsplit('/').next().unwrap_or(&path[..end]))
+}
+
+// (language, what it runs) for a known interpreter. What it runs is None for a REPL or `-c`.
+fn interpreter(base: &str, args: &[&str]) -> Option<(&'static str, Option<String>)> {
+    // "python3.12" and "ruby3.3" are the same languages.
+    let name = base.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    // Flags followed by inline code, and flags followed by some other value.
+    let (language, inline, takes_value): (_, &[&str], &[&str]) = match name {
+        "node" | "nodejs" => ("Node", &["-e", "--eval", "-p", "--print"], &["-r", "--require", "--import", "--loader"]),
+        "deno" => ("Deno", &["eval"], &[]),
+        "bun" => ("Bun", &["-e", "--eval"], &[]),
+        "python" | "Python" => ("Python", &["-c"], &["-W", "-X"]),
+        "ruby" => ("Ruby", &["-e"], &["-I", "-r"]),
+        "perl" => ("Perl", &["-e", "-E"], &["-I"]),
+        "php" => ("PHP", &["-r"], &["-c", "-d"]),
+        "java" => ("Java", &[], &["-cp", "-classpath", "--class-path", "-p", "--module-path"]),
+        "bash" | "zsh" | "sh" | "fish" => ("Shell", &["-c"], &["-o"]),
+        _ => return None,
+    };
+    let mut rest = args.iter();
+    while let Some(&arg) = rest.next() {
+        match arg {
+            _ if inline.contains(&arg) => return Some((language, None)),
+            "-m" if language == "Python" => return Some((language, rest.next().map(|m| m.to_string()))),
+            "-jar" if language == "Java" => return Some((language, rest.next().map(|jar| script_name(jar)))),
+            // Deno and Bun take a subcommand first: `deno run main.ts`.
+            "run" if matches!(language, "Deno" | "Bun") => {}
+            _ if takes_value.contains(&arg) => { rest.next(); }
+            _ if arg.starts_with('-') => {}
+            script => return Some((language, Some(script_name(script)))),
+        }
+    }
+    Some((language, None))
+}
+
+// A script's file name, with its directory when the name alone is generic.
+fn script_name(path: &str) -> String {
+    let mut parts = path.trim_end_matches('/').rsplit('/');
+    let file = parts.next().unwrap_or(path);
+    match parts.next() {
+        Some(dir) if !dir.is_empty() && GENERIC_SCRIPTS.contains(&file) => format!("{}/{}", dir, file),
+        _ => file.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpreters_show_their_script() {
+        assert_eq!(describe("/usr/bin/python3", "manage.py runserver", "").as_deref(), Some("Python: manage.py"));
+        assert_eq!(describe("python3.12", "-u -m http.server 8000", "").as_deref(), Some("Python: http.server"));
+        assert_eq!(describe("node", "--require ts-node/register /srv/api/index.js", "").as_deref(), Some("Node: api/index.js"));
+        assert_eq!(describe("node", "/usr/local/bin/npm run dev", "").as_deref(), Some("Node: npm"));
+        assert_eq!(describe("/usr/bin/java", "-Xmx2g -jar /opt/app/server.jar", "").as_deref(), Some("Java: server.jar"));
+        assert_eq!(describe("deno", "run --allow-net main.ts", "").as_deref(), Some("Deno: main.ts"));
+        // Nothing to add: inline code, a REPL, or not an interpreter at all.
+        assert_eq!(describe("python3", "-c print(1)", ""), None);
+        assert_eq!(describe("node", "", ""), None);
+        assert_eq!(describe("/usr/sbin/sshd", "-D", ""), None);
+    }
+
+    #[test]
+    fn helpers_name_their_app_and_job() {
+        let gpu = "/Applications/Google Chrome.app/Contents/Frameworks/Google Chrome Framework.framework/Versions/1/Helpers/Google Chrome Helper (GPU).app/Contents/MacOS/Google Chrome Helper (GPU)";
+        assert_eq!(describe(gpu, "--type=gpu-process --gpu-preferences=UAAAAAAAAAAgAAAAAAAAAAAAAAAAAAAAAABgAAAAAAA", gpu).as_deref(), Some("Chrome: GPU"));
+        let slack = "/Applications/Slack.app/Contents/Frameworks/Slack Helper.app/Contents/MacOS/Slack Helper";
+        assert_eq!(describe(slack, "--type=utility --utility-sub-type=network.mojom.NetworkService", "").as_deref(), Some("Slack: Network"));
+        assert_eq!(describe(slack, "--type=renderer --extension-process", slack).as_deref(), Some("Slack: Extension"));
+        let firefox = "/Applications/Firefox.app/Contents/MacOS/plugin-container.app/Contents/MacOS/plugin-container";
+        assert_eq!(describe(firefox, "-childID 3 -isForBrowser -prefsLen 3 -contentproc 1234 org.mozilla.machname.1 tab", "").as_deref(), Some("Firefox: Tab"));
+        assert_eq!(describe("/System/Library/Frameworks/WebKit.framework/Versions/A/XPCServices/com.apple.WebKit.WebContent.xpc/Contents/MacOS/com.apple.WebKit.WebContent", "", "").as_deref(), Some("WebKit: Web content"));
+    }
+}
@@ -1,291 +1,1134 @@
-use std::{io, thread, time::Duration, collections::HashMap};
+use std::{borrow::Cow, io::{self, Write}, thread, time::{Duration, Instant}, collections::{BTreeSet, HashMap, HashSet}};
 use std::sync::{Arc, Mutex};
 use std::process::Command;
 
-use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode};
+use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEventKind};
 use crossterm::execute;
-use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle};
 use ratatui::backend::CrosstermBackend;
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::style::{Style, Modifier, Color};
+use ratatui::layout::{Alignment, Constraint, Direction, Flex, Layout, Rect};
+use ratatui::style::{Style, Modifier};
+use ratatui::text::{Line, Span, Text};
 // ADDED: List, ListItem, ListState for the Kill Menu
-use ratatui::widgets::{Block, Borders, Row, Table, TableState, Gauge, Paragraph, Cell, Clear, List, ListItem, ListState};
-use ratatui::Terminal;
-use sysinfo::{System, LoadAvg, ProcessStatus, Cpu};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::{Block, Borders, Row, Table, TableState, Paragraph, Cell, Clear, List, ListItem, ListState, HighlightSpacing, Wrap};
+use ratatui::{Frame, Terminal, TerminalOptions, Viewport};
+use sysinfo::{LoadAvg, Networks};
 use users::get_user_by_uid;
 
-// Enums: SortOrder, SortBy
-#[derive(Clone, Copy)]
-enum SortOrder {
-    Asc,
-    Desc,
-}
+mod accessible;
+mod action_menu;
+mod alerts;
+mod app_group;
+mod bulk_kill;
+mod cli;
+mod collapse;
+mod columns;
+mod config;
+mod debug;
+mod detail;
+mod disks;
+mod escalation;
+mod filter_builder;
+mod keymap;
+mod labels;
+mod format;
+mod fuzzy;
+mod htoprc;
+mod memory_stats;
+mod meters;
+mod navigation;
+mod network;
+mod platform;
+mod presets;
+mod process_list;
+mod restart;
+mod reveal;
+mod sampling;
+mod screens;
+mod sensors;
+mod small_screen;
+mod setup;
+mod source;
+mod theme;
+mod trace;
+mod viewer;
+mod zombies;
+use action_menu::ActionMenu;
+use alerts::Alerts;
+use bulk_kill::BulkKill;
+use cli::{Args, ParseOutcome};
+use columns::{base_name_range, CellFormat, Column, ColumnState, TimeDisplay, MAX_PERCENT_DECIMALS};
+use config::Config;
+use debug::DebugStats;
+use detail::DetailState;
+use escalation::Escalation;
+use keymap::{Action, Keymap};
+use meters::Meter;
+use platform::ProcessIdentity;
+use process_list::{FilterMode, ProcessInfo, SortBy, SortOrder, TaskCounts, TREE_SEPARATOR, UNKNOWN_PARENT};
+use setup::SetupState;
+use source::{DataSource, Extras, Live, Recorder, Replay};
+use theme::{ColorDepth, Theme};
+use sampling::Sampling;
+use screens::Screen;
+use trace::Trace;
+use viewer::TextViewer;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum SortBy {
-    PID,
-    User,
-    CPU,
-    MEM,
-    Time,
-    Command,
-}
+// Event poll timeouts: short while the user is interacting, long once idle.
+const ACTIVE_POLL: Duration = Duration::from_millis(250);
+const IDLE_POLL: Duration = Duration::from_millis(1000);
+const IDLE_AFTER: Duration = Duration::from_secs(2);
+// With --no-altscreen: the header, a few rows of the table and the footer.
+const INLINE_MIN_ROWS: u16 = 16;
+// Popups below this many lines get all of them.
+const POPUP_FULL_HEIGHT: u16 = 25;
+// Nor are popups made smaller than this, width by height, where the screen has the room.
+const POPUP_MIN: (u16, u16) = (40, 10);
+// Two clicks on the same spot within this long are a double-click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+// Data older than this many refresh intervals gets the STALE DATA banner.
+const STALE_AFTER_INTERVALS: u32 = 3;
 
 // MODIFIED: Added KillMenu mode
 #[derive(PartialEq)]
 enum InputMode {
     Normal,
     Search,
+    Find, // Typing a query that moves the selection instead of filtering
     KillMenu,
+    ActionMenu, // Everything that can be done to the selected process
+    ConfirmKill, // Extra confirmation before signalling a protected process
+    ConfirmBulkKill, // Confirmation before signalling everything matching the filter
+    ConfirmRestart, // Confirmation before restarting a launchd job
+    Setup,
+    Detail, // Detail pane for one process
+    VmStats, // System-wide VM counters
+    Network, // Network interfaces
+    Disks, // Mounted volumes
+    Sensors, // Temperatures, fans and power
+    FilterBuilder, // Filter by fields rather than text
+    Presets, // Saved filters
+    AlertHistory, // The last alerts that tripped
+    Trace, // dtruss output for one process
+    Viewer, // Long text output, e.g. a stack sample
+    Zombies, // Zombies by the parent that has to reap them
 }
 
 // Struct: App - Modified to add tree view and kill menu state
 struct App {
-    processes: Vec<ProcessInfo>,
+    processes: HashMap<u32, ProcessInfo>, // Keyed by PID, updated in place each refresh
+    task_counts: TaskCounts, // Summary of `processes`, computed together with it
+    sorted_pids: Vec<u32>, // Current sort order of `processes`
+    sorted_upto: usize, // How many of `sorted_pids` are in order; fewer than all only while `top_n` caps the list
+    count_warned: bool, // The note about `process_count_warn` was shown; it isn't repeated
+    cmd_buf: String, // Scratch buffer for joining argv without allocating per process
+    user_names: UserNameCache,
+    columns: Vec<ColumnState>, // Displayed columns, in order, with their fitted widths
+    cell_format: CellFormat,
+    view: Vec<(usize, u32)>, // Rows as displayed: (tree depth, PID). Rebuilt when data, sort, filter or view mode change
     state: TableState,
     sort_by: SortBy,
     sort_order: SortOrder,
     cpus: Vec<f32>,
+    cpu_freqs: Vec<u64>, // MHz per core, empty when unknown
     mem_usage: f64,
+    show_mem_breakdown: bool, // Config: stacked wired/active/inactive/compressed memory bar
+    vm: Option<platform::VmStats>, // Only sampled while the breakdown meter or the 'v' popup shows it
+    vm_previous: Option<(platform::VmStats, Duration)>, // The sample before `vm` and how long before it
+    vm_sampled_at: Option<Instant>,
+    network: network::NetworkScreen, // Only sampled while the 'w' screen is open
+    disks: disks::DiskScreen, // Likewise for 'D'
+    sensors: sensors::SensorScreen, // Read in the background from launch on
     total_mem: u64,
     used_mem: u64,
     swap_usage: f64,
     total_swap: u64,
     used_swap: u64,
+    memory_pressure: Option<platform::MemoryPressure>,
+    cpu_split: Option<source::CpuSplit>, // User/system/idle over the last refresh interval
+    alerts: Alerts,
     message: Option<String>,
     uptime: u64,
     load_avg: LoadAvg,
     input_mode: InputMode,
     search_query: String,
     active_filter: Option<String>,
+    filter_mode: FilterMode, // Toggled with Tab while typing the filter
+    field_filter: Option<filter_builder::Filter>, // Built with 'F'; applies along with `active_filter`
+    filter_form: filter_builder::FilterForm,
+    preset_menu: presets::PresetMenu,
+    zombie_selected: usize, // In the zombies popup
+    leaking_only: bool, // "Show leaking": the list keeps just processes marked as leaking
+    follow_pids: BTreeSet<u32>, // --pid: only these and their descendants are shown
+    follow_missing: BTreeSet<u32>, // Those of `follow_pids` last reported as not running
+    find_query: String, // Being typed in Find mode
+    last_find: Option<String>, // Repeated by find next/previous
     tree_view: bool, // ADDED
+    stopped_apps: HashSet<String>, // Bundles stopped as a whole from the action menu, for their marker; see `app_group`
+    collapsed: HashSet<u32>, // Tree view: processes whose descendants are folded away; see `collapse`
+    screens: Vec<Screen>, // Tabs over the table; see `screens`
+    screen: usize, // Index of the one shown, whose settings are in the fields above
+    cpu_view: meters::CpuView, // 'g': per-core gauges or one for all cores
+    compact: bool, // Drawn in the small-terminal layout last time: one header line, fewer columns
+    tall_rows: bool, // Two lines per process: the second carries on the command, or shows the executable
+    expanded: Option<u32>, // PID of the row showing its whole command, wrapped; only ever the selected one
     kill_menu_state: ListState, // ADDED
     kill_signals: Vec<(&'static str, i32)>, // ADDED
+    kill_default_signal: i32, // Signal pre-selected when the kill menu first opens
+    last_signal_idx: Option<usize>, // Entry of `kill_signals` last picked, pre-selected after that
+    read_only: bool, // --read-only: process actions are refused
+    accessible: bool, // --accessible: plain output for screen readers; see `accessible`
+    pending_kill: Option<(u32, i32, bool)>, // (PID, signal, escalate) awaiting confirmation in ConfirmKill
+    escalate: bool, // Kill menu checkbox: follow up with SIGKILL if the process is still there later
+    escalations: Vec<Escalation>, // Processes due a SIGKILL unless they exit first
+    kill_target: Option<(u32, ProcessIdentity)>, // The process the kill menu was opened on, as it was then
+    kill_menu_bulk: bool, // The kill menu is choosing a signal for all processes matching the filter
+    pending_bulk: Option<BulkKill>,
+    pending_restart: Option<(u32, String)>, // (PID, launchctl service target) awaiting confirmation
+    action_menu: Option<ActionMenu>,
+    keymap: Keymap,
+    theme: Theme,
+    config: Config, // Settings as loaded; updated and saved when changed from inside the app
+    header_left: Vec<Meter>, // Header meters, left column
+    header_right: Vec<Meter>, // Header meters, right column
+    setup: SetupState,
+    detail: DetailState,
+    trace: Option<Trace>, // Running (or finished) in-app dtruss
+    sampling: Option<Sampling>, // Stack sample being taken in the background
+    viewer: Option<TextViewer>,
+    debug: DebugStats, // F12 overlay
+    battery: Option<platform::Battery>,
+    frontmost: Option<u32>, // PID of the frontmost app, highlighted in the table
+    networks: Option<Networks>, // Only created once a Network meter is configured
+    net_rate: Option<(u64, u64)>, // Bytes per second received / transmitted, all interfaces
+    last_refresh: Option<Instant>,
+    since_refresh: Option<f64>, // Seconds between the last two refreshes, for the rates; None after the first
+    stale_shown: bool, // Whether the last frame carried the STALE DATA banner
+    replay: Option<Replay>, // --replay: frames are stepped through by key instead of sampled
+    dirty: bool, // Set whenever something visible changed; the UI skips drawing while false
+    mouse_pos: Option<(u16, u16)>, // Last known pointer position (column, row)
+    last_click: Option<(Instant, u16, u16)>, // For telling double-clicks
+    popup_area: std::cell::Cell<Option<Rect>>, // Screen area of the open popup, from the last draw; see `clear_popup`
+    kill_menu_list: Rect, // Screen area of the kill menu's signals, from the last draw
+    table_body: Rect, // Screen area of the table rows (below the header), from the last draw
+    command_column: (u16, u16), // Screen x and width of the COMMAND column, from the last draw
+    expanded_drawn: Option<(usize, u16)>, // Row index and height of the expanded row, from the last draw
 }
 
-// Struct: ProcessInfo - No changes
-struct ProcessInfo {
-    pid: u32,
-    ppid: u32,
-    user: String,
-    status: String,
-    cpu: f32,
-    mem: f32,
-    virtual_mem: u64,
-    cpu_time: u64,
-    command: String,
+// Span of the ΔMEM column.
+const MEM_HISTORY: Duration = Duration::from_secs(60);
+
+// Bounds for '<'/'>' resizing COMMAND, and the step per key press.
+const MIN_COMMAND_WIDTH: u16 = 10;
+const MIN_SQUEEZED_WIDTH: u16 = 3; // What every other column keeps when COMMAND grows
+const COMMAND_RESIZE_STEP: i32 = 5;
+
+// How long an unresolvable UID is shown numerically before we ask the directory again.
+const USER_LOOKUP_RETRY: Duration = Duration::from_secs(30);
+
+// UID -> user name, shared by all processes across refreshes. Resolving names goes through
+// Open Directory and is far too slow to repeat for every process on every tick.
+#[derive(Default)]
+struct UserNameCache {
+    // A failed lookup stores the numeric UID plus when we tried, so it gets retried
+    // later (directory services can come up after we do) instead of sticking forever.
+    names: HashMap<u32, (String, Option<Instant>)>,
+}
+
+impl UserNameCache {
+    fn get(&mut self, uid: u32) -> &str {
+        let stale = match self.names.get(&uid) {
+            Some((_, Some(failed_at))) => failed_at.elapsed() >= USER_LOOKUP_RETRY,
+            Some((_, None)) => false,
+            None => true,
+        };
+        if stale {
+            let entry = match get_user_by_uid(uid) {
+                Some(user) => (user.name().to_string_lossy().into_owned(), None),
+                None => (uid.to_string(), Some(Instant::now())),
+            };
+            self.names.insert(uid, entry);
+        }
+        &self.names[&uid].0
+    }
 }
 
 // impl App - Modified to handle new state and logic
 impl App {
-    fn new() -> Self {
-        // These are common signals. 15 is polite, 9 is forceful.
-        let signals = vec![
-            (" 1 SIGHUP", 1), (" 2 SIGINT", 2), (" 9 SIGKILL", 9),
-            ("15 SIGTERM", 15), ("20 SIGTSTP", 20), ("24 SIGXCPU", 24),
+    fn new(config: &Config) -> Self {
+        // These are common signals. 15 is polite, 9 is forceful. Numbers differ between macOS and
+        // Linux (SIGTSTP, SIGCHLD), so they come from libc rather than being written out.
+        let mut signals = vec![
+            ("SIGHUP", libc::SIGHUP), ("SIGINT", libc::SIGINT), ("SIGKILL", libc::SIGKILL), ("SIGTERM", libc::SIGTERM),
+            ("SIGCHLD", libc::SIGCHLD), ("SIGTSTP", libc::SIGTSTP), ("SIGXCPU", libc::SIGXCPU),
         ];
-        let mut kill_menu_state = ListState::default();
-        kill_menu_state.select(Some(0)); // Select the first signal by default
+        signals.sort_by_key(|&(_, sig)| sig);
+        let (keymap, key_errors) = Keymap::new(&config.keys);
 
         Self {
-            processes: Vec::new(),
+            processes: HashMap::new(),
+            task_counts: TaskCounts::default(),
+            sorted_pids: Vec::new(),
+            sorted_upto: 0,
+            count_warned: false,
+            cmd_buf: String::new(),
+            user_names: UserNameCache::default(),
+            columns: config.columns.iter().map(|&c| ColumnState::new(c, &config.column_widths)).collect(),
+            cell_format: CellFormat {
+                percent_decimals: config.percent_decimals.min(MAX_PERCENT_DECIMALS),
+                path_column: config.columns.contains(&Column::Path),
+                ..Default::default()
+            },
+            view: Vec::new(),
             state: TableState::default(),
-            sort_by: SortBy::CPU,
-            sort_order: SortOrder::Desc,
+            sort_by: config.sort_by,
+            sort_order: config.sort_order_for(config.sort_by),
             cpus: Vec::new(),
+            cpu_freqs: Vec::new(),
             mem_usage: 0.0,
+            show_mem_breakdown: config.memory_breakdown,
+            vm: None,
+            vm_previous: None,
+            vm_sampled_at: None,
+            network: network::NetworkScreen::default(),
+            disks: disks::DiskScreen::default(),
+            sensors: sensors::SensorScreen::default(),
             total_mem: 0,
             used_mem: 0,
             swap_usage: 0.0,
             total_swap: 0,
             used_swap: 0,
-            message: None,
+            memory_pressure: None,
+            cpu_split: None,
+            alerts: Alerts::default(),
+            message: (!key_errors.is_empty()).then(|| format!("Config [keys]: {}", key_errors.join(", "))),
             uptime: 0,
             load_avg: LoadAvg { one: 0.0, five: 0.0, fifteen: 0.0 },
             input_mode: InputMode::Normal,
             search_query: String::new(),
             active_filter: None,
-            tree_view: false,
-            kill_menu_state,
+            filter_mode: FilterMode::Plain,
+            field_filter: None,
+            filter_form: filter_builder::FilterForm::default(),
+            preset_menu: presets::PresetMenu::default(),
+            zombie_selected: 0,
+            leaking_only: false,
+            follow_pids: BTreeSet::new(),
+            stopped_apps: HashSet::new(),
+            follow_missing: BTreeSet::new(),
+            find_query: String::new(),
+            last_find: None,
+            tree_view: config.tree_view,
+            collapsed: HashSet::new(),
+            screens: std::iter::once(Screen::main()).chain(config.screens.iter().map(|s| Screen::new(s, config))).collect(),
+            screen: 0,
+            cpu_view: meters::CpuView::default(),
+            compact: false,
+            tall_rows: config.tall_rows,
+            expanded: None,
+            kill_menu_state: ListState::default(),
             kill_signals: signals,
+            kill_default_signal: config.kill_default_signal,
+            last_signal_idx: None,
+            read_only: false,
+            accessible: false,
+            pending_kill: None,
+            escalate: config.kill_escalate,
+            escalations: Vec::new(),
+            kill_target: None,
+            kill_menu_bulk: false,
+            pending_bulk: None,
+            pending_restart: None,
+            action_menu: None,
+            keymap,
+            theme: Theme::new(config.theme, ColorDepth::Ansi16),
+            config: config.clone(),
+            header_left: config.header_left.clone(),
+            header_right: config.header_right.clone(),
+            setup: SetupState::default(),
+            detail: DetailState::default(),
+            trace: None,
+            sampling: None,
+            viewer: None,
+            debug: DebugStats::default(),
+            battery: None,
+            frontmost: None,
+            networks: None,
+            net_rate: None,
+            last_refresh: None,
+            since_refresh: None,
+            stale_shown: false,
+            replay: None,
+            dirty: true,
+            mouse_pos: None,
+            last_click: None,
+            popup_area: std::cell::Cell::new(None),
+            kill_menu_list: Rect::default(),
+            table_body: Rect::default(),
+            command_column: (0, 0),
+            expanded_drawn: None,
         }
     }
 
-    fn update_data(&mut self, sys: &mut System) {
-        // This method remains the same
-        sys.refresh_all();
-        sys.refresh_cpu();
-        sys.refresh_memory();
-
-        self.uptime = sysinfo::System::uptime();
-        self.load_avg = sysinfo::System::load_average();
-        self.cpus = sys.cpus().iter().map(Cpu::cpu_usage).collect();
-        self.total_mem = sys.total_memory();
-        self.used_mem = sys.used_memory();
-        self.total_swap = sys.total_swap();
-        self.used_swap = sys.used_swap();
+    // Takes in a sample the caller already refreshed. Sampling can be slow (or hang, under heavy
+    // swapping), so it's done without holding the App lock; the UI keeps running meanwhile.
+    fn update_data(&mut self, source: &mut dyn DataSource) {
+        let sample = source.system();
+        self.uptime = sample.uptime;
+        let [one, five, fifteen] = sample.load_avg;
+        self.load_avg = LoadAvg { one, five, fifteen };
+        self.cpus.clone_from(&sample.cpus);
+        self.cpu_freqs.clone_from(&sample.cpu_freqs);
+        self.total_mem = sample.total_mem;
+        self.used_mem = sample.used_mem;
+        self.total_swap = sample.total_swap;
+        self.used_swap = sample.used_swap;
+        self.memory_pressure = sample.memory_pressure;
+        self.cpu_split = sample.cpu_split;
+        self.battery.clone_from(&sample.battery);
+        self.sample_vm();
         self.mem_usage = if self.total_mem > 0 { (self.used_mem as f64 / self.total_mem as f64) * 100.0 } else { 0.0 };
         self.swap_usage = if self.total_swap > 0 { (self.used_swap as f64 / self.total_swap as f64) * 100.0 } else { 0.0 };
 
+        self.since_refresh = self.last_refresh.map(|t| t.elapsed().as_secs_f64());
+        self.last_refresh = Some(Instant::now());
+        self.update_meter_sources();
+        self.sample_interfaces();
+        self.sample_disks();
+        self.update_processes(source);
+        self.frontmost = source.frontmost_pid();
+        self.fit_columns();
+        self.check_alerts();
+        self.check_escalations();
+        self.check_followed();
+        self.check_process_count();
+        if self.input_mode == InputMode::Detail { self.refresh_detail(source); }
+        if let Some(e) = source.take_error() { self.message = Some(e); }
+        self.dirty = true;
+    }
+
+    // How long ago the data on screen was sampled, if that's long enough that the refresh thread
+    // must be stuck. Replays never go stale: they only change on request.
+    fn staleness(&self) -> Option<Duration> {
+        let age = self.last_refresh?.elapsed();
+        (self.replay.is_none() && age > self.config.refresh_interval() * STALE_AFTER_INTERVALS).then_some(age)
+    }
+
+    // True until the collector thread delivers its first sample; until then the meters and the
+    // table show placeholders instead of zeros.
+    fn collecting(&self) -> bool {
+        self.last_refresh.is_none()
+    }
+
+    // Shows another frame of a --replay recording; 0 (re)loads the current one.
+    fn step_replay(&mut self, delta: isize) {
+        let Some(mut replay) = self.replay.take() else { return };
+        if replay.step(delta) || delta == 0 {
+            self.update_data(&mut replay);
+        }
+        let (frame, frames) = replay.position();
+        self.message = Some(format!("Replay frame {}/{}", frame, frames));
+        self.replay = Some(replay);
+    }
+
+    // Adds or drops `auto_columns` for a terminal this wide.
+    fn reveal_columns(&mut self, width: u16) {
+        let configured: Vec<Column> = self.columns.iter().filter(|c| !c.auto).map(|c| c.column).collect();
+        let revealed = columns::revealed(width, &configured, &self.config.auto_columns, &self.config.hidden_columns);
+        if self.columns.iter().filter(|c| c.auto).map(|c| c.column).eq(revealed.iter().copied()) { return; }
+        self.columns.retain(|c| !c.auto);
+        let at = self.columns.iter().position(|c| c.column == Column::Command).unwrap_or(self.columns.len());
+        let added: Vec<ColumnState> = revealed.into_iter().map(|c| ColumnState::auto(c, &self.config.column_widths)).collect();
+        self.columns.splice(at..at, added);
+        self.fit_columns();
+    }
+
+    fn fit_columns(&mut self) {
+        let mut scratch = std::mem::take(&mut self.cmd_buf);
+        for column in &mut self.columns {
+            column.fit(self.processes.values(), self.cell_format, self.accessible, &mut scratch);
+        }
+        self.cmd_buf = scratch;
+    }
+
+    fn cycle_time_display(&mut self) {
+        self.cell_format.time = self.cell_format.time.next();
+        self.fit_columns();
+        self.sort_processes();
+    }
+
+    // 0 → 1 → 2 → 0 decimal places for the percentage columns.
+    fn cycle_percent_decimals(&mut self) {
+        self.cell_format.percent_decimals = (self.cell_format.percent_decimals + 1) % (MAX_PERCENT_DECIMALS + 1);
+        self.fit_columns();
+        self.message = Some(format!("Percentages with {} decimal places", self.cell_format.percent_decimals));
+    }
+
+    // '<'/'>': trades COMMAND's minimum width against the other columns, and saves it. The
+    // other columns keep at least a few cells each.
+    fn resize_command_column(&mut self, delta: i32) {
+        let others = self.columns.len().saturating_sub(1) as u16;
+        let max = self.table_body.width.saturating_sub(3 + others * (MIN_SQUEEZED_WIDTH + 1)).max(MIN_COMMAND_WIDTH);
+        let Some(command) = self.columns.iter_mut().find(|c| c.column == Column::Command) else { return };
+        let width = (command.min_width() as i32 + delta).clamp(MIN_COMMAND_WIDTH as i32, max as i32) as u16;
+        let limits = command.set_min_width(width).clone();
+        self.config.column_widths.insert(Column::Command.key().to_string(), limits);
+        self.message = Some(match self.config.save() {
+            Ok(()) => format!("COMMAND at least {} wide", width),
+            Err(e) => format!("Could not save column width: {}", e),
+        });
+    }
+
+    // Data that only some meters need is only collected while such a meter is shown.
+    fn update_meter_sources(&mut self) {
+        let shown = |meter: Meter| self.header_left.contains(&meter) || self.header_right.contains(&meter);
+        if shown(Meter::Network) {
+            let networks = self.networks.get_or_insert_with(Networks::new_with_refreshed_list);
+            networks.refresh();
+            let (rx, tx) = networks.iter().fold((0, 0), |(rx, tx), (_, data)| (rx + data.received(), tx + data.transmitted()));
+            // The first refresh has nothing to compare against.
+            self.net_rate = self.since_refresh.filter(|&e| e > 0.0).map(|e| ((rx as f64 / e) as u64, (tx as f64 / e) as u64));
+        } else {
+            // The network screen uses them too.
+            if self.input_mode != InputMode::Network { self.networks = None; }
+            self.net_rate = None;
+        }
+    }
+
+    // Updates `processes` in place: existing entries keep their String buffers and only
+    // get rewritten when a value actually changed, new PIDs are inserted, exited ones dropped.
+    fn update_processes(&mut self, source: &mut dyn DataSource) {
         let num_cpus = self.cpus.len() as f32;
-        let mut procs: Vec<ProcessInfo> = sys.processes().values().map(|p| {
-            ProcessInfo {
-                pid: p.pid().as_u32(),
-                ppid: p.parent().map(|pid| pid.as_u32()).unwrap_or(0),
-                user: p.user_id().and_then(|uid| get_user_by_uid(**uid)).map(|u| u.name().to_string_lossy().into_owned()).unwrap_or_else(|| "?".to_string()),
-                status: status_to_string(p.status()),
-                cpu: p.cpu_usage() / num_cpus.max(1.0),
-                mem: (p.memory() as f64 / self.total_mem as f64 * 100.0) as f32,
-                virtual_mem: p.virtual_memory(),
-                cpu_time: p.run_time(),
-                command: if !p.cmd().is_empty() { p.cmd().join(" ") } else { p.name().to_string() },
+        let mem_history_samples = (MEM_HISTORY.as_millis() / self.config.refresh_interval().as_millis()) as usize + 1;
+        let leak_samples = self.config.leak_samples();
+        // A replay is stepped through at any pace, but its frames were taken an interval apart.
+        let sampled_apart = self.since_refresh.map(|e| if self.replay.is_some() { self.config.refresh_interval().as_secs_f64() } else { e });
+        for info in self.processes.values_mut() { info.seen = false; }
+        source.for_each_process(&mut |p| {
+            let pid = p.pid;
+            let info = self.processes.entry(pid).or_insert_with(|| ProcessInfo { pid, user: "?".to_string(), ..Default::default() });
+            info.seen = true;
+            info.ppid = p.ppid.unwrap_or(0);
+            info.uid = p.uid;
+            set_if_changed(&mut info.user, info.uid.map(|uid| self.user_names.get(uid)).unwrap_or("?"));
+            set_if_changed(&mut info.name, p.name);
+            set_if_changed(&mut info.status, &p.status);
+            info.push_cpu(p.cpu_usage / num_cpus.max(1.0), self.config.smooth_cpu_samples, self.config.smooth_cpu);
+            info.mem = (p.memory as f64 / self.total_mem as f64 * 100.0) as f32;
+            info.virtual_mem = p.virtual_memory;
+            info.resident = p.memory;
+            info.push_resident(mem_history_samples, leak_samples);
+            info.run_time = p.run_time;
+            info.cpu_time = p.cpu_time;
+            info.threads = p.threads;
+            info.push_disk_io(p.disk_read, p.disk_written, sampled_apart);
+            info.footprint = p.footprint;
+            info.ports = p.ports;
+            (info.pgid, info.sid) = (p.group.map(|g| g.pgid), p.group.and_then(|g| g.sid));
+            // A process that has been busy its whole life scores close to its CPU%,
+            // one that only spiked once scores low.
+            info.cpu_avg = info.cpu_time.map(|t| {
+                if info.run_time == 0 { 0.0 } else { (t.as_secs_f64() / info.run_time as f64 * 100.0) as f32 / num_cpus.max(1.0) }
+            });
+            self.cmd_buf.clear();
+            if !p.cmd.is_empty() {
+                for (i, arg) in p.cmd.iter().enumerate() {
+                    if i > 0 { self.cmd_buf.push(' '); }
+                    self.cmd_buf.push_str(arg);
+                }
+            } else {
+                self.cmd_buf.push_str(p.name);
             }
-        }).collect();
-
-        procs.sort_by(|a, b| {
-            let ordering = match self.sort_by {
-                SortBy::PID => a.pid.cmp(&b.pid),
-                SortBy::User => a.user.cmp(&b.user),
-                SortBy::CPU => a.cpu.partial_cmp(&b.cpu).unwrap(),
-                SortBy::MEM => a.mem.partial_cmp(&b.mem).unwrap(),
-                SortBy::Time => a.cpu_time.cmp(&b.cpu_time),
-                SortBy::Command => a.command.cmp(&b.command),
-            };
-            match self.sort_order {
-                SortOrder::Asc => ordering,
-                SortOrder::Desc => ordering.reverse(),
+            let mut changed = set_if_changed(&mut info.command, &self.cmd_buf);
+            info.args_start = p.cmd.first().filter(|_| p.cmd.len() > 1).map_or(info.command.len(), |program| program.len() + 1);
+            changed |= set_if_changed(&mut info.exe, p.exe.unwrap_or(""));
+            if changed {
+                let (program, args) = info.command.split_at(info.args_start);
+                info.label = labels::describe(program.trim_end(), args, &info.exe);
             }
         });
-        self.processes = procs;
+        self.processes.retain(|_, info| info.seen);
+        self.collapsed.retain(|pid| self.processes.contains_key(pid));
+        self.task_counts = TaskCounts::from_processes(self.processes.values());
+        self.sort_processes();
     }
 
-    // ADDED BACK: Methods for tree view
-    fn tree_ordered_processes(&self) -> Vec<(usize, &ProcessInfo)> {
-        let mut pid_map: HashMap<u32, Vec<&ProcessInfo>> = HashMap::new();
-        let mut root_procs: Vec<&ProcessInfo> = Vec::new();
+    // Per-process counters the live source should collect for what is on screen.
+    fn extras(&self) -> Extras {
+        let shown = |column: Column| self.columns.iter().any(|c| c.column == column);
+        let task = [Column::Time, Column::CpuAvg, Column::Threads].into_iter().any(shown)
+            || matches!(self.sort_by, SortBy::Time | SortBy::CpuAvg)
+            || self.header_left.contains(&Meter::Tasks) || self.header_right.contains(&Meter::Tasks)
+            || self.input_mode == InputMode::Detail;
+        Extras {
+            footprint: shown(Column::Footprint),
+            ports: shown(Column::Ports),
+            group: shown(Column::Pgrp) || shown(Column::Sid),
+            task,
+            detail: self.detail_pid(),
+            battery: self.header_left.contains(&Meter::Battery) || self.header_right.contains(&Meter::Battery),
+        }
+    }
 
-        // Create a set of all PIDs for quick lookups
-        let all_pids: HashMap<u32, ()> = self.processes.iter().map(|p| (p.pid, ())).collect();
+    fn sort_processes(&mut self) {
+        self.sorted_pids.clear();
+        self.sorted_pids.extend(self.processes.keys().copied());
+        self.sorted_upto = 0;
+        self.rebuild_view();
+    }
 
-        for proc in &self.processes {
-            // A process is a root if its parent ID is 0, or if its parent ID does not exist in our list of processes.
-            if proc.ppid == 0 || !all_pids.contains_key(&proc.ppid) {
-                root_procs.push(proc);
-            } else {
-                pid_map.entry(proc.ppid).or_default().push(proc);
-            }
+    // How many rows the flat list is capped to by `top_n`, when that applies: not with a filter,
+    // --pid or "show leaking", which need every process to choose from, nor in the tree.
+    fn top_n(&self) -> Option<usize> {
+        let n = self.config.top_n;
+        let narrowed = self.active_filter.is_some() || self.field_filter.is_some() || self.leaking_only || !self.follow_pids.is_empty();
+        (n > 0 && n < self.processes.len() && !self.tree_view && !narrowed).then_some(n)
+    }
+
+    // Brings `sorted_pids` into order, as far as the view needs it.
+    fn ensure_sorted(&mut self) {
+        let needed = self.top_n().unwrap_or(self.sorted_pids.len());
+        if self.sorted_upto >= needed { return; }
+        // Sort by whichever time the column is showing.
+        let by_elapsed = self.cell_format.time == TimeDisplay::Elapsed;
+        if needed < self.sorted_pids.len() {
+            process_list::sort_top_pids(&mut self.sorted_pids, needed, &self.processes, self.sort_by, self.sort_order, by_elapsed);
+        } else {
+            process_list::sort_pids(&mut self.sorted_pids, &self.processes, self.sort_by, self.sort_order, by_elapsed);
         }
+        self.sorted_upto = needed;
+    }
 
-        root_procs.sort_by_key(|p| p.pid);
-        let mut ordered_list = Vec::new();
-        for root in root_procs {
-            self.add_tree_children(root, 0, &pid_map, &mut ordered_list);
+    // The rows exactly as they are drawn, (tree depth, PID) in order: --pid, the filters and
+    // `top_n` applied to the sorted PIDs, as the flat list or as the tree. In the tree, a filter
+    // keeps the matching processes and the ancestors that place them. Needs `ensure_sorted`.
+    fn visible_rows(&self) -> Vec<(usize, u32)> {
+        let (sorted_pids, mut rows) = self.matching_rows();
+        let filtered = self.active_filter.is_some() || self.field_filter.is_some() || self.leaking_only;
+        if !self.tree_view {
+            if let Some(n) = self.top_n() { rows.truncate(n); }
+            return rows;
+        }
+        if !filtered {
+            return process_list::tree_order(&self.processes, &sorted_pids, self.config.tree_pid_order, &self.collapsed);
         }
-        ordered_list
+        let kept: HashSet<u32> = rows.iter().flat_map(|&(_, pid)| process_list::parent_chain(&self.processes, pid)).collect();
+        let sorted_pids: Vec<u32> = sorted_pids.iter().copied().filter(|pid| kept.contains(pid)).collect();
+        process_list::tree_order(&self.processes, &sorted_pids, self.config.tree_pid_order, &self.collapsed)
     }
 
-    fn add_tree_children<'a>(
-        &self,
-        proc: &'a ProcessInfo,
-        depth: usize,
-        pid_map: &HashMap<u32, Vec<&'a ProcessInfo>>,
-        ordered_list: &mut Vec<(usize, &'a ProcessInfo)>,
-    ) {
-        ordered_list.push((depth, proc));
-        if let Some(children) = pid_map.get(&proc.pid) {
-            let mut sorted_children = children.clone();
-            sorted_children.sort_by_key(|c| c.pid);
-            for child in sorted_children {
-                self.add_tree_children(child, depth + 1, pid_map, ordered_list);
-            }
+    // The sorted PIDs --pid leaves, and those of them that pass the filters as flat rows.
+    fn matching_rows(&self) -> (Cow<'_, [u32]>, Vec<(usize, u32)>) {
+        let followed = (!self.follow_pids.is_empty()).then(|| process_list::with_descendants(&self.processes, &self.follow_pids));
+        let sorted_pids: Cow<[u32]> = match &followed {
+            Some(followed) => self.sorted_pids.iter().copied().filter(|pid| followed.contains(pid)).collect(),
+            None => Cow::Borrowed(&self.sorted_pids),
+        };
+        let mut rows = process_list::filter_pids(&self.processes, &sorted_pids, self.active_filter.as_deref(), self.filter_mode, self.cell_format.path_column);
+        if self.leaking_only { rows.retain(|(_, pid)| self.processes[pid].leaking); }
+        if let Some(filter) = &self.field_filter { rows.retain(|(_, pid)| filter.matches(&self.processes[pid])); }
+        (sorted_pids, rows)
+    }
+
+    // The processes shown because they match, in the order drawn: the tree's placeholder rows
+    // and the ancestors that only place a match are left out.
+    fn shown_matches(&mut self) -> Vec<u32> {
+        self.ensure_sorted();
+        let matched: HashSet<u32> = self.matching_rows().1.into_iter().map(|(_, pid)| pid).collect();
+        self.visible_rows().into_iter().map(|(_, pid)| pid).filter(|pid| matched.contains(pid)).collect()
+    }
+
+    // Recomputes `view`, which drawing, navigation and `selected_pid` all read, so the filter
+    // and tree are evaluated once per change rather than once per frame.
+    // The selection follows its PID through the rebuild rather than staying on a row index.
+    fn rebuild_view(&mut self) {
+        self.ensure_sorted();
+        let anchor = self.selected_pid();
+        self.view = self.visible_rows();
+        // A process folded into a collapsed subtree hands the selection to the row it is folded into.
+        let kept = anchor.is_some_and(|pid| self.select_pid(pid) || (self.tree_view && self.select_visible_ancestor(pid)));
+        if !kept && !self.view.is_empty() {
+            // The selected process went away (or nothing was selected): stay on the same row.
+            let i = self.state.selected().unwrap_or(0).min(self.view.len() - 1);
+            self.move_selection(Some(i), true);
         }
+        self.dirty = true;
     }
 
-    fn filtered_processes(&self) -> Vec<&ProcessInfo> {
-        if let Some(ref filter) = self.active_filter {
-            let filter_lower = filter.to_lowercase();
-            self.processes.iter().filter(|p| p.command.to_lowercase().contains(&filter_lower)).collect()
-        } else {
-            self.processes.iter().collect()
+    // Selects the row showing `pid`. Returns false if it isn't in the view.
+    fn select_pid(&mut self, pid: u32) -> bool {
+        let Some(i) = self.view.iter().position(|&(_, p)| p == pid) else { return false };
+        self.state.select(Some(i));
+        true
+    }
+
+    fn set_filter(&mut self, filter: Option<String>) {
+        self.active_filter = filter;
+        self.rebuild_view();
+        self.state.select(Some(0));
+        self.announce(format!("{} processes listed", self.view.len()));
+    }
+
+    fn toggle_leaking_only(&mut self) {
+        self.leaking_only = !self.leaking_only;
+        self.rebuild_view();
+        if self.leaking_only && self.view.is_empty() {
+            self.message = Some(format!("No process has grown steadily over the last {} seconds", self.config.leak_secs));
+        }
+    }
+
+    // After each refresh with --pid: says which of the PIDs given aren't running, once each
+    // time that changes. They stay followed; the rest keep showing.
+    fn check_followed(&mut self) {
+        let missing: BTreeSet<u32> = self.follow_pids.iter().copied().filter(|pid| !self.processes.contains_key(pid)).collect();
+        if !missing.is_subset(&self.follow_missing) {
+            let list = missing.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+            self.message = Some(if missing == self.follow_pids {
+                format!("None of the --pid processes are running ({})", list)
+            } else {
+                format!("Not running: PID {}", list)
+            });
+        }
+        self.follow_missing = missing;
+    }
+
+    // The first time the process count passes `process_count_warn`, suggests ways to keep
+    // refreshes cheap: sorting and filtering thousands of processes every refresh adds up.
+    fn check_process_count(&mut self) {
+        let limit = self.config.process_count_warn;
+        if self.count_warned || limit == 0 || self.processes.len() <= limit || self.config.top_n > 0 { return; }
+        self.count_warned = true;
+        let filter = self.keymap.label(Action::Search).unwrap_or_else(|| "/".to_string());
+        self.message = Some(format!("{} processes: a filter ({}), --pid or top_n in the config keeps refreshes quick", self.processes.len(), filter));
+    }
+
+    fn toggle_tall_rows(&mut self) {
+        self.tall_rows = !self.tall_rows;
+        self.announce(if self.tall_rows { "Two lines per process" } else { "One line per process" }.to_string());
+    }
+
+    fn row_height(&self) -> u16 {
+        if self.tall_rows { 2 } else { 1 }
+    }
+
+    // Right: the selected row grows to show its whole command; again, or moving away, folds it.
+    fn toggle_expanded_row(&mut self) {
+        let selected = self.selected_pid();
+        self.expanded = if self.expanded == selected { None } else { selected };
+    }
+
+    // Index in `view` and height of the expanded row. Forgets the expansion once the selection
+    // has moved to another process, however it got there.
+    fn expanded_row(&mut self) -> Option<(usize, u16)> {
+        let pid = self.expanded?;
+        let found = self.state.selected().filter(|_| self.selected_pid() == Some(pid)).and_then(|i| {
+            let p = self.processes.get(&pid)?;
+            let (room, _) = self.expanded_room(i, p)?;
+            let lines = Column::Command.cell_text(p, self.cell_format).chars().count().div_ceil(room);
+            Some((i, (lines as u16).max(self.row_height())))
+        });
+        if found.is_none() { self.expanded = None; }
+        found
+    }
+
+    // Characters of command per line of row `i`, and the indent before them (the tree prefix
+    // and marker). None before the first draw, while the COMMAND column's width is unknown.
+    fn expanded_room(&self, i: usize, p: &ProcessInfo) -> Option<(usize, usize)> {
+        let (depth, pid) = self.view[i];
+        let indent = tree_prefix(depth, self.collapsed.contains(&pid)).chars().count() + self.command_marker(p).width();
+        let width = self.command_column.1 as usize;
+        (width > 0).then(|| (width.saturating_sub(indent).max(1), indent))
+    }
+
+    fn toggle_tree_view(&mut self) {
+        self.tree_view = !self.tree_view;
+        self.rebuild_view();
+        self.announce(if self.tree_view { "Tree view" } else { "Flat list" }.to_string());
+    }
+
+    // 'o': switch between the flat list and the tree with the selected process still selected,
+    // so its ancestry (or its place in the sort order) is one key away. If the filter would hide
+    // it in the other view, the filter is dropped rather than losing the process.
+    fn reveal_selected(&mut self) {
+        let Some(pid) = self.selected_pid() else { return self.toggle_tree_view() };
+        self.toggle_tree_view();
+        self.show_pid(pid);
+    }
+
+    // Selects `pid`, dropping the filter (with a note) if that's what hides it.
+    fn show_pid(&mut self, pid: u32) {
+        if self.tree_view { self.expand_ancestors(pid); }
+        if !self.select_pid(pid) && self.active_filter.take().is_some() {
+            self.rebuild_view();
+            self.select_pid(pid);
+            self.message = Some(format!("Filter cleared to show PID {}", pid));
         }
     }
 
+    // Moves the selection to the next (or previous) row matching the last find query, wrapping
+    // around. Unlike the filter, every other row stays visible.
+    fn find_next(&mut self, forward: bool) {
+        let Some(query) = self.last_find.as_deref().map(str::to_lowercase) else {
+            self.message = Some(match self.keymap.label(Action::Find) {
+                Some(key) => format!("Nothing to find yet; {} to start", key),
+                None => "Nothing to find yet".to_string(),
+            });
+            return;
+        };
+        let match_exe = self.cell_format.path_column;
+        let found = navigation::find(self.state.selected(), self.view.len(), forward, |i| {
+            self.processes.get(&self.view[i].1).is_some_and(|p| process_list::matches_query(p, &query, match_exe))
+        });
+        match found {
+            Some(i) => self.state.select(Some(i)),
+            None => self.message = Some(format!("No process matches '{}'", self.last_find.as_deref().unwrap_or(""))),
+        }
+    }
+
+    // The selected process. None on the synthetic "(unknown parent)" row, so nothing can act on it.
     fn selected_pid(&self) -> Option<u32> {
         let idx = self.state.selected()?;
-        if self.tree_view {
-            // In tree view, filtering is tricky. For now, we get from the full list.
-            // A more advanced implementation would filter the tree itself.
-            let tree_list = self.tree_ordered_processes();
-            tree_list.get(idx).map(|(_, p)| p.pid)
-        } else {
-            self.filtered_processes().get(idx).map(|p| p.pid)
-        }
+        self.view.get(idx).map(|&(_, pid)| pid).filter(|&pid| pid != UNKNOWN_PARENT && pid != TREE_SEPARATOR)
     }
 
     fn get_list_length(&self) -> usize {
-        if self.tree_view {
-            self.processes.len() // Tree view shows all processes
+        self.view.len()
+    }
+
+    // Scrolls just enough to keep the selection inside a viewport of `height` rows (or keeps it
+    // centered, with `centered_cursor`) and returns the index of the first visible row.
+    fn scroll_to_selection(&mut self, height: usize) -> usize {
+        let selected = self.state.selected().unwrap_or(0);
+        let offset = if self.config.centered_cursor {
+            navigation::centered_offset(selected, height, self.get_list_length())
         } else {
-            self.filtered_processes().len()
+            navigation::scroll_offset(selected, self.state.offset(), height, self.get_list_length())
+        };
+        *self.state.offset_mut() = offset;
+        offset
+    }
+
+    // Navigation never clears an existing selection, even on an empty list. Tree separators
+    // can't be selected: the selection continues past them in the direction it was moving.
+    // (Separators only sit between two roots, so there is always a row on either side.)
+    fn move_selection(&mut self, to: Option<usize>, forward: bool) {
+        let Some(mut i) = to else { return };
+        if self.view.get(i).is_some_and(|&(_, pid)| pid == TREE_SEPARATOR) {
+            i = if forward { i + 1 } else { i - 1 };
         }
+        self.state.select(Some(i));
     }
 
     fn next(&mut self) {
-        let len = self.get_list_length();
-        if len == 0 { return; }
-        let i = match self.state.selected() {
-            Some(i) => if i >= len - 1 { 0 } else { i + 1 },
-            None => 0,
-        };
-        self.state.select(Some(i));
+        self.move_selection(navigation::next(self.state.selected(), self.get_list_length()), true);
     }
 
     fn previous(&mut self) {
-        let len = self.get_list_length();
-        if len == 0 { return; }
-        let i = match self.state.selected() {
-            Some(i) => if i == 0 { len - 1 } else { i - 1 },
-            None => len - 1,
-        };
-        self.state.select(Some(i));
+        self.move_selection(navigation::previous(self.state.selected(), self.get_list_length()), false);
     }
 
     fn page_down(&mut self, page_size: usize) {
-        let len = self.get_list_length();
-        if len == 0 { return; }
-        let i = self.state.selected().unwrap_or(0);
-        let new_i = (i + page_size).min(len - 1);
-        self.state.select(Some(new_i));
+        self.move_selection(navigation::page_down(self.state.selected(), self.get_list_length(), page_size), true);
     }
 
     fn page_up(&mut self, page_size: usize) {
-        let len = self.get_list_length();
-        if len == 0 { return; }
-        let i = self.state.selected().unwrap_or(0);
-        let new_i = i.saturating_sub(page_size);
-        self.state.select(Some(new_i));
+        self.move_selection(navigation::page_up(self.state.selected(), self.get_list_length(), page_size), false);
     }
 
     fn home(&mut self) {
-        if self.get_list_length() > 0 { self.state.select(Some(0)); }
+        self.move_selection(navigation::home(self.get_list_length()), true);
     }
 
     fn end(&mut self) {
-        let len = self.get_list_length();
-        if len > 0 { self.state.select(Some(len - 1)); }
+        self.move_selection(navigation::end(self.get_list_length()), false);
     }
 
+    // Pressing the key of the column already sorted by flips the order, as in htop.
     fn set_sort_by(&mut self, sort_by: SortBy) {
         if self.sort_by == sort_by {
-            self.sort_order = match self.sort_order {
-                SortOrder::Asc => SortOrder::Desc,
-                SortOrder::Desc => SortOrder::Asc,
-            }
+            self.sort_order = self.sort_order.reversed();
         } else {
             self.sort_by = sort_by;
-            self.sort_order = SortOrder::Desc;
+            self.sort_order = self.config.sort_order_for(sort_by);
         }
+        self.sort_processes();
         self.state.select(Some(0));
+        self.announce_sort();
+        self.note_tree_pid_order();
+    }
+
+    // 'I': the same column the other way round. The selected process stays selected.
+    fn invert_sort_order(&mut self) {
+        self.sort_order = self.sort_order.reversed();
+        self.sort_processes();
+        self.announce_sort();
+        self.note_tree_pid_order();
+    }
+
+    // Siblings in the tree follow the sort, unless `tree_pid_order` says otherwise; then a new
+    // sort would seem to do nothing there.
+    fn note_tree_pid_order(&mut self) {
+        if self.tree_view && self.config.tree_pid_order {
+            self.message = Some("The tree lists children by PID (tree_pid_order); the sort shows in the flat list".to_string());
+        }
+    }
+
+    // Opens the kill menu with the configured default signal highlighted.
+    // Falls back to the first entry if the configured signal isn't in the list.
+    fn open_kill_menu(&mut self) {
+        if self.read_only {
+            self.message = Some("read-only mode — actions disabled".to_string());
+            return;
+        }
+        // Whatever was picked last time this session, so a run of kills is Enter, Enter, ...
+        let idx = self.last_signal_idx
+            .or_else(|| self.kill_signals.iter().position(|(_, sig)| *sig == self.kill_default_signal))
+            .unwrap_or(0);
+        self.kill_menu_state.select(Some(idx));
+        self.kill_target = self.selected_pid().and_then(|pid| Some((pid, platform::process_identity(pid)?)));
+        self.kill_menu_bulk = false;
+        self.input_mode = InputMode::KillMenu;
+    }
+
+    // "SIGTERM (15)", or "signal 19" for one the kill menu doesn't list.
+    fn signal_label(&self, signal: i32) -> String {
+        match self.kill_signals.iter().find(|(_, s)| *s == signal) {
+            Some((name, _)) => format!("{} ({})", name, signal),
+            None => format!("signal {}", signal),
+        }
+    }
+
+    // Maps a screen position to an index into `view`, if it lands on a table row.
+    fn row_at(&self, x: u16, y: u16) -> Option<usize> {
+        let body = self.table_body;
+        if x < body.x || x >= body.x + body.width || y < body.y || y >= body.y + body.height {
+            return None;
+        }
+        // Rows are `row_height` lines each, but for an expanded one.
+        let mut top = body.y;
+        for idx in self.state.offset()..self.view.len() {
+            let height = match self.expanded_drawn {
+                Some((i, height)) if i == idx => height,
+                _ => self.row_height(),
+            };
+            if y < top + height { return Some(idx); }
+            top += height;
+        }
+        None
+    }
+
+    // Full command of the process under the pointer, if the pointer is over a COMMAND
+    // cell whose text doesn't fit the column.
+    fn hovered_truncated_command(&self) -> Option<&str> {
+        if self.accessible { return None; } // Pops up over the table, which readers take for a change
+        let (x, y) = self.mouse_pos?;
+        let (col_x, col_width) = self.command_column;
+        if x < col_x || x >= col_x + col_width {
+            return None;
+        }
+        let row = self.row_at(x, y)?;
+        if self.expanded_drawn.is_some_and(|(i, _)| i == row) { return None; } // Shown in full already
+        let (depth, pid) = self.view[row];
+        let p = self.processes.get(&pid)?;
+        let prefix_len = tree_prefix(depth, self.collapsed.contains(&pid)).chars().count();
+        let shown = Column::Command.cell_text(p, self.cell_format).chars().count();
+        (prefix_len + shown > col_width as usize).then_some(p.command.as_str())
+    }
+
+    fn handle_mouse(&mut self, kind: MouseEventKind, x: u16, y: u16) {
+        self.mouse_pos = Some((x, y));
+        if !matches!(self.input_mode, InputMode::Normal | InputMode::Search | InputMode::Find) {
+            return self.handle_popup_mouse(kind, x, y);
+        }
+        if self.input_mode != InputMode::Normal {
+            return;
+        }
+        match kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(idx) = self.row_at(x, y).filter(|&i| self.view[i].1 != TREE_SEPARATOR) { self.state.select(Some(idx)); }
+            }
+            MouseEventKind::Down(MouseButton::Right) => {
+                if let Some(idx) = self.row_at(x, y).filter(|&i| self.view[i].1 != TREE_SEPARATOR) {
+                    self.state.select(Some(idx));
+                    self.open_action_menu(Some((x, y)));
+                }
+            }
+            MouseEventKind::ScrollDown => self.next(),
+            MouseEventKind::ScrollUp => self.previous(),
+            _ => {}
+        }
+    }
+
+    // With a popup open, nothing reaches the table: a click outside the popup dismisses it as Esc
+    // would, and the wheel moves through the popup's list or text.
+    fn handle_popup_mouse(&mut self, kind: MouseEventKind, x: u16, y: u16) {
+        let Some(area) = self.popup_area.get() else { return };
+        let inside = contains(area, x, y);
+        match kind {
+            MouseEventKind::Down(_) if !inside => {
+                // Esc only clears the viewer's search; a click away means to be done with it.
+                if self.input_mode == InputMode::Viewer { self.viewer = None; self.input_mode = InputMode::Normal; }
+                else { self.handle_popup_key(KeyCode::Esc); }
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let double = self.double_click(x, y);
+                match self.input_mode {
+                    InputMode::Detail => self.click_detail(x, y),
+                    InputMode::ActionMenu => self.click_action_menu(x, y, double),
+                    InputMode::KillMenu if contains(self.kill_menu_list, x, y) => {
+                        let idx = self.kill_menu_state.offset() + (y - self.kill_menu_list.y) as usize;
+                        if idx >= self.kill_signals.len() { return; }
+                        self.kill_menu_state.select(Some(idx));
+                        if double { self.handle_popup_key(KeyCode::Enter); }
+                    }
+                    _ => {}
+                }
+            }
+            // Not in dialogs, where any key cancels, nor the filter builder, where ↑/↓ change fields.
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp if inside && matches!(self.input_mode,
+                InputMode::Viewer | InputMode::Trace | InputMode::KillMenu | InputMode::ActionMenu | InputMode::Setup
+                | InputMode::Network | InputMode::Disks | InputMode::Sensors | InputMode::Presets | InputMode::Zombies) => {
+                self.handle_popup_key(if kind == MouseEventKind::ScrollDown { KeyCode::Down } else { KeyCode::Up });
+            }
+            _ => {}
+        }
+    }
+
+    // Whether this click, at (x, y), follows one on the same spot closely enough to make a
+    // double-click. A third click starts over.
+    fn double_click(&mut self, x: u16, y: u16) -> bool {
+        let double = self.last_click.is_some_and(|(at, lx, ly)| (lx, ly) == (x, y) && at.elapsed() < DOUBLE_CLICK);
+        self.last_click = if double { None } else { Some((Instant::now(), x, y)) };
+        double
+    }
+
+    // Keys while a popup or menu is open.
+    fn handle_popup_key(&mut self, code: KeyCode) {
+        match self.input_mode {
+            InputMode::Setup => self.handle_setup_key(code),
+            InputMode::Detail => self.handle_detail_key(code),
+            InputMode::VmStats => self.handle_vm_stats_key(code),
+            InputMode::Network => self.handle_network_key(code),
+            InputMode::Disks => self.handle_disks_key(code),
+            InputMode::Sensors => self.handle_sensors_key(code),
+            InputMode::FilterBuilder => self.handle_filter_builder_key(code),
+            InputMode::Presets => self.handle_presets_key(code),
+            InputMode::AlertHistory => self.handle_alert_history_key(code),
+            InputMode::Trace => self.handle_trace_key(code),
+            InputMode::Viewer => self.handle_viewer_key(code),
+            InputMode::Zombies => self.handle_zombies_key(code),
+            InputMode::ActionMenu => self.handle_action_menu_key(code),
+            InputMode::KillMenu => match code {
+                KeyCode::Esc | KeyCode::Char('q') => self.input_mode = InputMode::Normal,
+                KeyCode::Down => self.next_kill_signal(),
+                KeyCode::Up => self.previous_kill_signal(),
+                KeyCode::Char('e') if !self.kill_menu_bulk => self.toggle_escalate(),
+                KeyCode::Enter if self.kill_menu_bulk => {
+                    self.last_signal_idx = self.kill_menu_state.selected();
+                    let signal = self.kill_menu_state.selected().map(|i| self.kill_signals[i].1);
+                    match signal {
+                        Some(signal) => self.request_bulk_kill(signal),
+                        None => self.input_mode = InputMode::Normal,
+                    }
+                }
+                KeyCode::Enter => {
+                    self.last_signal_idx = self.kill_menu_state.selected();
+                    let target = self.kill_target.as_ref().map(|&(pid, _)| pid);
+                    if let (Some(pid), Some(selected_signal_idx)) = (target, self.kill_menu_state.selected()) {
+                        let signal = self.kill_signals[selected_signal_idx].1;
+                        let escalate = self.escalate;
+                        self.request_kill(pid, signal, escalate);
+                    } else {
+                        self.input_mode = InputMode::Normal;
+                    }
+                }
+                _ => {}
+            },
+            // Only an explicit 'y' goes ahead; any other key cancels.
+            InputMode::ConfirmKill => self.confirm_kill(matches!(code, KeyCode::Char('y') | KeyCode::Char('Y'))),
+            InputMode::ConfirmBulkKill => self.confirm_bulk_kill(matches!(code, KeyCode::Char('y') | KeyCode::Char('Y'))),
+            InputMode::ConfirmRestart => self.confirm_restart(matches!(code, KeyCode::Char('y') | KeyCode::Char('Y'))),
+            InputMode::Normal | InputMode::Search | InputMode::Find => {}
+        }
+    }
+
+    // Across all cores, 0-100.
+    fn cpu_average(&self) -> f32 {
+        if self.cpus.is_empty() { 0.0 } else { self.cpus.iter().sum::<f32>() / self.cpus.len() as f32 }
+    }
+
+    // Text for the terminal title (`set_title`), readable in a background tab: the overall
+    // figures, then the busiest process.
+    fn title(&self) -> String {
+        // Nothing sampled yet: zeros would look like real figures.
+        if self.processes.is_empty() { return "htop-macos".to_string(); }
+        let stats = format!("htop-macos — CPU {:.0}% MEM {:.0}% load {:.1}", self.cpu_average(), self.mem_usage, self.load_avg.one);
+        match self.processes.values().max_by(|a, b| a.cpu.total_cmp(&b.cpu)) {
+            Some(p) => format!("{} — {} {:.0}%", stats, p.name, p.cpu),
+            None => stats,
+        }
+    }
+
+    // launchd, kernel_task and whatever else the config lists: signalling these takes
+    // the system down with them, so it is never a single keystroke away.
+    fn is_protected(&self, pid: u32) -> bool {
+        self.config.protected_pids.contains(&pid)
+            || self.processes.get(&pid).is_some_and(|p| self.config.protected_names.contains(&p.name))
+    }
+
+    fn process_label(&self, pid: u32) -> String {
+        match self.processes.get(&pid) {
+            Some(p) => format!("{} (PID {})", p.name, pid),
+            None => format!("PID {}", pid),
+        }
+    }
+
+    // Kill menu Enter: send right away, unless the target is protected.
+    fn request_kill(&mut self, pid: u32, signal: i32, escalate: bool) {
+        if !self.is_protected(pid) {
+            self.send_signal(pid, signal, escalate);
+        } else if self.config.block_protected {
+            self.message = Some(format!("{} is protected; signal not sent", self.process_label(pid)));
+        } else {
+            self.pending_kill = Some((pid, signal, escalate));
+            self.input_mode = InputMode::ConfirmKill;
+            return;
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn confirm_kill(&mut self, confirmed: bool) {
+        if let Some((pid, signal, escalate)) = self.pending_kill.take() {
+            if confirmed {
+                self.send_signal(pid, signal, escalate);
+            } else {
+                self.message = Some("Cancelled".to_string());
+            }
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    // The PID may have been reused since the menu opened (the process exited and a new one got
+    // its number), so it is checked against what we captured then, right before sending.
+    fn send_signal(&mut self, pid: u32, signal: i32, escalate: bool) {
+        let expected = self.kill_target.take().filter(|(target, _)| *target == pid).map(|(_, identity)| identity);
+        let current = platform::process_identity(pid);
+        if expected.is_none() || current != expected {
+            self.message = Some(match current {
+                Some(now) => format!("Process changed: PID {} is now {}; signal not sent", pid, now.name),
+                None => format!("PID {} has exited; signal not sent", pid),
+            });
+            return;
+        }
+        self.message = Some(match kill_process(pid, signal) {
+            Ok(_) => {
+                if let (true, Some(identity)) = (escalate, current) { self.schedule_escalation(pid, identity, signal); }
+                format!("Sent {} to PID {}", self.signal_label(signal), pid)
+            }
+            Err(e) => format!("Error killing {}: {}", pid, e),
+        });
     }
 
     // ADDED: Kill menu navigation
@@ -306,31 +1149,96 @@ impl App {
     }
 }
 
-// Helper functions - No changes
-fn status_to_string(s: ProcessStatus) -> String {
-    match s {
-        ProcessStatus::Run => "R".to_string(),
-        ProcessStatus::Sleep => "S".to_string(),
-        ProcessStatus::Idle => "D".to_string(),
-        ProcessStatus::Zombie => "Z".to_string(),
-        _ => format!("{:?}", s),
+// Helper functions
+// `text` as spans, with what `filter` (lowercase) matched in it drawn in `style`.
+fn highlight_matches<'a>(text: Cow<'a, str>, filter: Option<&str>, mode: FilterMode, style: Style) -> Vec<Span<'a>> {
+    let ranges = filter.map(|f| process_list::match_ranges(&text, f, mode)).unwrap_or_default();
+    if ranges.is_empty() { return vec![Span::raw(text)]; }
+    let piece = |range: std::ops::Range<usize>| -> Cow<'a, str> {
+        match &text {
+            Cow::Borrowed(s) => { let s: &'a str = s; Cow::Borrowed(&s[range]) }
+            Cow::Owned(s) => Cow::Owned(s[range].to_string()),
+        }
+    };
+    let mut spans = Vec::with_capacity(ranges.len() * 2 + 1);
+    let mut at = 0;
+    for range in ranges {
+        if range.start > at { spans.push(Span::raw(piece(at..range.start))); }
+        at = range.end;
+        spans.push(Span::styled(piece(range), style));
+    }
+    if at < text.len() { spans.push(Span::raw(piece(at..text.len()))); }
+    spans
+}
+
+// `spans` with `style` patched onto the bytes in `range`, counted across all of them.
+fn emphasize<'a>(spans: Vec<Span<'a>>, range: std::ops::Range<usize>, style: Style) -> Vec<Span<'a>> {
+    let mut out = Vec::with_capacity(spans.len() + 2);
+    let mut at = 0;
+    for span in spans {
+        let len = span.content.len();
+        let (start, end) = (range.start.clamp(at, at + len) - at, range.end.clamp(at, at + len) - at);
+        at += len;
+        if start == end { out.push(span); continue; }
+        for (piece, patched) in [(0..start, false), (start..end, true), (end..len, false)] {
+            if piece.is_empty() { continue; }
+            let piece_style = if patched { span.style.patch(style) } else { span.style };
+            out.push(Span::styled(span.content[piece].to_string(), piece_style));
+        }
+    }
+    out
+}
+
+// What goes before a command in tree view: indentation and a branch, with '+' in it when the
+// process's subtree is collapsed.
+fn tree_prefix(depth: usize, collapsed: bool) -> String {
+    let mut prefix = " ".repeat(depth * 2);
+    match (depth > 0, collapsed) {
+        (true, false) => prefix.push_str("└─ "),
+        (true, true) => prefix.push_str("└+ "),
+        (false, true) => prefix.push_str("+ "),
+        (false, false) => {}
+    }
+    prefix
+}
+
+// Cuts `spans` into lines of `width` characters each, keeping their styles. Always at least one line.
+fn wrap_spans(spans: Vec<Span<'_>>, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1); // Nothing would ever fit on a zero-width line
+    let mut lines = vec![Line::default()];
+    let mut room = width;
+    for span in spans {
+        let mut rest = span.content.as_ref();
+        while !rest.is_empty() {
+            if room == 0 {
+                lines.push(Line::default());
+                room = width;
+            }
+            let cut = rest.char_indices().nth(room).map_or(rest.len(), |(cut, _)| cut);
+            room -= rest[..cut].chars().count();
+            lines.last_mut().unwrap().spans.push(Span::styled(rest[..cut].to_string(), span.style));
+            rest = &rest[cut..];
+        }
     }
+    lines
 }
 
-fn format_time(secs: u64) -> String {
-    let mins = secs / 60;
-    let hours = mins / 60;
-    let days = hours / 24;
-    if days > 99 { format!("{}d", days) }
-    else if days > 0 { format!("{:02}d{:02}h", days, hours % 24) }
-    else { format!("{:02}:{:02}:{:02}", hours, mins % 60, secs % 60) }
+// Second line of a tall row's COMMAND cell: what didn't fit on the first line, or else the
+// executable's path when the command doesn't already start with it.
+fn second_command_line<'a>(text: &'a str, p: &'a ProcessInfo, width: usize, indent: usize) -> Option<&'a str> {
+    let room = width.saturating_sub(indent).max(1);
+    if let Some(rest) = text.char_indices().nth(room).map(|(cut, _)| text[cut..].trim_start()).filter(|r| !r.is_empty()) {
+        return Some(rest);
+    }
+    (!p.exe.is_empty() && !text.starts_with(&p.exe)).then_some(p.exe.as_str())
 }
 
-fn format_uptime(secs: u64) -> String {
-    let days = secs / 86400;
-    let hours = (secs % 86400) / 3600;
-    let mins = (secs % 3600) / 60;
-    format!("{} days, {:02}:{:02}", days, hours, mins)
+// Overwrites `dst` only when the text differs, reusing its existing allocation. True if it did.
+fn set_if_changed(dst: &mut String, src: &str) -> bool {
+    if dst == src { return false; }
+    dst.clear();
+    dst.push_str(src);
+    true
 }
 
 fn kill_process(pid: u32, signal: i32) -> Result<(), String> {
@@ -342,8 +1250,21 @@ fn kill_process(pid: u32, signal: i32) -> Result<(), String> {
     }
 }
 
+fn contains(area: Rect, x: u16, y: u16) -> bool {
+    x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+}
+
+// Blanks `area` for a popup to be drawn in, and remembers it so clicks can be told apart as in
+// or outside it.
+fn clear_popup(f: &mut Frame, app: &App, area: Rect) {
+    f.render_widget(Clear, area);
+    app.popup_area.set(Some(area));
+}
+
 // ADDED: Helper to create a centered popup area
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    // A share of a short screen, --no-altscreen's box say, leaves too few lines to be of use.
+    let percent_y = if r.height < POPUP_FULL_HEIGHT { 100 } else { percent_y };
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -353,210 +1274,804 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         ])
         .split(r);
 
-    Layout::default()
+    let rect = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
             Constraint::Percentage((100 - percent_x) / 2),
             Constraint::Percentage(percent_x),
             Constraint::Percentage((100 - percent_x) / 2),
         ])
-        .split(popup_layout[1])[1]
+        .split(popup_layout[1])[1];
+    let width = rect.width.max(POPUP_MIN.0.min(r.width));
+    let height = rect.height.max(POPUP_MIN.1.min(r.height));
+    if (width, height) == (rect.width, rect.height) { return rect; }
+    Rect { x: r.x + (r.width - width) / 2, y: r.y + (r.height - height) / 2, width, height }
+}
+
+// Places a `width` x `height` box next to the point (x, y): below it if there is room,
+// otherwise above, shifted left as needed so it stays inside `bounds`.
+fn rect_near(x: u16, y: u16, width: u16, height: u16, bounds: Rect) -> Rect {
+    let width = width.min(bounds.width);
+    let height = height.min(bounds.height);
+    let x = x.min(bounds.x + bounds.width - width);
+    let y = if y + 1 + height <= bounds.y + bounds.height { y + 1 } else { y.saturating_sub(height).max(bounds.y) };
+    Rect { x, y, width, height }
 }
 
 // main() - Significant changes to rendering and input handling
 fn main() -> Result<(), io::Error> {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(ParseOutcome::Run(args)) => args,
+        Ok(ParseOutcome::Exit(text)) => { println!("{}", text); return Ok(()); }
+        Err(e) => { eprintln!("htop-macos: {}", e); std::process::exit(2); }
+    };
+    // Open files before taking over the terminal so errors print normally.
+    let replay = args.replay.as_deref().map(Replay::load).transpose().unwrap_or_else(|e| {
+        eprintln!("htop-macos: {}", e);
+        std::process::exit(1);
+    });
+    // The first run with an htoprc around starts from htop's settings.
+    let (config, config_note) = match htoprc::first_run_import() {
+        Some((config, note)) => (config, Some(note)),
+        None => match Config::load() {
+            Ok(config) => (config, None),
+            Err(e) => (Config::default(), Some(format!("Config error, using defaults: {}", e))),
+        },
+    };
+    let record_to = args.record.as_deref().map(|path| Recorder::open(path, config.snapshot_every, config.snapshot_keep)).transpose().unwrap_or_else(|e| {
+        eprintln!("htop-macos: {}", e);
+        std::process::exit(1);
+    });
+
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    // Inline, the mouse would take text selection away from the terminal, which is the point of
+    // leaving the output in the scrollback.
+    let mouse = args.mouse.unwrap_or(!args.inline);
+    if !args.inline { execute!(stdout, EnterAlternateScreen)?; }
+    if mouse { execute!(stdout, EnableMouseCapture)?; }
     let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let mut terminal = if args.inline {
+        let rows = config.inline_rows.clamp(INLINE_MIN_ROWS, crossterm::terminal::size()?.1.max(INLINE_MIN_ROWS));
+        Terminal::with_options(backend, TerminalOptions { viewport: Viewport::Inline(rows) })?
+    } else {
+        Terminal::new(backend)?
+    };
 
-    let app = Arc::new(Mutex::new(App::new()));
+    let set_title = args.set_title.unwrap_or(config.set_title);
+    if set_title { execute!(terminal.backend_mut(), PushTitle)?; }
+    let mut app = App::new(&config);
+    if config_note.is_some() { app.message = config_note; }
+    app.read_only = args.read_only;
+    app.accessible = args.accessible;
+    app.follow_pids = args.pids.iter().copied().collect();
+    let term = std::env::var("TERM").ok();
+    let colorterm = std::env::var("COLORTERM").ok();
+    app.theme = Theme::new(config.theme, args.colors.unwrap_or_else(|| ColorDepth::detect(term.as_deref(), colorterm.as_deref())));
+    let app = Arc::new(Mutex::new(app));
     let running = Arc::new(Mutex::new(true));
 
-    {
+    if let Some(replay) = replay {
+        // A recording is browsed frame by frame; its PIDs don't refer to anything live.
+        let mut app = app.lock().unwrap();
+        app.read_only = true;
+        app.replay = Some(replay);
+        app.step_replay(0);
+    } else {
+        app.lock().unwrap().start_sensors();
         let app = Arc::clone(&app);
         let running = Arc::clone(&running);
+        let refresh_interval = config.refresh_interval();
         thread::spawn(move || {
-            let mut sys = System::new_all();
+            // Sampling everything the first time takes a while; the UI shows placeholders meanwhile.
+            let mut source: Box<dyn DataSource + Send> = match record_to {
+                Some(out) => Box::new(Recorder::new(Live::new(), out)),
+                None => Box::new(Live::new()),
+            };
             while *running.lock().unwrap() {
-                app.lock().unwrap().update_data(&mut sys);
-                thread::sleep(Duration::from_secs(2));
+                // Sampling takes a moment of its own (see `Live::refresh`); keep the pace steady.
+                let started = Instant::now();
+                source.want_extras(app.lock().unwrap().extras());
+                source.refresh();
+                let sample = started.elapsed();
+                let mut app = app.lock().unwrap();
+                let updating = Instant::now();
+                app.update_data(source.as_mut());
+                (app.debug.sample, app.debug.update) = (sample, updating.elapsed());
+                drop(app);
+                thread::sleep(refresh_interval.saturating_sub(started.elapsed()));
             }
         });
     }
-    thread::sleep(Duration::from_millis(100));
-
+    let mut page_size = 0;
+    let mut last_activity = Instant::now();
+    let mut last_title = String::new();
     loop {
         let mut app_guard = app.lock().unwrap();
-        let mut table_height = 0;
-
-        terminal.draw(|f| {
-            let size = f.size();
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Length(5), Constraint::Min(10), Constraint::Length(3)])
-                .split(size);
-
-            // --- HEADER ---
-            let header_chunks = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Percentage(50), Constraint::Percentage(50)]).split(chunks[0]);
-
-            let num_cpus = app_guard.cpus.len();
-            if num_cpus > 0 {
-                let cpu_constraints: Vec<Constraint> = (0..num_cpus).map(|_| Constraint::Ratio(1, num_cpus as u32)).collect();
-                let cpu_chunks = Layout::default().direction(Direction::Horizontal).constraints(cpu_constraints).split(header_chunks[0]);
-                for (i, &cpu_usage) in app_guard.cpus.iter().enumerate() {
-                    let gauge = Gauge::default().block(Block::default().title(format!("CPU{}", i+1))).percent(cpu_usage as u16).gauge_style(Style::default().fg(Color::Green));
-                    f.render_widget(gauge, cpu_chunks[i]);
+        // A stuck refresh changes nothing, so the banner has to trigger its own redraws.
+        if app_guard.staleness().is_some() || app_guard.stale_shown { app_guard.dirty = true; }
+        // And for dtruss output coming in.
+        if app_guard.poll_trace() { app_guard.dirty = true; }
+        // And for a stack sample's progress, and its result.
+        if app_guard.poll_sampling() { app_guard.dirty = true; }
+        // And for new sensor readings, when their screen is open.
+        if app_guard.poll_sensors() { app_guard.dirty = true; }
+        // And for the disks screen's volumes, read in the background too.
+        if app_guard.poll_disks() { app_guard.dirty = true; }
+        // Same for the flashing header of an unacknowledged alert.
+        if app_guard.alerts.flash_phase() != app_guard.alerts.flash_drawn { app_guard.dirty = true; }
+        // Nothing changed since the last frame: don't touch the terminal at all.
+        if app_guard.dirty {
+            let mut table_height = 0;
+            let render_started = Instant::now();
+
+            terminal.draw(|f| {
+                let size = f.size();
+                app_guard.popup_area.set(None);
+                let fit = small_screen::fit(size);
+                if fit == small_screen::Fit::TooSmall {
+                    // No rows to click on.
+                    app_guard.table_body = Rect::default();
+                    return small_screen::draw_too_small(f, size);
                 }
-            }
+                app_guard.compact = fit == small_screen::Fit::Compact;
+                app_guard.reveal_columns(size.width);
+                let (header_height, table_min) = if app_guard.compact { (1, 3) } else { (meters::header_height(&app_guard), 10) };
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(header_height), Constraint::Min(table_min), Constraint::Length(3)])
+                    .split(size);
 
-            let right_header_chunks = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Length(3)]).split(header_chunks[1]);
+                // --- HEADER ---
+                if app_guard.compact { meters::draw_summary(f, &app_guard, chunks[0]); } else { meters::draw_header(f, &app_guard, chunks[0]); }
+                let flash = app_guard.alerts.flash_phase();
+                if flash == Some(true) {
+                    f.buffer_mut().set_style(Rect { height: 1, ..chunks[0] }, app_guard.theme.banner);
+                }
+                app_guard.alerts.flash_drawn = flash;
 
-            let mem_text = format!("Mem[{} / {}MiB]", app_guard.used_mem / 1024 / 1024, app_guard.total_mem / 1024 / 1024);
-            f.render_widget(Paragraph::new(mem_text).style(Style::default().fg(Color::Cyan)), right_header_chunks[0]);
+                // --- TABLE ---
+                // In rows, not lines: borders and the header row take 3 lines.
+                let row_height = app_guard.row_height();
+                let lines = chunks[1].height.saturating_sub(3);
+                // An expanded row (always the selected one, so always in view) takes its extra
+                // lines from the other rows.
+                let expanded = app_guard.expanded_row().map(|(i, height)| (i, height.min(lines).max(row_height)));
+                let extra = expanded.map_or(0, |(_, height)| height - row_height);
+                table_height = ((lines.saturating_sub(extra) / row_height) as usize).max(1);
+                app_guard.expanded_drawn = expanded;
+                let header_cells = app_guard.shown_columns().map(|c| {
+                    Cell::from(c.column.header_with_arrow(app_guard.cell_format, app_guard.sort_by, app_guard.sort_order)).style(app_guard.theme.column_header)
+                });
+                let header = Row::new(header_cells).style(app_guard.theme.header_row).height(1);
 
-            let swp_text = format!("Swp[{} / {}MiB]", app_guard.used_swap / 1024 / 1024, app_guard.total_swap / 1024 / 1024);
-            f.render_widget(Paragraph::new(swp_text).style(Style::default().fg(Color::Magenta)), right_header_chunks[1]);
+                // Only the rows inside the viewport are built; cells borrow from the process data.
+                let offset = app_guard.scroll_to_selection(table_height);
+                let visible_selection = app_guard.state.selected().map(|i| i.saturating_sub(offset));
+                let app_ref = &*app_guard;
+                let end = (offset + table_height).min(app_ref.view.len());
+                // Matched on the text as displayed, so highlights line up with what is visible.
+                let filter = app_ref.active_filter.as_deref().filter(|f| !f.is_empty()).map(str::to_lowercase);
+                let rows: Vec<Row> = app_ref.view[offset..end].iter().zip(offset..).filter_map(|(&(depth, pid), i)| {
+                    if pid == TREE_SEPARATOR {
+                        // A faint rule between two independent trees.
+                        return Some(Row::new(app_ref.shown_columns().map(|c| Cell::from("─".repeat(c.width.max(1) as usize))))
+                            .style(app_ref.theme.dim).height(row_height));
+                    }
+                    if pid == UNKNOWN_PARENT {
+                        return Some(Row::new(app_ref.shown_columns().map(|c| {
+                            Cell::from(if c.column == Column::Command { "(unknown parent)" } else { "" })
+                        })).style(app_ref.theme.dim).height(row_height));
+                    }
+                    let p = app_ref.processes.get(&pid)?;
+                    let row_style = if p.status == "Z" {
+                        app_ref.theme.warning
+                    } else if app_ref.frontmost == Some(pid) {
+                        app_ref.theme.frontmost
+                    } else {
+                        Style::default()
+                    };
+                    let height = expanded.filter(|&(row, _)| row == i).map(|(_, height)| height);
+                    Some(Row::new(app_ref.shown_columns().map(|c| {
+                        if c.column == Column::Command {
+                            let prefix = tree_prefix(depth, app_ref.collapsed.contains(&pid));
+                            let marker = app_ref.command_marker(p);
+                            let indent = prefix.chars().count() + marker.width();
+                            let text = c.column.cell_text(p, app_ref.cell_format);
+                            // COMMAND takes up the slack, so its fitted width is only a minimum; the width it
+                            // got on the last draw is what the text was cut at.
+                            let width = app_ref.command_column.1.max(c.width) as usize;
+                            let second = app_ref.tall_rows.then(|| second_command_line(&text, p, width, indent))
+                                .flatten().map(|line| format!("{}{}", " ".repeat(indent), line));
+                            let mut spans = vec![Span::raw(prefix), marker];
+                            let mut matched = highlight_matches(text, filter.as_deref(), app_ref.filter_mode, app_ref.theme.filter_match);
+                            if let Some(range) = base_name_range(p, app_ref.cell_format).filter(|_| app_ref.config.highlight_base_name) {
+                                matched = emphasize(matched, range, app_ref.theme.base_name);
+                            }
+                            if height.is_some() {
+                                // Expanded: the whole command, wrapped under its first character.
+                                let room = width.saturating_sub(indent).max(1);
+                                let mut lines = wrap_spans(matched, room);
+                                lines[0].spans.splice(0..0, spans);
+                                for line in &mut lines[1..] { line.spans.insert(0, Span::raw(" ".repeat(indent))); }
+                                return Cell::from(Text::from(lines));
+                            }
+                            spans.extend(matched);
+                            let mut lines = vec![Line::from(spans)];
+                            lines.extend(second.map(|line| Line::from(Span::styled(line, app_ref.theme.dim))));
+                            Cell::from(Text::from(lines))
+                        } else if c.column == Column::Path && filter.is_some() {
+                            Cell::from(Line::from(highlight_matches(c.column.cell_text(p, app_ref.cell_format), filter.as_deref(), app_ref.filter_mode, app_ref.theme.filter_match)))
+                        } else if (c.column == Column::Ports && p.ports.is_some_and(|n| n > app_ref.config.ports_warn))
+                            || (c.column == Column::MemDelta && p.mem_delta.is_some_and(|d| d > (app_ref.config.mem_growth_warn_mb * 1024 * 1024) as i64)) {
+                            // Likely a leak.
+                            Cell::from(c.column.cell_text(p, app_ref.cell_format)).style(app_ref.theme.warning.add_modifier(Modifier::BOLD))
+                        } else if c.column == Column::Pgrp && p.pgid == Some(p.pid) {
+                            // Group leader: what the group is named after.
+                            Cell::from(c.column.cell_text(p, app_ref.cell_format)).style(Style::default().add_modifier(Modifier::BOLD))
+                        } else {
+                            Cell::from(c.column.cell_text(p, app_ref.cell_format))
+                        }
+                    })).style(row_style).height(height.unwrap_or(row_height)))
+                }).collect();
 
-            let tasks_text = format!("Tasks: {}, Load Avg: {:.2} {:.2} {:.2}", app_guard.processes.len(), app_guard.load_avg.one, app_guard.load_avg.five, app_guard.load_avg.fifteen);
-            let uptime_text = format!("Uptime: {}", format_uptime(app_guard.uptime));
-            f.render_widget(Paragraph::new(format!("{}\n{}", tasks_text, uptime_text)), right_header_chunks[2]);
+                let widths: Vec<Constraint> = app_ref.shown_columns().map(ColumnState::constraint).collect();
+                let mut table_block = Block::default().borders(Borders::ALL).title(app_guard.screen_tabs());
+                let staleness = app_guard.staleness();
+                if let Some(age) = staleness {
+                    let banner = Span::styled(format!(" STALE DATA — last refresh {}s ago ", age.as_secs()), app_guard.theme.banner);
+                    table_block = table_block.title(Title::from(banner).alignment(Alignment::Right));
+                }
+                let table_inner = table_block.inner(chunks[1]);
+                let table = Table::new(rows, widths.clone())
+                    .header(header).block(table_block)
+                    .highlight_style(Style::default().add_modifier(Modifier::REVERSED)).highlight_symbol(app_guard.selection_marker(">> "))
+                    .highlight_spacing(HighlightSpacing::Always);
+                let mut visible_state = TableState::default().with_selected(visible_selection);
+                f.render_stateful_widget(table, chunks[1], &mut visible_state);
+                app_guard.stale_shown = staleness.is_some();
 
-            // --- TABLE ---
-            table_height = chunks[1].height as usize - 2;
-            let header_cells = ["PID", "USER", "VIRT", "S", "CPU%", "MEM%", "TIME+", "COMMAND"].iter().map(|h| Cell::from(*h).style(Style::default().fg(Color::Red)));
-            let header = Row::new(header_cells).style(Style::default().bg(Color::Blue)).height(1);
+                // Remember where rows and the COMMAND column landed so mouse events can be mapped
+                // back to them. Mirrors ratatui's own column layout: selection gutter, then columns.
+                let [_, columns_area] = Layout::horizontal([Constraint::Length(3), Constraint::Fill(0)]).areas(table_inner);
+                let columns = Layout::horizontal(widths).flex(Flex::Start).spacing(1).split(columns_area);
+                let command_idx = app_guard.shown_columns().position(|c| c.column == Column::Command);
+                app_guard.command_column = command_idx.map(|i| (columns[i].x, columns[i].width)).unwrap_or((0, 0));
+                app_guard.table_body = Rect { y: table_inner.y + 1, height: table_inner.height.saturating_sub(1), ..table_inner };
+                if app_guard.collecting() {
+                    let placeholder = Paragraph::new("Collecting process data…").style(app_guard.theme.dim).alignment(Alignment::Center);
+                    f.render_widget(placeholder, Rect { y: app_guard.table_body.y + app_guard.table_body.height / 3, height: 1, ..app_guard.table_body });
+                }
 
-            let rows: Vec<Row> = if app_guard.tree_view {
-                let tree_items = app_guard.tree_ordered_processes();
-                tree_items.iter().map(|(depth, p)| {
-                    let mut command = " ".repeat(*depth * 2);
-                    if *depth > 0 { command.push_str("└─ "); }
-                    command.push_str(&p.command);
+                // --- FOOTER ---
+                let footer_area = chunks[2];
+                if app_guard.input_mode == InputMode::Search {
+                    let search_text = format!("/{}", app_guard.search_query);
+                    let title = match app_guard.filter_mode {
+                        FilterMode::Plain => "Search (Esc to cancel, Enter to apply, Tab for fuzzy)",
+                        FilterMode::Fuzzy => "Fuzzy search, best matches first (Esc to cancel, Enter to apply, Tab for plain)",
+                    };
+                    let search_bar = Paragraph::new(search_text.clone()).style(app_guard.theme.input).block(Block::default().borders(Borders::ALL).title(title));
+                    f.render_widget(Clear, footer_area);
+                    f.render_widget(search_bar, footer_area);
+                    f.set_cursor(footer_area.x + search_text.len() as u16 + 1, footer_area.y + 1);
+                } else if app_guard.input_mode == InputMode::Find {
+                    let find_text = format!("Find: {}", app_guard.find_query);
+                    let find_bar = Paragraph::new(find_text.clone()).style(app_guard.theme.input).block(Block::default().borders(Borders::ALL).title("Find (Esc to cancel, Enter to jump; n/N for the next and previous match)"));
+                    f.render_widget(Clear, footer_area);
+                    f.render_widget(find_bar, footer_area);
+                    f.set_cursor(footer_area.x + find_text.chars().count() as u16 + 1, footer_area.y + 1);
+                } else {
+                    let footer_chunks = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(1), Constraint::Length(2)]).split(footer_area);
+                    // Actions that are disabled in read-only mode are still listed, but grayed out.
+                    let action_style = if app_guard.read_only { app_guard.theme.dim } else { Style::default() };
+                    let mut footer_actions = vec![
+                        (Action::Setup, "Setup"), (Action::ToggleTree, "Tree"), (Action::Kill, "Kill"),
+                        (Action::Quit, "Quit"), (Action::Search, "Search"), (Action::InvertSort, "Invert"),
+                    ];
+                    if app_guard.screens.len() > 1 { footer_actions.push((Action::NextScreen, "Screen")); }
+                    if app_guard.replay.is_some() { footer_actions.extend([(Action::PreviousFrame, "Prev"), (Action::NextFrame, "Next")]); }
+                    // The action bar shows whatever key each action is bound to; unbound actions are left out.
+                    // Too narrow for one line, it wraps onto a second, breaking only between hints.
+                    let mut help_text = Line::default();
+                    for (action, name) in footer_actions {
+                        let Some(key) = app_guard.keymap.label(action) else { continue };
+                        if !help_text.spans.is_empty() { help_text.spans.push(Span::raw("  ")); }
+                        let style = if action == Action::Kill { action_style } else { Style::default() };
+                        help_text.spans.push(Span::styled(format!("{}\u{a0}{}", key, name), style));
+                    }
+                    f.render_widget(Paragraph::new(help_text).wrap(Wrap { trim: true }), footer_chunks[1]);
+                    if let Some((alert, _)) = &app_guard.alerts.banner {
+                        // Stays until a key is pressed, over the filter and any message.
+                        let history = app_guard.keymap.label(Action::AlertHistory).map_or(String::new(), |key| format!(", {} for the history", key));
+                        let banner = format!(" ALERT {} — any key to acknowledge{} ", alert, history);
+                        f.render_widget(Paragraph::new(banner).style(app_guard.theme.banner), footer_chunks[0]);
+                    } else {
+                        let mut leaking = if app_guard.leaking_only { "[Leaking only] ".to_string() } else { String::new() };
+                        if let Some(filter) = &app_guard.field_filter { leaking.push_str(&format!("[{}] ", filter.summary())); }
+                        let dynamic_text = if let Some(filter) = &app_guard.active_filter {
+                            let kind = if app_guard.filter_mode == FilterMode::Fuzzy { "Fuzzy" } else { "Filter" };
+                            format!("{}[{}: {}] (Esc to clear)", leaking, kind, filter)
+                        } else if !leaking.is_empty() {
+                            format!("{}{}", leaking, app_guard.message.as_deref().unwrap_or("(Esc to clear)"))
+                        } else if let Some(msg) = &app_guard.message { msg.clone() } else { "".to_string() };
+                        // A pending SIGKILL or a sample being taken stays in view, whatever else the footer says.
+                        let pending: String = [app_guard.escalation_hint(), app_guard.sampling_hint()].into_iter().flatten().map(|hint| hint + " ").collect();
+                        // So does what --pid narrowed the list to.
+                        let following = if app_guard.follow_pids.is_empty() { String::new() } else {
+                            let pids = app_guard.follow_pids.iter().map(u32::to_string).collect::<Vec<_>>().join(", ");
+                            format!("[PID {} and children] ", pids)
+                        };
+                        // And that `top_n` left the rest of the list out.
+                        let following = match app_guard.top_n() {
+                            Some(n) => format!("{}[top {} of {}] ", following, n, app_guard.processes.len()),
+                            None => following,
+                        };
+                        let dynamic_text = match app_guard.mode_announcement() {
+                            Some(mode) if dynamic_text.is_empty() => format!("{} open, Esc to close", mode),
+                            Some(mode) => format!("{}: {}", mode, dynamic_text),
+                            None => dynamic_text,
+                        };
+                        f.render_widget(Paragraph::new(Line::from(vec![Span::styled(pending, app_guard.theme.warning), Span::raw(following), Span::raw(dynamic_text)])), footer_chunks[0]);
+                    }
+                }
 
-                    Row::new(vec![
-                        Cell::from(p.pid.to_string()), Cell::from(p.user.clone()), Cell::from(format!("{}M", p.virtual_mem / 1024 / 1024)),
-                        Cell::from(p.status.clone()), Cell::from(format!("{:.1}", p.cpu)), Cell::from(format!("{:.1}", p.mem)),
-                        Cell::from(format_time(p.cpu_time)), Cell::from(command),
-                    ])
-                }).collect()
-            } else {
-                let procs = app_guard.filtered_processes();
-                procs.iter().map(|p| {
-                    Row::new(vec![
-                        Cell::from(p.pid.to_string()), Cell::from(p.user.clone()), Cell::from(format!("{}M", p.virtual_mem / 1024 / 1024)),
-                        Cell::from(p.status.clone()), Cell::from(format!("{:.1}", p.cpu)), Cell::from(format!("{:.1}", p.mem)),
-                        Cell::from(format_time(p.cpu_time)), Cell::from(p.command.clone()),
-                    ])
-                }).collect()
-            };
+                // --- POPUPS (drawn last to be on top) ---
+                if app_guard.input_mode == InputMode::KillMenu {
+                    let items: Vec<ListItem> = app_guard.kill_signals.iter().map(|(name, sig)| ListItem::new(format!("{:2} {}", sig, name))).collect();
+                    let mut block = Block::default().borders(Borders::ALL).title(if app_guard.kill_menu_bulk { "Signal all matching" } else { "Select signal" });
+                    if !app_guard.kill_menu_bulk {
+                        let tick = if app_guard.escalate { "x" } else { " " };
+                        block = block.title(Title::from(format!("[{}] e: SIGKILL after {}s", tick, app_guard.config.kill_escalate_secs)).position(Position::Bottom));
+                    }
+                    let list = List::new(items)
+                        .block(block)
+                        .highlight_style(app_guard.theme.selection)
+                        .highlight_symbol(app_guard.selection_marker(">> "));
 
-            let table = Table::new(rows, [Constraint::Length(6), Constraint::Length(9), Constraint::Length(7), Constraint::Length(2), Constraint::Length(5), Constraint::Length(5), Constraint::Length(9), Constraint::Min(20)])
-                .header(header).block(Block::default().borders(Borders::ALL).title("Processes"))
-                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)).highlight_symbol(">> ");
-            f.render_stateful_widget(table, chunks[1], &mut app_guard.state);
-
-            // --- FOOTER ---
-            let footer_area = chunks[2];
-            if app_guard.input_mode == InputMode::Search {
-                let search_text = format!("/{}", app_guard.search_query);
-                let search_bar = Paragraph::new(search_text.clone()).style(Style::default().fg(Color::Yellow)).block(Block::default().borders(Borders::ALL).title("Search (Esc to cancel, Enter to apply)"));
-                f.render_widget(Clear, footer_area);
-                f.render_widget(search_bar, footer_area);
-                f.set_cursor(footer_area.x + search_text.len() as u16 + 1, footer_area.y + 1);
-            } else {
-                let footer_chunks = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(1), Constraint::Length(2)]).split(footer_area);
-                let help_text = "F5 Tree  F9 Kill  F10 Quit  '/' Search  'I' Invert";
-                f.render_widget(Paragraph::new(help_text), footer_chunks[1]);
-                let dynamic_text = if let Some(filter) = &app_guard.active_filter {
-                    format!("[Filter: {}] (Esc to clear)", filter)
-                } else if let Some(msg) = &app_guard.message { msg.clone() } else { "".to_string() };
-                f.render_widget(Paragraph::new(dynamic_text), footer_chunks[0]);
-            }
+                    // A zombie has already exited; only its parent reaping it makes it go away.
+                    let zombie_parent = app_guard.selected_pid().filter(|_| !app_guard.kill_menu_bulk).and_then(|pid| app_guard.processes.get(&pid)).filter(|p| p.status == "Z").map(|p| p.ppid);
+                    let area = if zombie_parent.is_some() { centered_rect(40, 40, size) } else { centered_rect(30, 30, size) };
+                    clear_popup(f, &app_guard, area);
+                    let list_area = if let Some(ppid) = zombie_parent {
+                        let parts = Layout::default().direction(Direction::Vertical).constraints([Constraint::Length(4), Constraint::Min(3)]).split(area);
+                        let warning = format!("This is a zombie: it has already exited and signals won't affect it. Its parent, {}, has to reap it: send that SIGCHLD or kill it instead.", app_guard.process_label(ppid));
+                        f.render_widget(Paragraph::new(warning).style(app_guard.theme.warning).wrap(Wrap { trim: true }), parts[0]);
+                        parts[1]
+                    } else {
+                        area
+                    };
+                    app_guard.kill_menu_list = Block::default().borders(Borders::ALL).inner(list_area);
+                    f.render_stateful_widget(list, list_area, &mut app_guard.kill_menu_state);
+                } else if app_guard.input_mode == InputMode::ActionMenu {
+                    action_menu::draw_action_menu(f, &mut app_guard, size);
+                } else if let (InputMode::ConfirmKill, Some((pid, signal))) = (&app_guard.input_mode, app_guard.pending_kill.map(|(pid, signal, _)| (pid, signal))) {
+                    let signal_label = app_guard.signal_label(signal);
+                    let text = vec![
+                        Line::from(format!("{} is a protected process.", app_guard.process_label(pid))),
+                        Line::from(""),
+                        Line::from(format!("Are you REALLY sure you want to send {}?", signal_label)),
+                        Line::from(""),
+                        Line::from(Span::styled("y = send it, any other key = cancel", app_guard.theme.dim)),
+                    ];
+                    let area = centered_rect(50, 25, size);
+                    let dialog = Paragraph::new(text).wrap(Wrap { trim: true })
+                        .block(Block::default().borders(Borders::ALL).title("Protected process").border_style(app_guard.theme.warning));
+                    clear_popup(f, &app_guard, area);
+                    f.render_widget(dialog, area);
+                } else if app_guard.input_mode == InputMode::ConfirmBulkKill {
+                    bulk_kill::draw_bulk_confirm(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::ConfirmRestart {
+                    restart::draw_restart_confirm(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Setup {
+                    setup::draw_setup(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Detail {
+                    app_guard.detail.chain_hits = detail::draw_detail(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::VmStats {
+                    memory_stats::draw_vm_stats(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Network {
+                    network::draw_network(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Disks {
+                    disks::draw_disks(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Sensors {
+                    sensors::draw_sensors(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::FilterBuilder {
+                    filter_builder::draw_filter_builder(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Presets {
+                    presets::draw_presets(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::AlertHistory {
+                    alerts::draw_alert_history(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Zombies {
+                    zombies::draw_zombies(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Trace {
+                    trace::draw_trace(f, &app_guard, size);
+                } else if app_guard.input_mode == InputMode::Viewer {
+                    let height = viewer::draw_viewer(f, &app_guard, size);
+                    if let Some(viewer) = &mut app_guard.viewer { viewer.height = height; }
+                } else if let (Some(command), Some((mx, my))) = (app_guard.hovered_truncated_command(), app_guard.mouse_pos) {
+                    // Tooltip with the full command line, just below (or above) the pointer.
+                    let width = (command.chars().count() as u16 + 2).min(size.width.saturating_sub(4)).max(3);
+                    let lines = (command.chars().count() as u16).div_ceil(width.saturating_sub(2).max(1));
+                    let area = rect_near(mx, my, width, lines + 2, size);
+                    let tooltip = Paragraph::new(command).wrap(Wrap { trim: false })
+                        .block(Block::default().borders(Borders::ALL).style(app_guard.theme.input));
+                    f.render_widget(Clear, area);
+                    f.render_widget(tooltip, area);
+                }
+                if app_guard.debug.shown { debug::draw_debug_overlay(f, &app_guard, size); }
+                if app_guard.accessible { accessible::plain_buffer(f.buffer_mut()); }
+            })?;
+            app_guard.debug.render = render_started.elapsed();
+            app_guard.debug.frames += 1;
+
+            page_size = table_height;
+            app_guard.dirty = false;
 
-            // --- POPUPS (drawn last to be on top) ---
-            if app_guard.input_mode == InputMode::KillMenu {
-                let items: Vec<ListItem> = app_guard.kill_signals.iter().map(|(s, _)| ListItem::new(*s)).collect();
-                let list = List::new(items)
-                    .block(Block::default().borders(Borders::ALL).title("Select signal"))
-                    .highlight_style(Style::default().bg(Color::Blue).fg(Color::White))
-                    .highlight_symbol(">> ");
-
-                let area = centered_rect(20, 30, size);
-                f.render_widget(Clear, area);
-                f.render_stateful_widget(list, area, &mut app_guard.kill_menu_state);
+            if app_guard.alerts.take_bell() {
+                terminal.backend_mut().write_all(b"\x07")?;
+                terminal.backend_mut().flush()?;
             }
-        })?;
 
-        let page_size = table_height;
+            // The title only changes with the data, so this writes it at most once a refresh.
+            if set_title {
+                let title = app_guard.title();
+                if title != last_title {
+                    execute!(terminal.backend_mut(), SetTitle(&title))?;
+                    last_title = title;
+                }
+            }
+        }
         drop(app_guard);
 
-        if event::poll(Duration::from_millis(250))? {
-            if let Event::Key(key) = event::read()? {
-                let mut app = app.lock().unwrap();
-                match app.input_mode {
-                    InputMode::Normal => match key.code {
-                        KeyCode::Char('q') | KeyCode::F(10) => { *running.lock().unwrap() = false; break; }
-                        KeyCode::Char('/') => { app.input_mode = InputMode::Search; app.message = None; }
-                        KeyCode::Char('I') | KeyCode::Char('i') => { let s = app.sort_by; app.set_sort_by(s); }
-                        KeyCode::Char('P') | KeyCode::Char('p') => app.set_sort_by(SortBy::PID),
-                        KeyCode::Char('U') | KeyCode::Char('u') => app.set_sort_by(SortBy::User),
-                        KeyCode::Char('M') | KeyCode::Char('m') => app.set_sort_by(SortBy::MEM),
-                        KeyCode::Char('T') | KeyCode::Char('t') => app.set_sort_by(SortBy::Time),
-                        KeyCode::Char('C') | KeyCode::Char('c') => app.set_sort_by(SortBy::Command),
-                        KeyCode::Down => app.next(),
-                        KeyCode::Up => app.previous(),
-                        KeyCode::PageDown => app.page_down(page_size),
-                        KeyCode::PageUp => app.page_up(page_size),
-                        KeyCode::Home => app.home(),
-                        KeyCode::End => app.end(),
-                        KeyCode::F(5) => app.tree_view = !app.tree_view,
-                        KeyCode::F(9) => { if app.selected_pid().is_some() { app.input_mode = InputMode::KillMenu; } }
-                        KeyCode::Esc => {
-                            if app.active_filter.is_some() {
-                                app.active_filter = None;
+        // Poll quickly right after user activity, back off once the UI has been idle for a while.
+        let poll_timeout = if last_activity.elapsed() < IDLE_AFTER { ACTIVE_POLL } else { IDLE_POLL };
+        if event::poll(poll_timeout)? {
+            let event = event::read()?;
+            last_activity = Instant::now();
+            let mut app = app.lock().unwrap();
+            if !matches!(event, Event::Mouse(_)) { app.dirty = true; }
+            match event {
+                Event::Mouse(mouse) => {
+                    let tooltip_before = app.hovered_truncated_command().is_some();
+                    app.handle_mouse(mouse.kind, mouse.column, mouse.row);
+                    // Moving the pointer only shows when it moves the command tooltip (or takes it
+                    // away); sweeping across the table otherwise draws nothing.
+                    if mouse.kind != MouseEventKind::Moved || tooltip_before || app.hovered_truncated_command().is_some() { app.dirty = true; }
+                }
+                // The first key after an alert only acknowledges it.
+                Event::Key(_) if app.input_mode == InputMode::Normal && app.alerts.banner.is_some() => app.alerts.banner = None,
+                Event::Key(key) => match app.input_mode {
+                    InputMode::Normal => match app.keymap.action(key) {
+                        Some(Action::Quit) => {
+                            *running.lock().unwrap() = false;
+                            app.stop_trace();
+                            break;
+                        }
+                        Some(Action::Search) => { app.input_mode = InputMode::Search; app.message = None; }
+                        Some(Action::Find) => { app.input_mode = InputMode::Find; app.find_query.clear(); app.message = None; }
+                        Some(Action::FindNext) => app.find_next(true),
+                        Some(Action::FindPrevious) => app.find_next(false),
+                        Some(Action::InvertSort) => app.invert_sort_order(),
+                        Some(Action::SortPid) => app.set_sort_by(SortBy::Pid),
+                        Some(Action::SortUser) => app.set_sort_by(SortBy::User),
+                        Some(Action::SortCpu) => app.set_sort_by(SortBy::Cpu),
+                        Some(Action::SortMem) => app.set_sort_by(SortBy::Mem),
+                        Some(Action::SortTime) => app.set_sort_by(SortBy::Time),
+                        Some(Action::SortCpuAvg) => app.set_sort_by(SortBy::CpuAvg),
+                        Some(Action::SortCommand) => app.set_sort_by(SortBy::Command),
+                        Some(Action::SortPorts) => app.set_sort_by(SortBy::Ports),
+                        Some(Action::SortMemDelta) => app.set_sort_by(SortBy::MemDelta),
+                        Some(Action::SortPgrp) => app.set_sort_by(SortBy::Pgrp),
+                        Some(Action::SortSid) => app.set_sort_by(SortBy::Sid),
+                        Some(Action::SortDiskRead) => app.set_sort_by(SortBy::DiskRead),
+                        Some(Action::SortDiskWrite) => app.set_sort_by(SortBy::DiskWrite),
+                        Some(Action::CycleTimeDisplay) => app.cycle_time_display(),
+                        Some(Action::CyclePercentDecimals) => app.cycle_percent_decimals(),
+                        Some(Action::NextFrame) => app.step_replay(1),
+                        Some(Action::PreviousFrame) => app.step_replay(-1),
+                        Some(Action::Down) => app.next(),
+                        Some(Action::Up) => app.previous(),
+                        Some(Action::PageDown) => app.page_down(page_size),
+                        Some(Action::PageUp) => app.page_up(page_size),
+                        Some(Action::Home) => app.home(),
+                        Some(Action::End) => app.end(),
+                        Some(Action::Setup) => app.open_setup(),
+                        Some(Action::ToggleTree) => app.toggle_tree_view(),
+                        Some(Action::ToggleTallRows) => app.toggle_tall_rows(),
+                        Some(Action::ToggleCpuView) => app.toggle_cpu_view(),
+                        Some(Action::ExpandRow) => app.toggle_expanded_row(),
+                        Some(Action::NextScreen) => app.next_screen(),
+                        Some(Action::PreviousScreen) => app.previous_screen(),
+                        Some(Action::RevealInTree) => app.reveal_selected(),
+                        Some(Action::ToggleCollapse) => app.toggle_collapse(),
+                        Some(Action::CollapseAll) => app.collapse_all(),
+                        Some(Action::ExpandAll) => app.expand_all(),
+                        Some(Action::Details) => app.open_detail(),
+                        Some(Action::ProcessMenu) => app.open_action_menu(None),
+                        Some(Action::DebugOverlay) => app.toggle_debug_overlay(),
+                        Some(Action::RevealInFinder) => app.reveal_in_finder(),
+                        Some(Action::OpenConsole) => app.open_console(),
+                        Some(Action::TraceSyscalls) => app.start_trace(),
+                        Some(Action::SampleStacks) => app.start_sampling(false),
+                        Some(Action::SaveStackSample) => app.start_sampling(true),
+                        Some(Action::VmStats) => app.open_vm_stats(),
+                        Some(Action::NetworkInterfaces) => app.open_network(),
+                        Some(Action::Disks) => app.open_disks(),
+                        Some(Action::Sensors) => app.open_sensors(),
+                        Some(Action::FilterBuilder) => app.open_filter_builder(),
+                        Some(Action::FilterPresets) => app.open_presets(),
+                        Some(Action::AlertHistory) => app.input_mode = InputMode::AlertHistory,
+                        Some(Action::Zombies) => app.open_zombies(),
+                        Some(Action::Kill) if app.read_only || app.selected_pid().is_some() => app.open_kill_menu(),
+                        Some(Action::KillMatching) => app.open_bulk_kill_menu(),
+                        Some(Action::CancelKill) => app.cancel_escalations(),
+                        Some(Action::Restart) => app.request_restart(),
+                        Some(Action::NarrowCommand) => app.resize_command_column(-COMMAND_RESIZE_STEP),
+                        Some(Action::WidenCommand) => app.resize_command_column(COMMAND_RESIZE_STEP),
+                        Some(Action::ToggleLeaking) => app.toggle_leaking_only(),
+                        Some(Action::ClearFilter) => {
+                            if app.active_filter.is_some() || app.leaking_only || app.field_filter.is_some() {
+                                app.leaking_only = false;
+                                app.field_filter = None;
+                                app.set_filter(None);
                                 app.search_query.clear();
-                                app.state.select(Some(0));
                             }
                             app.message = None;
                         }
-                        _ => {}
+                        Some(Action::Kill) | None => {}
                     },
                     InputMode::Search => match key.code {
                         KeyCode::Enter => {
                             app.input_mode = InputMode::Normal;
-                            app.active_filter = if app.search_query.is_empty() { None } else { Some(app.search_query.clone()) };
-                            app.state.select(Some(0));
+                            let filter = if app.search_query.is_empty() { None } else { Some(app.search_query.clone()) };
+                            app.set_filter(filter);
+                        }
+                        KeyCode::Tab => {
+                            app.filter_mode = if app.filter_mode == FilterMode::Plain { FilterMode::Fuzzy } else { FilterMode::Plain };
+                            // A filter already applied switches along with it.
+                            if app.active_filter.is_some() { app.rebuild_view(); }
                         }
                         KeyCode::Char(c) => app.search_query.push(c),
                         KeyCode::Backspace => { app.search_query.pop(); },
                         KeyCode::Esc => { app.input_mode = InputMode::Normal; app.search_query.clear(); }
                         _ => {}
                     },
-                    InputMode::KillMenu => match key.code {
-                        KeyCode::Esc | KeyCode::Char('q') => app.input_mode = InputMode::Normal,
-                        KeyCode::Down => app.next_kill_signal(),
-                        KeyCode::Up => app.previous_kill_signal(),
+                    InputMode::Find => match key.code {
                         KeyCode::Enter => {
-                            if let (Some(pid), Some(selected_signal_idx)) = (app.selected_pid(), app.kill_menu_state.selected()) {
-                                let signal = app.kill_signals[selected_signal_idx].1;
-                                match kill_process(pid, signal) {
-                                    Ok(_) => app.message = Some(format!("Sent signal {} to PID {}", signal, pid)),
-                                    Err(e) => app.message = Some(format!("Error killing {}: {}", pid, e)),
-                                }
-                            }
                             app.input_mode = InputMode::Normal;
+                            if !app.find_query.is_empty() {
+                                app.last_find = Some(app.find_query.clone());
+                                app.find_next(true);
+                            }
                         }
+                        KeyCode::Char(c) => app.find_query.push(c),
+                        KeyCode::Backspace => { app.find_query.pop(); },
+                        KeyCode::Esc => app.input_mode = InputMode::Normal,
                         _ => {}
-                    }
+                    },
+                    _ => app.handle_popup_key(key.code),
+                },
+                // ratatui finds the inline box again from the cursor, which drawing leaves
+                // wherever the last change was.
+                Event::Resize(..) if args.inline => {
+                    let top = terminal.get_frame().size().y;
+                    terminal.set_cursor(0, top)?;
+                    terminal.autoresize()?;
                 }
+                _ => {}
             }
         }
     }
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+    if mouse { execute!(terminal.backend_mut(), DisableMouseCapture)?; }
+    if args.inline {
+        // The last frame stays; the prompt comes back on the line below it.
+        let area = terminal.get_frame().size();
+        terminal.set_cursor(0, area.bottom().saturating_sub(1))?;
+        println!();
+    } else {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
+    // Terminals without a title stack ignore the pop; ours is at least cleared there.
+    if set_title { execute!(terminal.backend_mut(), SetTitle(""), PopTitle)?; }
     terminal.show_cursor()?;
     Ok(())
 }
+
+// xterm's title stack (also in iTerm2 and most others): the title from before we started is
+// saved, and put back on exit. crossterm has no command for it.
+struct PushTitle;
+struct PopTitle;
+
+impl crossterm::Command for PushTitle {
+    fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result { f.write_str("\x1b[22;0t") }
+}
+
+impl crossterm::Command for PopTitle {
+    fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result { f.write_str("\x1b[23;0t") }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::alloc::{GlobalAlloc, Layout as AllocLayout, System as StdAlloc};
+    use std::cell::Cell;
+    use source::{Frame as RecordedFrame, RecordedProcess, Replay};
+
+    // Counts the heap allocations made by each thread of the test binary; tests run in parallel,
+    // so one test's count must not pick up another's.
+    struct CountingAlloc;
+    thread_local! {
+        static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn allocations() -> usize {
+        ALLOCATIONS.with(Cell::get)
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: AllocLayout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            unsafe { StdAlloc.alloc(layout) }
+        }
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: AllocLayout) {
+            unsafe { StdAlloc.dealloc(ptr, layout) }
+        }
+    }
+
+    #[global_allocator]
+    static GLOBAL: CountingAlloc = CountingAlloc;
+
+    #[test]
+    fn tall_row_continues_the_command_or_shows_the_executable() {
+        let p = ProcessInfo { command: "node server.js --port 3000".to_string(), exe: "/usr/local/bin/node".to_string(), ..Default::default() };
+        assert_eq!(second_command_line(&p.command, &p, 16, 2), Some("--port 3000"));
+        assert_eq!(second_command_line(&p.command, &p, 80, 2), Some("/usr/local/bin/node"));
+        let full = ProcessInfo { command: "/usr/local/bin/node".to_string(), ..p.clone() };
+        assert_eq!(second_command_line(&full.command, &full, 80, 0), None);
+    }
+
+    #[test]
+    fn expanded_command_wraps_keeping_highlights() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let spans = vec![Span::raw("node "), Span::styled("server", bold), Span::raw(".js --port 3000")];
+        let lines = wrap_spans(spans, 8);
+        let text: Vec<String> = lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect();
+        assert_eq!(text, ["node ser", "ver.js -", "-port 30", "00"]);
+        assert_eq!(lines[1].spans[0].style, bold);
+        // No room at all still ends, a character per line.
+        assert_eq!(wrap_spans(vec![Span::raw("abc")], 0).len(), 3);
+        assert_eq!(wrap_spans(Vec::new(), 8).len(), 1);
+    }
+
+    #[test]
+    fn invert_flips_the_order_and_keeps_the_selection() {
+        let mut app = App::with_processes(&Config::default(), &[(1, 0, 5.0, ""), (2, 0, 50.0, ""), (3, 0, 20.0, "")]);
+        app.select_pid(3);
+        app.invert_sort_order();
+        assert!(app.sort_by == SortBy::Cpu && app.sort_order == SortOrder::Asc);
+        assert_eq!(app.view.iter().map(|&(_, pid)| pid).collect::<Vec<_>>(), [1, 3, 2]);
+        assert_eq!(app.selected_pid(), Some(3));
+    }
+
+    #[test]
+    fn clicks_in_the_kill_menu_pick_and_outside_dismiss() {
+        let mut app = App::new(&Config::default());
+        let click = MouseEventKind::Down(MouseButton::Left);
+        app.input_mode = InputMode::KillMenu;
+        app.kill_menu_state.select(Some(0));
+        app.popup_area.set(Some(Rect::new(10, 10, 30, 12)));
+        app.kill_menu_list = Rect::new(11, 11, 28, 10);
+        app.handle_mouse(click, 15, 13);
+        assert_eq!(app.kill_menu_state.selected(), Some(2));
+        // Below the last signal, and on the border: nothing to pick.
+        app.handle_mouse(click, 15, 11 + app.kill_signals.len() as u16);
+        app.handle_mouse(click, 10, 14);
+        assert_eq!(app.kill_menu_state.selected(), Some(2));
+        app.handle_mouse(MouseEventKind::ScrollDown, 15, 13);
+        assert_eq!(app.kill_menu_state.selected(), Some(3));
+        // The wheel outside scrolls neither the menu nor the table.
+        app.handle_mouse(MouseEventKind::ScrollDown, 1, 1);
+        assert_eq!(app.kill_menu_state.selected(), Some(3));
+        assert!(app.input_mode == InputMode::KillMenu);
+        app.handle_mouse(click, 1, 1);
+        assert!(app.input_mode == InputMode::Normal);
+    }
+
+    #[test]
+    fn top_n_caps_the_flat_list_but_not_a_filtered_one() {
+        let list = [(1, 0, 5.0, "p1"), (2, 0, 50.0, "p2"), (3, 0, 20.0, "p3"), (4, 0, 1.0, "p4"), (5, 0, 30.0, "p5")];
+        let mut app = App::with_processes(&Config { top_n: 2, ..Config::default() }, &list);
+        assert_eq!(app.view.iter().map(|&(_, pid)| pid).collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(app.sorted_upto, 2);
+        // The filter picks from everything, in full sort order.
+        app.set_filter(Some("p".to_string()));
+        assert_eq!(app.view.iter().map(|&(_, pid)| pid).collect::<Vec<_>>(), [2, 5, 3, 1, 4]);
+        app.set_filter(None);
+        assert_eq!(app.view.len(), 2);
+        app.tree_view = true;
+        app.rebuild_view();
+        assert_eq!(app.sorted_upto, 5);
+    }
+
+    #[test]
+    fn sorting_in_the_tree_reorders_siblings_only() {
+        let list = [(1, 0, 1.0, ""), (10, 1, 5.0, ""), (11, 10, 1.0, ""), (12, 10, 40.0, ""), (20, 1, 30.0, "")];
+        let mut app = App::with_processes(&Config { tree_view: true, ..Config::default() }, &list);
+        // Under CPU sort the hotter subtree and the hotter child come first, each under its parent.
+        assert_eq!(app.view, [(0, 1), (1, 20), (1, 10), (2, 12), (2, 11)]);
+        app.set_sort_by(SortBy::Pid);
+        assert_eq!(app.view, [(0, 1), (1, 10), (2, 11), (2, 12), (1, 20)]);
+        app.invert_sort_order();
+        assert_eq!(app.view, [(0, 1), (1, 20), (1, 10), (2, 12), (2, 11)]);
+        assert_eq!(app.message, None);
+
+        app.config.tree_pid_order = true;
+        app.set_sort_by(SortBy::Cpu);
+        assert_eq!(app.view, [(0, 1), (1, 10), (2, 11), (2, 12), (1, 20)]);
+        assert!(app.message.as_deref().is_some_and(|m| m.contains("tree_pid_order")));
+    }
+
+    #[test]
+    fn the_tree_honors_the_filter_and_the_selection_stays_on_its_row() {
+        let list = [(1, 0, 0.0, "launchd"), (10, 1, 0.0, "zsh"), (11, 10, 0.0, "python3 a.py"), (12, 10, 0.0, "vim"), (20, 1, 0.0, "python3 b.py"), (30, 1, 0.0, "Finder")];
+        let mut app = App::with_processes(&Config { sort_by: SortBy::Pid, ..Config::default() }, &list);
+        app.search_query = "python".to_string();
+        app.set_filter(Some("python".to_string()));
+        app.state.select(Some(1));
+        assert_eq!(app.selected_pid(), Some(20));
+
+        // Matches stay in place under the ancestors that lead to them; the rest of the tree goes.
+        app.toggle_tree_view();
+        assert_eq!(app.view, [(0, 1), (1, 10), (2, 11), (1, 20)]);
+        assert_eq!(app.get_list_length(), app.view.len());
+        assert_eq!(app.selected_pid(), Some(20));
+        assert_eq!(app.view[app.state.selected().unwrap()].1, 20);
+        app.end();
+        assert_eq!(app.state.selected(), Some(3));
+        app.previous();
+        assert_eq!(app.selected_pid(), Some(11));
+
+        app.toggle_tree_view();
+        assert_eq!(app.view, [(0, 11), (0, 20)]);
+        assert_eq!(app.selected_pid(), Some(11));
+        assert_eq!(app.view[app.state.selected().unwrap()].1, 11);
+        app.set_filter(None);
+        app.toggle_tree_view();
+        assert_eq!(app.get_list_length(), 6);
+    }
+
+    #[test]
+    fn steady_state_refresh_barely_allocates() {
+        let processes = (1..=300).map(|pid| RecordedProcess {
+            pid,
+            ppid: Some(1),
+            uid: None,
+            name: format!("worker{}", pid),
+            cmd: vec![format!("/usr/libexec/worker{}", pid), "--serve".to_string()],
+            exe: Some(format!("/usr/libexec/worker{}", pid)),
+            status: "S".to_string(),
+            cpu_usage: pid as f32 / 10.0,
+            memory: pid as u64 * 4096,
+            virtual_memory: pid as u64 * 65536,
+            run_time: 600,
+            cpu_time: Some(Duration::from_millis(pid as u64)),
+            threads: Some(4),
+            disk_read: Some(0),
+            disk_written: Some(0),
+        }).collect();
+        let mut source = Replay::from_frames(vec![RecordedFrame { processes, ..Default::default() }]).unwrap();
+        let mut app = App::new(&Config::default());
+        app.update_processes(&mut source);
+        assert_eq!(app.processes.len(), 300);
+
+        // Same snapshot again: every entry already exists and nothing changed, so the only
+        // allocations left should be incidental (previously: several Strings per process).
+        let before = allocations();
+        app.update_processes(&mut source);
+        let allocations = allocations() - before;
+        assert!(allocations < app.processes.len(), "{} allocations for {} processes", allocations, app.processes.len());
+        assert_eq!(app.sorted_pids.len(), app.processes.len());
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::Instant;
+
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+use crate::format::format_bytes;
+use crate::platform::{self, VmStats};
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+impl App {
+    // 'v': the whole `vm_stat` picture behind the memory meter.
+    pub fn open_vm_stats(&mut self) {
+        self.input_mode = InputMode::VmStats;
+        // The breakdown meter may have sampled moments ago; another sample now would give rates
+        // over a few milliseconds.
+        if self.vm.is_none() { self.sample_vm(); }
+    }
+
+    // Called on every refresh. The OS is only asked while the meter or the popup shows the
+    // numbers; the previous sample is kept for per-second rates.
+    pub fn sample_vm(&mut self) {
+        if !self.show_mem_breakdown && self.input_mode != InputMode::VmStats {
+            (self.vm, self.vm_previous, self.vm_sampled_at) = (None, None, None);
+            return;
+        }
+        let now = Instant::now();
+        let last = self.vm.zip(self.vm_sampled_at);
+        self.vm = platform::vm_stats();
+        self.vm_previous = last.map(|(vm, at)| (vm, now - at));
+        self.vm_sampled_at = Some(now);
+    }
+
+    pub fn handle_vm_stats_key(&mut self, code: KeyCode) {
+        if matches!(code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('v')) {
+            self.input_mode = InputMode::Normal;
+        }
+    }
+}
+
+pub fn draw_vm_stats(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(60, 70, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Virtual memory");
+    let Some(vm) = app.vm else {
+        return f.render_widget(Paragraph::new("VM statistics aren't available on this system.  Esc to close").block(block), area);
+    };
+    let label = app.theme.label;
+    let size = |name: &'static str, bytes: u64| Line::from(vec![Span::styled(format!("{:<16}", name), label), Span::raw(format_bytes(bytes))]);
+    let ratio = if vm.compressed > 0 { format!(", {:.1}x", vm.compressor_holds as f64 / vm.compressed as f64) } else { String::new() };
+    let mut lines = vec![
+        size("Page size", vm.page_size),
+        Line::from(""),
+        size("Free", vm.free),
+        size("Active", vm.active),
+        size("Inactive", vm.inactive),
+        size("Speculative", vm.speculative),
+        size("Wired", vm.wired),
+        size("Purgeable", vm.purgeable),
+        Line::from(vec![
+            Span::styled(format!("{:<16}", "Compressed"), label),
+            Span::raw(format!("{} (holding {}{})", format_bytes(vm.compressed), format_bytes(vm.compressor_holds), ratio)),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(format!("{:<16}{:>14}{:>12}", "Pages", "since boot", "per second"), app.theme.column_header)),
+    ];
+    // Rates need two samples; until the next refresh the column stays empty.
+    let previous = app.vm_previous.filter(|(_, dt)| !dt.is_zero());
+    let counter = |name: &'static str, get: fn(&VmStats) -> u64| {
+        let rate = previous.map_or(String::new(), |(old, dt)| format!("{:.0}", get(&vm).saturating_sub(get(&old)) as f64 / dt.as_secs_f64()));
+        Line::from(vec![Span::styled(format!("{:<16}", name), label), Span::raw(format!("{:>14}{:>12}", get(&vm), rate))])
+    };
+    lines.extend([
+        counter("Faults", |v| v.faults),
+        counter("Page-ins", |v| v.pageins),
+        counter("Page-outs", |v| v.pageouts),
+        counter("Swap-ins", |v| v.swapins),
+        counter("Swap-outs", |v| v.swapouts),
+        counter("Compressions", |v| v.compressions),
+        counter("Decompressions", |v| v.decompressions),
+        Line::from(""),
+        Line::from(Span::styled("Esc close", app.theme.dim)),
+    ]);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
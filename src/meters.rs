@@ -0,0 +1,276 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Gauge, Paragraph, Wrap};
+use ratatui::Frame;
+use serde::{Deserialize, Serialize};
+
+use crate::format::{format_bytes, format_uptime};
+use crate::theme::Theme;
+use crate::{platform, App};
+
+// Everything that can be placed in the header's left or right column.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Meter {
+    CpuPerCore,
+    CpuAverage,
+    CpuSplit, // "CPU usage: 12.3% user, 4.1% sys, 83.6% idle"
+    Memory,
+    Swap,
+    Tasks,
+    LoadAverage,
+    Uptime,
+    Battery,
+    Network,
+}
+
+// What the per-core meter shows, switched with 'g': a gauge for each core, or one for them all
+// when there are too many cores to leave room for anything else.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CpuView {
+    #[default]
+    PerCore,
+    Aggregate,
+}
+
+impl Meter {
+    pub const ALL: [Meter; 10] = [
+        Meter::CpuPerCore, Meter::CpuAverage, Meter::CpuSplit, Meter::Memory, Meter::Swap, Meter::Tasks,
+        Meter::LoadAverage, Meter::Uptime, Meter::Battery, Meter::Network,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Meter::CpuPerCore => "CPUs (per core)",
+            Meter::CpuAverage => "CPU (average)",
+            Meter::CpuSplit => "CPU user/sys/idle",
+            Meter::Memory => "Memory",
+            Meter::Swap => "Swap",
+            Meter::Tasks => "Task counter",
+            Meter::LoadAverage => "Load average",
+            Meter::Uptime => "Uptime",
+            Meter::Battery => "Battery",
+            Meter::Network => "Network",
+        }
+    }
+
+    // Rows this meter occupies in the header. The CPU split takes none where the OS has no
+    // such numbers, once that is known.
+    pub fn height(self, app: &App) -> u16 {
+        match self.shown(app) {
+            None => 0,
+            Some(Meter::CpuPerCore) => 2, // Title row plus the gauges
+            Some(Meter::CpuSplit) if app.cpu_split.is_none() && !app.collecting() => 0,
+            _ => 1,
+        }
+    }
+
+    // The per-core meter collapses to the average with `CpuView::Aggregate`, or to nothing when
+    // the average is in the header already.
+    fn shown(self, app: &App) -> Option<Meter> {
+        if self != Meter::CpuPerCore || app.cpu_view == CpuView::PerCore { return Some(self); }
+        let average_shown = app.header_left.iter().chain(&app.header_right).any(|&m| m == Meter::CpuAverage);
+        (!average_shown).then_some(Meter::CpuAverage)
+    }
+}
+
+impl App {
+    pub fn toggle_cpu_view(&mut self) {
+        self.cpu_view = if self.cpu_view == CpuView::PerCore { CpuView::Aggregate } else { CpuView::PerCore };
+        self.announce(if self.cpu_view == CpuView::PerCore { "CPU gauge for each core" } else { "One CPU gauge for all cores" }.to_string());
+    }
+}
+
+fn column_height(meters: &[Meter], app: &App) -> u16 {
+    meters.iter().map(|m| m.height(app)).sum()
+}
+
+// Height of the header for the configured meters: the taller of the two columns.
+pub fn header_height(app: &App) -> u16 {
+    column_height(&app.header_left, app).max(column_height(&app.header_right, app))
+}
+
+pub fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    // A column without meters gives its width to the other one.
+    let columns: Vec<&[Meter]> = [&app.header_left[..], &app.header_right[..]].into_iter().filter(|c| !c.is_empty()).collect();
+    if columns.is_empty() {
+        return;
+    }
+    let column_areas = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns.len() as u32); columns.len()])
+        .split(area);
+    for (meters, column_area) in columns.iter().zip(column_areas.iter()) {
+        let constraints: Vec<Constraint> = meters.iter().map(|m| Constraint::Length(m.height(app))).chain([Constraint::Min(0)]).collect();
+        let rows = Layout::default().direction(Direction::Vertical).constraints(constraints).split(*column_area);
+        for (meter, row) in meters.iter().zip(rows.iter()) {
+            draw_meter(f, app, *meter, *row);
+        }
+    }
+}
+
+// The whole header on one line, for small terminals: "CPU 12.3%  Mem 4.1G/16.0G  Load 1.23  Tasks 312".
+pub fn draw_summary(f: &mut Frame, app: &App, area: Rect) {
+    if app.collecting() {
+        return f.render_widget(Paragraph::new("Collecting…").style(app.theme.dim), area);
+    }
+    let line = Line::from(vec![
+        Span::styled(format!("CPU {:.1}%", app.cpu_average()), app.theme.cpu),
+        Span::styled(format!("  Mem {}/{}", format_bytes(app.used_mem), format_bytes(app.total_mem)), app.theme.memory),
+        Span::raw(format!("  Load {:.2}  Tasks {}", app.load_avg.one, app.task_counts.total)),
+    ]);
+    f.render_widget(Paragraph::new(line), area);
+}
+
+fn draw_meter(f: &mut Frame, app: &App, meter: Meter, area: Rect) {
+    let Some(meter) = meter.shown(app) else { return };
+    if app.collecting() {
+        // Nothing sampled yet: a grayed-out name rather than zeros that look like data.
+        return f.render_widget(Paragraph::new(format!("{} …", meter.name())).style(app.theme.dim), area);
+    }
+    match meter {
+        // Readers get the figures as text, on the lines the gauges would take.
+        Meter::CpuPerCore if app.accessible => {
+            let text: Vec<String> = app.cpus.iter().enumerate().map(|(i, usage)| format!("CPU{}: {:.0}%", i + 1, usage)).collect();
+            f.render_widget(Paragraph::new(text.join(", ")).wrap(Wrap { trim: true }), area);
+        }
+        Meter::CpuAverage if app.accessible => f.render_widget(Paragraph::new(format!("CPU: {:.1}%", app.cpu_average())), area),
+        Meter::CpuPerCore => {
+            let num_cpus = app.cpus.len();
+            if num_cpus > 0 {
+                let cpu_constraints: Vec<Constraint> = (0..num_cpus).map(|_| Constraint::Ratio(1, num_cpus as u32)).collect();
+                let cpu_chunks = Layout::default().direction(Direction::Horizontal).constraints(cpu_constraints).split(area);
+                for (i, &cpu_usage) in app.cpus.iter().enumerate() {
+                    let title = match app.cpu_freqs.get(i).filter(|&&mhz| mhz > 0) {
+                        Some(mhz) => format!("CPU{} {:.1}GHz", i + 1, *mhz as f64 / 1000.0),
+                        None => format!("CPU{}", i + 1),
+                    };
+                    let gauge = Gauge::default().block(Block::default().title(title)).percent(cpu_usage as u16).gauge_style(app.theme.cpu);
+                    f.render_widget(gauge, cpu_chunks[i]);
+                }
+            }
+        }
+        Meter::CpuAverage => {
+            let avg = app.cpu_average();
+            let gauge = Gauge::default().percent(avg.clamp(0.0, 100.0) as u16).label(format!("CPU {:.1}%", avg)).gauge_style(app.theme.cpu);
+            f.render_widget(gauge, area);
+        }
+        Meter::CpuSplit => {
+            let Some(split) = app.cpu_split else { return };
+            let line = Line::from(vec![
+                Span::raw("CPU usage: "),
+                Span::styled(format!("{:.1}% user", split.user), app.theme.cpu),
+                Span::raw(", "),
+                Span::styled(format!("{:.1}% sys", split.system), app.theme.warning),
+                Span::raw(", "),
+                Span::styled(format!("{:.1}% idle", split.idle), app.theme.dim),
+            ]);
+            f.render_widget(Paragraph::new(line), area);
+        }
+        Meter::Memory => {
+            if let Some(vm) = app.vm.as_ref().filter(|_| app.show_mem_breakdown && app.accessible) {
+                let text = format!("Mem: wired {}, active {}, inactive {}, compressed {}",
+                    format_bytes(vm.wired), format_bytes(vm.active), format_bytes(vm.inactive), format_bytes(vm.compressed));
+                f.render_widget(Paragraph::new(text).style(app.theme.memory), area);
+            } else if let Some(vm) = app.vm.as_ref().filter(|_| app.show_mem_breakdown) {
+                f.render_widget(Paragraph::new(memory_breakdown_line(vm, app.total_mem, area.width, &app.theme)), area);
+            } else {
+                let mem_text = format!("Mem[{} / {}MiB]", app.used_mem / 1024 / 1024, app.total_mem / 1024 / 1024);
+                f.render_widget(Paragraph::new(mem_text).style(app.theme.memory), area);
+            }
+        }
+        Meter::Swap => {
+            let swp_text = format!("Swp[{} / {}MiB]", app.used_swap / 1024 / 1024, app.total_swap / 1024 / 1024);
+            f.render_widget(Paragraph::new(swp_text).style(app.theme.swap), area);
+        }
+        Meter::Tasks => {
+            let counts = &app.task_counts;
+            let mut line = Line::from(vec![
+                Span::raw(format!("Tasks: {}, {} thr, {} kthr; ", counts.total, counts.threads, counts.kernel_threads)),
+                Span::styled(format!("{} running", counts.running), app.theme.cpu),
+            ]);
+            if counts.zombies > 0 {
+                line.spans.push(Span::raw(", "));
+                line.spans.push(Span::styled(format!("{} zombie", counts.zombies), app.theme.warning));
+            }
+            f.render_widget(Paragraph::new(line), area);
+        }
+        Meter::LoadAverage => {
+            let text = format!("Load average: {:.2} {:.2} {:.2}", app.load_avg.one, app.load_avg.five, app.load_avg.fifteen);
+            f.render_widget(Paragraph::new(text), area);
+        }
+        Meter::Uptime => f.render_widget(Paragraph::new(format!("Uptime: {}", format_uptime(app.uptime))), area),
+        Meter::Battery => {
+            let text = match &app.battery {
+                Some(b) => format!("Battery: {}% ({})", b.percent, b.state),
+                None => "Battery: n/a".to_string(),
+            };
+            f.render_widget(Paragraph::new(text), area);
+        }
+        Meter::Network => {
+            let text = match app.net_rate {
+                Some((rx, tx)) => format!("Network: rx {}/s tx {}/s", format_bytes(rx), format_bytes(tx)),
+                None => "Network: -".to_string(),
+            };
+            f.render_widget(Paragraph::new(text), area);
+        }
+    }
+}
+
+// "Mem[████▓▓▓░░    ] W 2.1G A 4.0G I 1.2G C 512M": one bar segment per memory category,
+// like Activity Monitor's memory tab, followed by a color-keyed legend.
+fn memory_breakdown_line(vm: &platform::VmStats, total: u64, width: u16, theme: &Theme) -> Line<'static> {
+    let [wired, active, inactive, compressed] = theme.mem_segments;
+    let segments = [
+        ("W", vm.wired, wired),
+        ("A", vm.active, active),
+        ("I", vm.inactive, inactive),
+        ("C", vm.compressed, compressed),
+    ];
+    let mut legend = Vec::new();
+    let mut legend_len = 0;
+    for (label, bytes, color) in segments {
+        let text = format!(" {} {}", label, format_bytes(bytes));
+        legend_len += text.chars().count();
+        legend.push(Span::styled(text, Style::default().fg(color)));
+    }
+    let bar_width = (width as usize).saturating_sub(legend_len + 5); // "Mem[" + "]"
+    let total = total.max(vm.wired + vm.active + vm.inactive + vm.compressed + vm.free).max(1);
+
+    let mut spans = vec![Span::styled("Mem[", theme.memory)];
+    let mut used_cells = 0;
+    for (_, bytes, color) in segments {
+        let cells = ((bytes as f64 / total as f64) * bar_width as f64).round() as usize;
+        let cells = cells.min(bar_width - used_cells);
+        used_cells += cells;
+        spans.push(Span::styled("█".repeat(cells), Style::default().fg(color)));
+    }
+    spans.push(Span::raw(" ".repeat(bar_width - used_cells)));
+    spans.push(Span::styled("]", theme.memory));
+    spans.extend(legend);
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn the_aggregate_view_gives_back_header_rows() {
+        let mut app = App::new(&Config::default());
+        app.header_left = vec![Meter::CpuPerCore, Meter::Memory];
+        app.header_right = vec![Meter::Tasks];
+        assert_eq!(header_height(&app), 3);
+        app.toggle_cpu_view();
+        assert_eq!(Meter::CpuPerCore.shown(&app), Some(Meter::CpuAverage));
+        assert_eq!(header_height(&app), 2);
+        // The average is there already; it isn't shown twice.
+        app.header_right.push(Meter::CpuAverage);
+        assert_eq!(Meter::CpuPerCore.shown(&app), None);
+        assert_eq!(header_height(&app), 2);
+        app.toggle_cpu_view();
+        assert_eq!(header_height(&app), 3);
+    }
+}
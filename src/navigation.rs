@@ -0,0 +1,139 @@
+// Moving a selection through a list of `len` rows. Pure index arithmetic; the App applies the
+// results to its TableState.
+
+// Down one row, wrapping from the last row to the first.
+pub fn next(selected: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 { return None; }
+    Some(match selected {
+        Some(i) if i + 1 < len => i + 1,
+        _ => 0,
+    })
+}
+
+// Up one row, wrapping from the first row to the last.
+pub fn previous(selected: Option<usize>, len: usize) -> Option<usize> {
+    if len == 0 { return None; }
+    Some(match selected {
+        Some(i) if i > 0 && i < len => i - 1,
+        _ => len - 1,
+    })
+}
+
+// Page moves stop at the ends instead of wrapping.
+pub fn page_down(selected: Option<usize>, len: usize, page: usize) -> Option<usize> {
+    if len == 0 { return None; }
+    Some((selected.unwrap_or(0) + page).min(len - 1))
+}
+
+pub fn page_up(selected: Option<usize>, len: usize, page: usize) -> Option<usize> {
+    if len == 0 { return None; }
+    Some(selected.unwrap_or(0).saturating_sub(page).min(len - 1))
+}
+
+pub fn home(len: usize) -> Option<usize> {
+    if len == 0 { None } else { Some(0) }
+}
+
+pub fn end(len: usize) -> Option<usize> {
+    len.checked_sub(1)
+}
+
+// First visible row for a viewport of `height` rows: scrolls just enough to keep `selected` in
+// view, and never leaves empty space below the last row.
+pub fn scroll_offset(selected: usize, offset: usize, height: usize, len: usize) -> usize {
+    let mut offset = offset;
+    if selected < offset {
+        offset = selected;
+    } else if height > 0 && selected >= offset + height {
+        offset = selected + 1 - height;
+    }
+    offset.min(len.saturating_sub(height))
+}
+
+// First visible row that puts `selected` in the middle of the viewport, as far as the ends of
+// the list allow (vim's `scrolloff` set to half the screen).
+pub fn centered_offset(selected: usize, height: usize, len: usize) -> usize {
+    selected.saturating_sub(height / 2).min(len.saturating_sub(height))
+}
+
+// The next row after `selected` (or before it, going back) for which `matches` holds, wrapping
+// around the list. The selected row itself is checked last, so a lone match is found again.
+pub fn find(selected: Option<usize>, len: usize, forward: bool, matches: impl Fn(usize) -> bool) -> Option<usize> {
+    if len == 0 { return None; }
+    // With nothing selected, start from whichever end we're heading away from.
+    let start = selected.filter(|&i| i < len).unwrap_or(if forward { len - 1 } else { 0 });
+    (1..=len).map(|step| if forward { (start + step) % len } else { (start + len - step) % len }).find(|&i| matches(i))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_and_previous_wrap_around() {
+        assert_eq!(next(Some(2), 3), Some(0));
+        assert_eq!(next(Some(0), 3), Some(1));
+        assert_eq!(previous(Some(0), 3), Some(2));
+        assert_eq!(previous(Some(2), 3), Some(1));
+        // Nothing selected yet: start from whichever end we're heading away from.
+        assert_eq!(next(None, 3), Some(0));
+        assert_eq!(previous(None, 3), Some(2));
+    }
+
+    #[test]
+    fn stale_selection_past_the_end_recovers() {
+        // The list shrank under the selection between refreshes.
+        assert_eq!(next(Some(10), 3), Some(0));
+        assert_eq!(previous(Some(10), 3), Some(2));
+        assert_eq!(page_up(Some(10), 3, 1), Some(2));
+    }
+
+    #[test]
+    fn empty_list_selects_nothing() {
+        assert_eq!(next(Some(0), 0), None);
+        assert_eq!(previous(None, 0), None);
+        assert_eq!(page_down(None, 0, 10), None);
+        assert_eq!(page_up(None, 0, 10), None);
+        assert_eq!(home(0), None);
+        assert_eq!(end(0), None);
+    }
+
+    #[test]
+    fn paging_clamps_at_the_ends() {
+        assert_eq!(page_down(Some(5), 20, 10), Some(15));
+        assert_eq!(page_down(Some(15), 20, 10), Some(19));
+        assert_eq!(page_up(Some(5), 20, 10), Some(0));
+        assert_eq!(end(20), Some(19));
+    }
+
+    #[test]
+    fn scroll_keeps_selection_visible() {
+        assert_eq!(scroll_offset(0, 0, 10, 100), 0);
+        assert_eq!(scroll_offset(12, 0, 10, 100), 3);
+        assert_eq!(scroll_offset(4, 8, 10, 100), 4);
+        // Shrinking list: don't leave blank rows at the bottom.
+        assert_eq!(scroll_offset(15, 50, 10, 20), 10);
+        assert_eq!(scroll_offset(0, 3, 10, 5), 0);
+    }
+
+    #[test]
+    fn find_wraps_and_comes_back_to_a_lone_match() {
+        let rows = ["launchd", "zsh", "Safari", "zsh", "cargo"];
+        let zsh = |i: usize| rows[i] == "zsh";
+        assert_eq!(find(Some(1), rows.len(), true, zsh), Some(3));
+        assert_eq!(find(Some(3), rows.len(), true, zsh), Some(1));
+        assert_eq!(find(Some(1), rows.len(), false, zsh), Some(3));
+        assert_eq!(find(None, rows.len(), true, zsh), Some(1));
+        assert_eq!(find(Some(2), rows.len(), true, |i| rows[i] == "Safari"), Some(2));
+        assert_eq!(find(Some(0), rows.len(), true, |i| rows[i] == "fish"), None);
+        assert_eq!(find(None, 0, true, zsh), None);
+    }
+
+    #[test]
+    fn centered_scroll_stops_at_the_ends() {
+        assert_eq!(centered_offset(50, 10, 100), 45);
+        assert_eq!(centered_offset(2, 10, 100), 0);
+        assert_eq!(centered_offset(98, 10, 100), 90);
+        assert_eq!(centered_offset(3, 10, 5), 0);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use sysinfo::Networks;
+
+use crate::format::format_bytes;
+use crate::platform::{self, InterfaceLink};
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// One row of the network screen.
+#[derive(Clone, Default)]
+pub struct Interface {
+    pub name: String,
+    pub link: InterfaceLink,
+    pub mac: String,
+    pub total: (u64, u64), // Bytes received and sent since boot
+    pub rate: Option<(u64, u64)>, // Bytes per second in and out since the last refresh; None at first
+    pub packets: (u64, u64),
+    pub errors: (u64, u64),
+}
+
+// The network screen ('w'): sampled on each refresh while it is open, and forgotten on closing.
+#[derive(Default)]
+pub struct NetworkScreen {
+    interfaces: Vec<Interface>,
+    sampled_at: Option<Instant>,
+    selected: usize,
+}
+
+impl App {
+    pub fn open_network(&mut self) {
+        self.input_mode = InputMode::Network;
+        self.sample_interfaces();
+    }
+
+    // Called on every refresh. sysinfo's list is refreshed too, so interfaces that came or went
+    // (a VPN connecting, a dock unplugged) show up or disappear with it.
+    pub fn sample_interfaces(&mut self) {
+        if self.input_mode != InputMode::Network {
+            self.network = NetworkScreen::default();
+            return;
+        }
+        let networks = self.networks.get_or_insert_with(Networks::new);
+        networks.refresh_list();
+        let mut links = platform::interfaces();
+        let current = networks.iter().map(|(name, data)| Interface {
+            name: name.clone(),
+            link: links.remove(name).unwrap_or_default(),
+            mac: data.mac_address().to_string(),
+            total: (data.total_received(), data.total_transmitted()),
+            rate: None,
+            packets: (data.total_packets_received(), data.total_packets_transmitted()),
+            errors: (data.total_errors_on_received(), data.total_errors_on_transmitted()),
+        }).collect();
+        let now = Instant::now();
+        let elapsed = self.network.sampled_at.map(|at| (now - at).as_secs_f64());
+        let selected = self.network.interfaces.get(self.network.selected).map(|i| i.name.clone());
+        self.network.interfaces = merge(&self.network.interfaces, current, elapsed);
+        self.network.sampled_at = Some(now);
+        // The selection follows its interface, or stays on the same row if it went away.
+        let found = selected.and_then(|name| self.network.interfaces.iter().position(|i| i.name == name));
+        self.network.selected = found.unwrap_or(self.network.selected).min(self.network.interfaces.len().saturating_sub(1));
+    }
+
+    pub fn handle_network_key(&mut self, code: KeyCode) {
+        let len = self.network.interfaces.len();
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('w') => self.input_mode = InputMode::Normal,
+            KeyCode::Down if len > 0 => self.network.selected = (self.network.selected + 1).min(len - 1),
+            KeyCode::Up => self.network.selected = self.network.selected.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
+
+// This refresh's interfaces with rates against the previous ones, in display order: those in
+// use first, then inactive ones, loopback last; by name within each group so rows stay put.
+fn merge(previous: &[Interface], mut current: Vec<Interface>, elapsed: Option<f64>) -> Vec<Interface> {
+    let before: HashMap<&str, (u64, u64)> = previous.iter().map(|i| (i.name.as_str(), i.total)).collect();
+    for interface in &mut current {
+        let (Some(&(rx, tx)), Some(elapsed)) = (before.get(interface.name.as_str()), elapsed.filter(|&e| e > 0.0)) else { continue };
+        let per_second = |now: u64, then: u64| (now.saturating_sub(then) as f64 / elapsed) as u64;
+        interface.rate = Some((per_second(interface.total.0, rx), per_second(interface.total.1, tx)));
+    }
+    current.sort_by(|a, b| (a.link.loopback, !a.link.up, &a.name).cmp(&(b.link.loopback, !b.link.up, &b.name)));
+    current
+}
+
+pub fn draw_network(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(80, 70, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Network interfaces");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [list_area, detail_area] = Layout::vertical([Constraint::Min(3), Constraint::Length(6)]).areas(inner);
+
+    let interfaces = &app.network.interfaces;
+    let rate = |r: Option<(u64, u64)>, pick: fn((u64, u64)) -> u64| r.map_or("-".to_string(), |r| format!("{}/s", format_bytes(pick(r))));
+    let rows: Vec<Row> = interfaces.iter().map(|i| {
+        let address = match i.link.addresses.len() {
+            0 => String::new(),
+            1 => i.link.addresses[0].to_string(),
+            n => format!("{} (+{})", i.link.addresses[0], n - 1),
+        };
+        let row = Row::new([
+            Cell::from(i.name.as_str()),
+            Cell::from(if i.link.up { "up" } else { "down" }),
+            Cell::from(address),
+            Cell::from(rate(i.rate, |r| r.0)),
+            Cell::from(rate(i.rate, |r| r.1)),
+            Cell::from(format_bytes(i.total.0)),
+            Cell::from(format_bytes(i.total.1)),
+        ]);
+        if i.link.up && !i.link.loopback { row } else { row.style(app.theme.dim) }
+    }).collect();
+    let header = Row::new(["NAME", "STATE", "ADDRESS", "IN/S", "OUT/S", "IN", "OUT"]).style(app.theme.column_header);
+    let widths = [Constraint::Length(10), Constraint::Length(5), Constraint::Min(20), Constraint::Length(9), Constraint::Length(9), Constraint::Length(7), Constraint::Length(7)];
+    let table = Table::new(rows, widths).header(header).highlight_style(app.theme.selection).highlight_symbol(app.selection_marker(""));
+    let mut state = TableState::default().with_selected((!interfaces.is_empty()).then_some(app.network.selected));
+    f.render_stateful_widget(table, list_area, &mut state);
+
+    let label = |name: &'static str| Span::styled(format!("{:<11}", name), app.theme.label);
+    let mut lines = match interfaces.get(app.network.selected) {
+        Some(i) => {
+            let addresses: Vec<String> = i.link.addresses.iter().map(ToString::to_string).collect();
+            vec![
+                Line::from(vec![label("Addresses"), Span::raw(if addresses.is_empty() { "none".to_string() } else { addresses.join(", ") })]),
+                Line::from(vec![label("MAC"), Span::raw(i.mac.as_str())]),
+                Line::from(vec![label("Packets"), Span::raw(format!("{} in, {} out", i.packets.0, i.packets.1))]),
+                Line::from(vec![
+                    label("Errors"),
+                    Span::raw(format!("{} in, {} out; {} dropped", i.errors.0, i.errors.1, i.link.drops.map_or("-".to_string(), |d| d.to_string()))),
+                ]),
+            ]
+        }
+        None => vec![Line::from("No network interfaces")],
+    };
+    lines.resize(4, Line::default());
+    lines.extend([Line::from(""), Line::from(Span::styled("↑/↓ select   Esc close", app.theme.dim))]);
+    f.render_widget(Paragraph::new(lines), detail_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interface(name: &str, up: bool, loopback: bool, total: u64) -> Interface {
+        Interface { name: name.to_string(), link: InterfaceLink { up, loopback, ..Default::default() }, total: (total, total / 2), ..Default::default() }
+    }
+
+    #[test]
+    fn rates_need_two_samples_and_loopback_sorts_last() {
+        let first = merge(&[], vec![interface("lo0", true, true, 0), interface("en0", true, false, 1000), interface("en1", false, false, 0)], None);
+        assert_eq!(first.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(), ["en0", "en1", "lo0"]);
+        assert!(first.iter().all(|i| i.rate.is_none()));
+
+        // The VPN came up and en1 went away: only en0 has something to compare against.
+        let second = merge(&first, vec![interface("en0", true, false, 5000), interface("utun3", true, false, 300), interface("lo0", true, true, 0)], Some(2.0));
+        assert_eq!(second.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(), ["en0", "utun3", "lo0"]);
+        assert_eq!(second[0].rate, Some((2000, 1000)));
+        assert_eq!(second[1].rate, None);
+    }
+}
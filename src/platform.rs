@@ -0,0 +1,898 @@
+// Per-process details that sysinfo doesn't expose, read straight from the OS.
+// Everything here is best-effort: a `None` means "not available" (no permission,
+// process gone, or unsupported platform) and callers render it as such.
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy)]
+pub struct TaskInfo {
+    pub cpu_time: Duration, // User + system CPU time consumed so far
+    pub threads: u32,
+}
+
+#[cfg(target_os = "macos")]
+pub fn task_info(pid: u32) -> Option<TaskInfo> {
+    use std::sync::OnceLock;
+
+    // proc_taskinfo reports times in Mach absolute time units, which are only
+    // nanoseconds on Intel; Apple Silicon needs the timebase conversion.
+    static TIMEBASE: OnceLock<(u64, u64)> = OnceLock::new();
+    let &(numer, denom) = TIMEBASE.get_or_init(|| {
+        let mut info = libc::mach_timebase_info { numer: 0, denom: 0 };
+        if unsafe { libc::mach_timebase_info(&mut info) } == 0 && info.denom != 0 {
+            (info.numer as u64, info.denom as u64)
+        } else {
+            (1, 1)
+        }
+    });
+
+    let info = proc_taskinfo(pid)?;
+    let ticks = info.pti_total_user + info.pti_total_system;
+    Some(TaskInfo {
+        cpu_time: Duration::from_nanos((ticks as u128 * numer as u128 / denom as u128) as u64),
+        threads: info.pti_threadnum.max(0) as u32,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn proc_taskinfo(pid: u32) -> Option<libc::proc_taskinfo> {
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as libc::c_int;
+    let ret = unsafe { libc::proc_pidinfo(pid as libc::c_int, libc::PROC_PIDTASKINFO, 0, &mut info as *mut _ as *mut libc::c_void, size) };
+    (ret == size).then_some(info)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn task_info(_pid: u32) -> Option<TaskInfo> {
+    None
+}
+
+#[derive(Clone)]
+pub struct ThreadInfo {
+    pub tid: u64,
+    pub name: String, // Empty for unnamed threads
+    pub state: &'static str, // Same letters as the process S column
+    pub cpu: Option<f32>, // Percent of one core, as the kernel estimates it
+    pub cpu_time: Duration,
+}
+
+// Threads of one process. None when we may not look (another user's process, unless root).
+#[cfg(target_os = "macos")]
+pub fn threads(pid: u32) -> Option<Vec<ThreadInfo>> {
+    const PROC_PIDLISTTHREADIDS: libc::c_int = 28;
+    const PROC_PIDTHREADID64INFO: libc::c_int = 15;
+    let capacity = proc_taskinfo(pid)?.pti_threadnum.max(0) as usize + 16; // Room for threads started meanwhile
+    let mut ids = vec![0u64; capacity];
+    let bytes = unsafe {
+        libc::proc_pidinfo(pid as libc::c_int, PROC_PIDLISTTHREADIDS, 0, ids.as_mut_ptr() as *mut libc::c_void, (capacity * 8) as libc::c_int)
+    };
+    if bytes <= 0 {
+        return None;
+    }
+    ids.truncate(bytes as usize / 8);
+    let size = std::mem::size_of::<libc::proc_threadinfo>() as libc::c_int;
+    Some(ids.into_iter().filter_map(|tid| {
+        let mut info: libc::proc_threadinfo = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::proc_pidinfo(pid as libc::c_int, PROC_PIDTHREADID64INFO, tid, &mut info as *mut _ as *mut libc::c_void, size) };
+        // The thread may have exited since it was listed.
+        if ret != size { return None; }
+        let name: Vec<u8> = info.pth_name.iter().take_while(|&&c| c != 0).map(|&c| c as u8).collect();
+        Some(ThreadInfo {
+            tid,
+            name: String::from_utf8_lossy(&name).into_owned(),
+            state: match info.pth_run_state {
+                libc::TH_STATE_RUNNING => "R",
+                libc::TH_STATE_STOPPED => "T",
+                libc::TH_STATE_WAITING => "S",
+                libc::TH_STATE_UNINTERRUPTIBLE => "D",
+                libc::TH_STATE_HALTED => "H",
+                _ => "?",
+            },
+            cpu: Some(info.pth_cpu_usage as f32 / 10.0), // TH_USAGE_SCALE is 1000
+            cpu_time: Duration::from_nanos(info.pth_user_time + info.pth_system_time),
+        })
+    }).collect())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn threads(_pid: u32) -> Option<Vec<ThreadInfo>> {
+    None
+}
+
+// Counters from proc_pid_rusage (and task_info, where we're allowed to). One or two syscalls
+// per process, so callers only ask for the processes they are going to show.
+#[derive(Clone, Copy)]
+pub struct ResourceUsage {
+    pub phys_footprint: u64, // What Activity Monitor calls "Memory": dirty + compressed + IOKit mappings
+    pub peak_footprint: u64, // Highest phys_footprint over the process's lifetime
+    // Bytes of the process currently held by the compressor. Needs task_for_pid, so in
+    // practice only available when running as root. macOS keeps no per-process count
+    // of what was swapped out: the compressor swaps out its own segments, not process pages.
+    pub compressed: Option<u64>,
+    // Cumulative counters. macOS counts context switches as one number; it doesn't split
+    // voluntary from involuntary like Linux does.
+    pub wakeups: u64, // Idle and interrupt wakeups: what keeps the CPU out of low-power states
+    pub context_switches: u64,
+    pub faults: u64,
+    pub pageins: u64, // Faults that had to read from disk
+}
+
+#[cfg(target_os = "macos")]
+pub fn resource_usage(pid: u32) -> Option<ResourceUsage> {
+    let mut info: libc::rusage_info_v4 = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::proc_pid_rusage(pid as libc::c_int, libc::RUSAGE_INFO_V4, &mut info as *mut _ as *mut libc::rusage_info_t) };
+    if ret != 0 {
+        return None;
+    }
+    let task = proc_taskinfo(pid);
+    Some(ResourceUsage {
+        phys_footprint: info.ri_phys_footprint,
+        peak_footprint: info.ri_lifetime_max_phys_footprint,
+        compressed: task_vm_info(pid).map(|vm| vm.compressed),
+        wakeups: info.ri_pkg_idle_wkups + info.ri_interrupt_wkups,
+        context_switches: task.map_or(0, |t| t.pti_csw.max(0) as u64),
+        faults: task.map_or(0, |t| t.pti_faults.max(0) as u64),
+        pageins: task.map_or(0, |t| t.pti_pageins.max(0) as u64),
+    })
+}
+
+// The leading fields of <mach/task_info.h>'s task_vm_info (TASK_VM_INFO_REV0), which libc
+// doesn't define. The kernel fills in as much as the count we pass asks for.
+#[cfg(target_os = "macos")]
+#[repr(C, packed(4))]
+#[allow(dead_code)] // Only `compressed` is read; the rest is there for the layout
+struct TaskVmInfo {
+    virtual_size: u64,
+    region_count: i32,
+    page_size: i32,
+    resident_size: u64,
+    resident_size_peak: u64,
+    device: u64,
+    device_peak: u64,
+    internal: u64,
+    internal_peak: u64,
+    external: u64,
+    external_peak: u64,
+    reusable: u64,
+    reusable_peak: u64,
+    purgeable_volatile_pmap: u64,
+    purgeable_volatile_resident: u64,
+    purgeable_volatile_virtual: u64,
+    compressed: u64,
+    compressed_peak: u64,
+    compressed_lifetime: u64,
+}
+
+#[cfg(target_os = "macos")]
+fn task_vm_info(pid: u32) -> Option<TaskVmInfo> {
+    const TASK_VM_INFO: libc::task_flavor_t = 22;
+    let task = TaskPort::open(pid)?;
+    let mut info: TaskVmInfo = unsafe { std::mem::zeroed() };
+    let mut count = (std::mem::size_of::<TaskVmInfo>() / std::mem::size_of::<libc::natural_t>()) as libc::mach_msg_type_number_t;
+    let ret = unsafe { libc::task_info(task.0, TASK_VM_INFO, &mut info as *mut _ as libc::task_info_t, &mut count) };
+    (ret == libc::KERN_SUCCESS).then_some(info)
+}
+
+// Number of Mach port names the process holds. Leaking ports ends with the process killed
+// for exhausting its port space. Needs the task port, like `task_vm_info`.
+#[cfg(target_os = "macos")]
+pub fn port_count(pid: u32) -> Option<u32> {
+    #[repr(C)]
+    struct IpcInfoSpaceBasic {
+        genno_mask: libc::natural_t,
+        table_size: libc::natural_t,
+        table_next: libc::natural_t,
+        table_inuse: libc::natural_t,
+        reserved: [libc::natural_t; 2],
+    }
+    unsafe extern "C" {
+        fn mach_port_space_basic_info(task: libc::mach_port_t, info: *mut IpcInfoSpaceBasic) -> libc::kern_return_t;
+    }
+    let task = TaskPort::open(pid)?;
+    let mut info: IpcInfoSpaceBasic = unsafe { std::mem::zeroed() };
+    let ret = unsafe { mach_port_space_basic_info(task.0, &mut info) };
+    (ret == libc::KERN_SUCCESS).then_some(info.table_inuse)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn port_count(_pid: u32) -> Option<u32> {
+    None
+}
+
+// A send right to another process's task, released on drop. task_for_pid only succeeds for
+// our own processes when running as root (or with the debugger entitlement).
+#[cfg(target_os = "macos")]
+struct TaskPort(libc::mach_port_t);
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)] // libc points at the mach2 crate for mach_task_self
+impl TaskPort {
+    fn open(pid: u32) -> Option<Self> {
+        let mut task: libc::mach_port_t = 0;
+        let ret = unsafe { libc::task_for_pid(libc::mach_task_self(), pid as libc::pid_t, &mut task) };
+        (ret == libc::KERN_SUCCESS).then_some(TaskPort(task))
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[allow(deprecated)]
+impl Drop for TaskPort {
+    fn drop(&mut self) {
+        unsafe extern "C" {
+            fn mach_port_deallocate(task: libc::mach_port_t, name: libc::mach_port_t) -> libc::kern_return_t;
+        }
+        unsafe { mach_port_deallocate(libc::mach_task_self(), self.0) };
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn resource_usage(_pid: u32) -> Option<ResourceUsage> {
+    None
+}
+
+// What tells a process apart from a later one that got the same PID: when it started. The
+// short name (at most 15 characters) is kept to check it against what the table shows.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProcessIdentity {
+    pub start: u64, // Opaque; only compared for equality
+    pub name: String,
+}
+
+#[cfg(target_os = "macos")]
+pub fn process_identity(pid: u32) -> Option<ProcessIdentity> {
+    let mut info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+    let ret = unsafe { libc::proc_pidinfo(pid as libc::c_int, libc::PROC_PIDTBSDINFO, 0, &mut info as *mut _ as *mut libc::c_void, size) };
+    if ret != size {
+        return None;
+    }
+    let name: Vec<u8> = info.pbi_comm.iter().take_while(|&&c| c != 0).map(|&c| c as u8).collect();
+    Some(ProcessIdentity {
+        start: info.pbi_start_tvsec * 1_000_000 + info.pbi_start_tvusec,
+        name: String::from_utf8_lossy(&name).into_owned(),
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn process_identity(_pid: u32) -> Option<ProcessIdentity> {
+    None
+}
+
+// Job control: the process group signals from the terminal go to, and the login session.
+#[derive(Clone, Copy)]
+pub struct ProcessGroup {
+    pub pgid: u32,
+    pub sid: Option<u32>,
+}
+
+// proc_bsdinfo carries the group but not the session, which getsid(2) gives for any process.
+#[cfg(target_os = "macos")]
+pub fn process_group(pid: u32) -> Option<ProcessGroup> {
+    let mut info: libc::proc_bsdinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_bsdinfo>() as libc::c_int;
+    let ret = unsafe { libc::proc_pidinfo(pid as libc::c_int, libc::PROC_PIDTBSDINFO, 0, &mut info as *mut _ as *mut libc::c_void, size) };
+    if ret != size {
+        return None;
+    }
+    let sid = unsafe { libc::getsid(pid as libc::pid_t) };
+    Some(ProcessGroup { pgid: info.pbi_pgid, sid: (sid >= 0).then_some(sid as u32) })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn process_group(_pid: u32) -> Option<ProcessGroup> {
+    None
+}
+
+// Where a process runs from. Each is None when the OS won't tell us: other users' processes
+// need root, and some system processes hide them even from root.
+#[derive(Clone, Default)]
+pub struct ProcessPaths {
+    pub cwd: Option<String>,
+    pub exe: Option<String>,
+}
+
+#[cfg(target_os = "macos")]
+pub fn process_paths(pid: u32) -> ProcessPaths {
+    let mut info: libc::proc_vnodepathinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_vnodepathinfo>() as libc::c_int;
+    let ret = unsafe { libc::proc_pidinfo(pid as libc::c_int, libc::PROC_PIDVNODEPATHINFO, 0, &mut info as *mut _ as *mut libc::c_void, size) };
+    // libc spells vip_path as 32 rows of 32 bytes; it's one NUL-terminated MAXPATHLEN buffer.
+    let cwd = (ret == size).then(|| unsafe { std::ffi::CStr::from_ptr(info.pvi_cdir.vip_path.as_ptr() as *const libc::c_char) })
+        .map(|path| path.to_string_lossy().into_owned());
+    let mut buf = vec![0u8; libc::PROC_PIDPATHINFO_MAXSIZE as usize];
+    let len = unsafe { libc::proc_pidpath(pid as libc::c_int, buf.as_mut_ptr() as *mut libc::c_void, buf.len() as u32) };
+    let exe = (len > 0).then(|| String::from_utf8_lossy(&buf[..len as usize]).into_owned());
+    ProcessPaths { cwd: cwd.filter(|p| !p.is_empty()), exe }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn process_paths(_pid: u32) -> ProcessPaths {
+    ProcessPaths::default()
+}
+
+// Puts `text` on the clipboard with `pbcopy`.
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+    let mut child = Command::new("pbcopy").stdin(Stdio::piped()).spawn().map_err(|e| e.to_string())?;
+    child.stdin.take().map_or(Ok(()), |mut stdin| stdin.write_all(text.as_bytes())).map_err(|e| e.to_string())?;
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(status.to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// System-wide VM page counters (what `vm_stat` prints). Sizes are already converted to bytes;
+// the event counters are cumulative since boot, in pages.
+#[derive(Clone, Copy)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct VmStats {
+    pub page_size: u64,
+    pub free: u64,
+    pub active: u64,
+    pub inactive: u64,
+    pub speculative: u64,
+    pub wired: u64,
+    pub purgeable: u64,
+    pub compressed: u64, // Memory occupied by the compressor, not the uncompressed size it holds
+    pub compressor_holds: u64, // What `compressed` would take uncompressed
+    pub faults: u64,
+    pub pageins: u64,
+    pub pageouts: u64,
+    pub swapins: u64,
+    pub swapouts: u64,
+    pub compressions: u64,
+    pub decompressions: u64,
+}
+
+#[cfg(target_os = "macos")]
+pub fn vm_stats() -> Option<VmStats> {
+    let mut stats: libc::vm_statistics64 = unsafe { std::mem::zeroed() };
+    let mut count = libc::HOST_VM_INFO64_COUNT;
+    let ret = unsafe {
+        libc::host_statistics64(libc::mach_host_self(), libc::HOST_VM_INFO64, &mut stats as *mut _ as libc::host_info64_t, &mut count)
+    };
+    if ret != libc::KERN_SUCCESS {
+        return None;
+    }
+    // 16 KiB on Apple Silicon, 4 KiB on Intel.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if page_size <= 0 {
+        return None;
+    }
+    let page_size = page_size as u64;
+    let bytes = |pages: u32| pages as u64 * page_size;
+    Some(VmStats {
+        page_size,
+        free: bytes(stats.free_count),
+        active: bytes(stats.active_count),
+        inactive: bytes(stats.inactive_count),
+        speculative: bytes(stats.speculative_count),
+        wired: bytes(stats.wire_count),
+        purgeable: bytes(stats.purgeable_count),
+        compressed: bytes(stats.compressor_page_count),
+        compressor_holds: stats.total_uncompressed_pages_in_compressor * page_size,
+        faults: stats.faults,
+        pageins: stats.pageins,
+        pageouts: stats.pageouts,
+        swapins: stats.swapins,
+        swapouts: stats.swapouts,
+        compressions: stats.compressions,
+        decompressions: stats.decompressions,
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn vm_stats() -> Option<VmStats> {
+    None
+}
+
+// CPU time of all cores together since boot, in scheduler ticks. Only differences between two
+// readings mean anything.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CpuTicks {
+    pub user: u64, // Including nice
+    pub system: u64,
+    pub idle: u64,
+}
+
+// The aggregate of what `host_processor_info` gives per core.
+#[cfg(target_os = "macos")]
+pub fn cpu_ticks() -> Option<CpuTicks> {
+    let mut info: libc::host_cpu_load_info = unsafe { std::mem::zeroed() };
+    let mut count = libc::HOST_CPU_LOAD_INFO_COUNT;
+    let ret = unsafe {
+        libc::host_statistics(libc::mach_host_self(), libc::HOST_CPU_LOAD_INFO, &mut info as *mut _ as libc::host_info_t, &mut count)
+    };
+    if ret != libc::KERN_SUCCESS {
+        return None;
+    }
+    let tick = |state: libc::c_int| info.cpu_ticks[state as usize] as u64;
+    Some(CpuTicks {
+        user: tick(libc::CPU_STATE_USER) + tick(libc::CPU_STATE_NICE),
+        system: tick(libc::CPU_STATE_SYSTEM),
+        idle: tick(libc::CPU_STATE_IDLE),
+    })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn cpu_ticks() -> Option<CpuTicks> {
+    None
+}
+
+// The kernel's memory pressure level, the one Activity Monitor's pressure graph is colored by.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryPressure {
+    Normal,
+    Warning,
+    Critical,
+}
+
+#[cfg(target_os = "macos")]
+pub fn memory_pressure() -> Option<MemoryPressure> {
+    let mut level: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>();
+    let ret = unsafe {
+        libc::sysctlbyname(c"kern.memorystatus_vm_pressure_level".as_ptr(), &mut level as *mut _ as *mut libc::c_void, &mut len, std::ptr::null_mut(), 0)
+    };
+    if ret != 0 {
+        return None;
+    }
+    // The kernel's DISPATCH_MEMORYPRESSURE_* bits.
+    match level {
+        1 => Some(MemoryPressure::Normal),
+        2 => Some(MemoryPressure::Warning),
+        4 => Some(MemoryPressure::Critical),
+        _ => None,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn memory_pressure() -> Option<MemoryPressure> {
+    None
+}
+
+// The application in front (the one with the menu bar), via LaunchServices' `lsappinfo`.
+// Its PID is only looked up when a different app comes to the front, so a refresh normally
+// spawns one process.
+#[derive(Default)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub struct FrontApp {
+    asn: String, // "ASN:0x0-0x2d02d:", unique to each launch of an app
+    pid: Option<u32>,
+}
+
+impl FrontApp {
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn refresh(&mut self) {
+        use std::process::Command;
+        let asn = match Command::new("lsappinfo").arg("front").output() {
+            Ok(front) => String::from_utf8_lossy(&front.stdout).trim().to_string(),
+            Err(_) => String::new(),
+        };
+        if asn == self.asn { return; }
+        // "\"pid\"=612"
+        self.pid = if asn.is_empty() { None } else {
+            Command::new("lsappinfo").args(["info", "-only", "pid", &asn]).output().ok()
+                .and_then(|info| String::from_utf8_lossy(&info.stdout).split_once('=')?.1.trim().parse().ok())
+        };
+        self.asn = asn;
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    pub fn refresh(&mut self) {}
+}
+
+// The launchd job a process runs as, as a `launchctl` service target ("gui/501/com.foo.agent",
+// "system/com.foo.daemon"). Only jobs in our own domain are visible: the user's agents, or
+// the system daemons when running as root. Spawns `launchctl`.
+#[cfg(target_os = "macos")]
+pub fn launchd_service(pid: u32) -> Option<String> {
+    // "PID\tStatus\tLabel", then one job per line; PID is "-" for jobs that aren't running.
+    let output = std::process::Command::new("launchctl").arg("list").output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let label = text.lines().skip(1).find_map(|line| {
+        let mut fields = line.split('\t');
+        (fields.next()?.parse() == Ok(pid)).then(|| fields.nth(1)).flatten()
+    })?;
+    let uid = unsafe { libc::getuid() };
+    Some(if uid == 0 { format!("system/{}", label) } else { format!("gui/{}/{}", uid, label) })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn launchd_service(_pid: u32) -> Option<String> {
+    None
+}
+
+// Who signed a process's code, and whether it runs in the App Sandbox. From `codesign`.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct CodeSigning {
+    pub signed: bool,
+    pub identifier: Option<String>, // "com.apple.Safari"
+    pub authority: Option<String>, // Leaf certificate: "Software Signing" for Apple, "Developer ID Application: …"
+    pub team: Option<String>, // Team ID; Apple's own code has none
+    pub adhoc: bool, // Signed without a certificate (local builds, Homebrew)
+    pub sandboxed: bool,
+}
+
+// `codesign` accepts a PID and checks the code the process is running, so this needs no access
+// to the executable. Spawns a process and takes a moment: only for the one process shown.
+#[cfg(target_os = "macos")]
+pub fn code_signing(pid: u32) -> Option<CodeSigning> {
+    let output = std::process::Command::new("codesign").args(["-dvv", "--entitlements", ":-"]).arg(pid.to_string()).output().ok()?;
+    let details = String::from_utf8_lossy(&output.stderr);
+    // "No such process" and the like: nothing to say, rather than "not signed".
+    if !output.status.success() && !details.contains("not signed at all") {
+        return None;
+    }
+    Some(parse_codesign(&details, &String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn code_signing(_pid: u32) -> Option<CodeSigning> {
+    None
+}
+
+// `details` is codesign's "Key=value" report (stderr), `entitlements` the plist it prints (stdout).
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_codesign(details: &str, entitlements: &str) -> CodeSigning {
+    if details.contains("not signed at all") {
+        return CodeSigning::default();
+    }
+    let mut signing = CodeSigning { signed: true, ..Default::default() };
+    for line in details.lines() {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        match key {
+            "Identifier" => signing.identifier = Some(value.to_string()),
+            // Listed leaf first, then up to the root.
+            "Authority" if signing.authority.is_none() => signing.authority = Some(value.to_string()),
+            "TeamIdentifier" if value != "not set" => signing.team = Some(value.to_string()),
+            "Signature" if value == "adhoc" => signing.adhoc = true,
+            _ => {}
+        }
+    }
+    // <key>com.apple.security.app-sandbox</key><true/>, with any whitespace in between.
+    signing.sandboxed = entitlements.split_once("<key>com.apple.security.app-sandbox</key>")
+        .is_some_and(|(_, rest)| rest.trim_start().starts_with("<true/>"));
+    signing
+}
+
+// `launchctl kickstart -k`: launchd kills the job and starts it again, the clean way to
+// restart a supervised service.
+pub fn restart_process(service: &str) -> Result<(), String> {
+    let output = std::process::Command::new("launchctl").args(["kickstart", "-k", service]).output();
+    match output {
+        Ok(o) if o.status.success() => Ok(()),
+        Ok(o) => Err(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// macOS's `open`: `["-R", path]` selects a file in Finder, `["-a", "Console"]` launches an app.
+pub fn open(args: &[&str]) -> Result<(), String> {
+    let output = std::process::Command::new("open").args(args).output();
+    match output {
+        Ok(o) if o.status.success() => Ok(()),
+        Ok(o) => Err(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Call stacks of every thread of `pid`, sampled for a moment: `spindump` as root, which also
+// sees the kernel side, `sample` otherwise. Blocks for a second or two: run it off the UI thread.
+// Whatever was written is returned even if the tool failed half way, e.g. because the process
+// exited.
+#[cfg(target_os = "macos")]
+pub fn stack_sample(pid: u32) -> Result<String, String> {
+    use std::process::Command;
+    let pid = pid.to_string();
+    if is_root() {
+        let output = Command::new("spindump").args([pid.as_str(), "2", "-stdout"]).output().map_err(|e| e.to_string())?;
+        return sample_output(&output, String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+    // `sample` only writes its report to a file. -mayDie reads symbols up front, so a process
+    // that exits while being sampled still gets a report.
+    let file = std::env::temp_dir().join(format!("htop-macos-sample-{}-{}.txt", pid, std::process::id()));
+    let output = Command::new("sample").args([pid.as_str(), "1", "-mayDie", "-file"]).arg(&file).output().map_err(|e| e.to_string())?;
+    let text = std::fs::read_to_string(&file).unwrap_or_default();
+    let _ = std::fs::remove_file(&file);
+    sample_output(&output, text)
+}
+
+#[cfg(target_os = "macos")]
+fn sample_output(output: &std::process::Output, text: String) -> Result<String, String> {
+    if !text.trim().is_empty() { return Ok(text); }
+    let err = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(if err.is_empty() { format!("no output ({})", output.status) } else { err })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn stack_sample(_pid: u32) -> Result<String, String> {
+    Err("stack samples come from macOS's sample and spindump".to_string())
+}
+
+pub fn is_root() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+// DTrace, and so dtruss, only attaches when System Integrity Protection is off or was enabled
+// without its DTrace restrictions. Checked up front: dtrace itself just says "system integrity
+// protection is on" or fails to find probes. Spawns `csrutil`.
+#[cfg(target_os = "macos")]
+pub fn dtrace_allowed() -> Result<(), String> {
+    let Ok(output) = std::process::Command::new("csrutil").arg("status").output() else { return Ok(()) };
+    if sip_allows_dtrace(&String::from_utf8_lossy(&output.stdout)) {
+        Ok(())
+    } else {
+        Err("System Integrity Protection blocks DTrace, which dtruss needs. To allow it, run \
+             `csrutil enable --without dtrace` from Recovery".to_string())
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn dtrace_allowed() -> Result<(), String> {
+    Err("Tracing uses dtruss, which only exists on macOS".to_string())
+}
+
+// `csrutil status` output. Anything unrecognised counts as allowed, leaving dtrace to explain.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn sip_allows_dtrace(status: &str) -> bool {
+    !status.contains("status: enabled") || status.contains("DTrace Restrictions: disabled")
+}
+
+// Runs a shell command in a new window of `app`, Terminal or iTerm, through AppleScript.
+pub fn run_in_terminal(app: &str, command: &str) -> Result<(), String> {
+    let command = applescript_string(command);
+    let script = match app {
+        "Terminal" => format!("tell application \"Terminal\"\nactivate\ndo script {}\nend tell", command),
+        // `create window … command` would close the window as soon as dtruss exits.
+        "iTerm" | "iTerm2" => format!(
+            "tell application \"iTerm\"\nactivate\nset w to (create window with default profile)\n\
+             tell current session of w to write text {}\nend tell", command),
+        _ => return Err(format!("unsupported terminal_app \"{}\" (Terminal or iTerm)", app)),
+    };
+    let output = std::process::Command::new("osascript").arg("-e").arg(script).output();
+    match output {
+        Ok(o) if o.status.success() => Ok(()),
+        Ok(o) => Err(String::from_utf8_lossy(&o.stderr).trim().to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// An AppleScript string literal holding `text`.
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// Current clock of each core, in MHz. Apple Silicon publishes it through `powermetrics`, which
+// only root may run, and which samples for a moment itself: call it sparingly, off the UI thread.
+#[cfg(target_os = "macos")]
+pub fn cpu_frequencies() -> Option<Vec<u64>> {
+    if unsafe { libc::geteuid() } != 0 {
+        return None;
+    }
+    let output = std::process::Command::new("powermetrics")
+        .args(["--samplers", "cpu_power", "-i", "100", "-n", "1"]).output().ok()?;
+    let freqs = parse_core_frequencies(&String::from_utf8_lossy(&output.stdout));
+    (!freqs.is_empty()).then_some(freqs)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn cpu_frequencies() -> Option<Vec<u64>> {
+    None
+}
+
+// "CPU 4 frequency: 3228 MHz" lines of powermetrics' cpu_power sampler, indexed by core.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_core_frequencies(text: &str) -> Vec<u64> {
+    let mut freqs = Vec::new();
+    for line in text.lines() {
+        let Some((cpu, mhz)) = line.trim().strip_prefix("CPU ").and_then(|rest| rest.split_once(" frequency: ")) else { continue };
+        let (Ok(cpu), Some(Ok(mhz))) = (cpu.parse::<usize>(), mhz.strip_suffix(" MHz").map(str::parse::<u64>)) else { continue };
+        if freqs.len() <= cpu { freqs.resize(cpu + 1, 0); }
+        freqs[cpu] = mhz;
+    }
+    freqs
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Battery {
+    pub percent: u8,
+    pub state: String, // "charging", "discharging", "charged", ...
+}
+
+// `pmset` is the only unprivileged way to read the battery without linking IOKit.
+// Callers should rate-limit this: it spawns a process.
+#[cfg(target_os = "macos")]
+pub fn battery() -> Option<Battery> {
+    // " -InternalBattery-0 (id=1234)\t87%; discharging; 4:12 remaining present: true"
+    let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let line = text.lines().find(|l| l.contains("InternalBattery"))?;
+    let mut fields = line.split('\t').nth(1)?.split(';').map(str::trim);
+    let percent = fields.next()?.trim_end_matches('%').parse().ok()?;
+    let state = fields.next().unwrap_or("").to_string();
+    Some(Battery { percent, state })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn battery() -> Option<Battery> {
+    None
+}
+
+// What `ifconfig` says about a network interface, beyond the byte counters sysinfo has.
+#[derive(Clone, Default)]
+pub struct InterfaceLink {
+    pub addresses: Vec<IpAddr>, // IPv4 first, in the order the OS lists them
+    pub up: bool, // Up and running: configured, with a link
+    pub loopback: bool,
+    pub drops: Option<u64>, // Packets dropped since boot; macOS only counts incoming ones
+}
+
+// Every interface by name, from getifaddrs, which lists one entry per address.
+#[cfg(unix)]
+pub fn interfaces() -> HashMap<String, InterfaceLink> {
+    let mut list: HashMap<String, InterfaceLink> = HashMap::new();
+    let mut first: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut first) } != 0 {
+        return list;
+    }
+    let mut next = first;
+    while let Some(ifa) = unsafe { next.as_ref() } {
+        next = ifa.ifa_next;
+        let name = unsafe { std::ffi::CStr::from_ptr(ifa.ifa_name) }.to_string_lossy().into_owned();
+        let link = list.entry(name).or_default();
+        let flags = ifa.ifa_flags as libc::c_int;
+        link.up = flags & libc::IFF_UP != 0 && flags & libc::IFF_RUNNING != 0;
+        link.loopback = flags & libc::IFF_LOOPBACK != 0;
+        let Some(addr) = (unsafe { ifa.ifa_addr.as_ref() }) else { continue };
+        match addr.sa_family as libc::c_int {
+            libc::AF_INET => {
+                let sin = unsafe { &*(ifa.ifa_addr as *const libc::sockaddr_in) };
+                let at = link.addresses.iter().take_while(|a| a.is_ipv4()).count();
+                link.addresses.insert(at, IpAddr::from(u32::from_be(sin.sin_addr.s_addr).to_be_bytes()));
+            }
+            libc::AF_INET6 => {
+                let sin6 = unsafe { &*(ifa.ifa_addr as *const libc::sockaddr_in6) };
+                link.addresses.push(IpAddr::from(sin6.sin6_addr.s6_addr));
+            }
+            // The link-level entry carries the interface's counters.
+            #[cfg(target_os = "macos")]
+            libc::AF_LINK if !ifa.ifa_data.is_null() => {
+                let data = unsafe { &*(ifa.ifa_data as *const libc::if_data) };
+                link.drops = Some(data.ifi_iqdrops as u64);
+            }
+            _ => {}
+        }
+    }
+    unsafe { libc::freeifaddrs(first) };
+    list
+}
+
+#[cfg(not(unix))]
+pub fn interfaces() -> HashMap<String, InterfaceLink> {
+    HashMap::new()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum SensorKind {
+    Temperature, // °C
+    Fan, // RPM
+    Power, // Watts
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SensorReading {
+    pub name: String,
+    pub kind: SensorKind,
+    pub value: f32, // NaN when the sensor didn't answer
+}
+
+// Fans and power rails. sysinfo covers temperatures but not these; on macOS they come from the
+// SMC through `powermetrics`, which only root may run and which takes a moment: call it
+// sparingly, off the UI thread. Intel Macs have an `smc` sampler with fans and die
+// temperatures; Apple Silicon has none, but reports CPU/GPU/ANE power.
+#[cfg(target_os = "macos")]
+pub fn smc_sensors() -> Vec<SensorReading> {
+    if unsafe { libc::geteuid() } != 0 {
+        return Vec::new();
+    }
+    let samplers = if cfg!(target_arch = "aarch64") { "cpu_power,gpu_power" } else { "smc" };
+    let Ok(output) = std::process::Command::new("powermetrics").args(["--samplers", samplers, "-i", "100", "-n", "1"]).output() else { return Vec::new() };
+    parse_powermetrics_sensors(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn smc_sensors() -> Vec<SensorReading> {
+    Vec::new()
+}
+
+// "Fan: 1298.68 rpm", "CPU die temperature: 47.81 C" and "GPU Power: 45 mW" lines of
+// powermetrics' smc, cpu_power and gpu_power samplers.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_powermetrics_sensors(text: &str) -> Vec<SensorReading> {
+    let mut readings = Vec::new();
+    for line in text.lines() {
+        let Some((name, value)) = line.trim().split_once(": ") else { continue };
+        let (kind, number, scale) = if let Some(rpm) = value.strip_suffix(" rpm") {
+            (SensorKind::Fan, rpm, 1.0)
+        } else if let Some(celsius) = value.strip_suffix(" C").filter(|_| name.ends_with("temperature")) {
+            (SensorKind::Temperature, celsius, 1.0)
+        } else if let Some(mw) = value.strip_suffix(" mW").filter(|_| name.contains("Power")) {
+            (SensorKind::Power, mw, 1000.0)
+        } else {
+            continue;
+        };
+        let Ok(number) = number.trim().parse::<f32>() else { continue };
+        readings.push(SensorReading { name: name.to_string(), kind, value: number / scale });
+    }
+    readings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sip_status_decides_dtrace() {
+        assert!(!sip_allows_dtrace("System Integrity Protection status: enabled.\n"));
+        assert!(sip_allows_dtrace("System Integrity Protection status: disabled.\n"));
+        assert!(sip_allows_dtrace(
+            "System Integrity Protection status: enabled (Custom Configuration).\n\nConfiguration:\n\
+             \tApple Internal: disabled\n\tKext Signing: enabled\n\tDTrace Restrictions: disabled\n"));
+    }
+
+    #[test]
+    fn applescript_strings_are_escaped() {
+        assert_eq!(applescript_string(r#"echo 'a "b"'; ls C:\"#), r#""echo 'a \"b\"'; ls C:\\""#);
+    }
+
+    #[test]
+    fn codesign_report_is_parsed() {
+        let safari = parse_codesign(
+            "Executable=/Applications/Safari.app/Contents/MacOS/Safari\nIdentifier=com.apple.Safari\nFormat=app bundle with Mach-O universal\n\
+             Authority=Software Signing\nAuthority=Apple Code Signing Certification Authority\nAuthority=Apple Root CA\nTeamIdentifier=not set\n",
+            "<?xml version=\"1.0\"?>\n<plist version=\"1.0\">\n<dict>\n\t<key>com.apple.security.app-sandbox</key>\n\t<true/>\n</dict>\n</plist>\n",
+        );
+        assert_eq!(safari, CodeSigning {
+            signed: true,
+            identifier: Some("com.apple.Safari".to_string()),
+            authority: Some("Software Signing".to_string()),
+            team: None,
+            adhoc: false,
+            sandboxed: true,
+        });
+        let local = parse_codesign("Identifier=htop-macos-55554944\nSignature=adhoc\nTeamIdentifier=not set\n", "");
+        assert!(local.signed && local.adhoc && local.authority.is_none() && !local.sandboxed);
+        assert_eq!(parse_codesign("/usr/local/bin/foo: code object is not signed at all\n", ""), CodeSigning::default());
+    }
+
+    #[test]
+    fn parses_powermetrics_core_frequencies() {
+        let text = "**** Processor usage ****\n\nE-Cluster HW active frequency: 1020 MHz\n\
+                    CPU 0 frequency: 972 MHz\nCPU 0 active residency:  12.3%\nCPU 1 frequency: 1100 MHz\n\
+                    P0-Cluster HW active frequency: 3228 MHz\nCPU 2 frequency: 3228 MHz\n";
+        assert_eq!(parse_core_frequencies(text), vec![972, 1100, 3228]);
+        assert!(parse_core_frequencies("powermetrics must be invoked as the superuser").is_empty());
+    }
+
+    #[test]
+    fn parses_powermetrics_sensors() {
+        let intel = parse_powermetrics_sensors("**** SMC sensors ****\n\nCPU Thermal level: 0\nFan: 1298.68 rpm\nCPU die temperature: 47.81 C\nCPU Plimit: 0.00\n");
+        assert_eq!(intel, [
+            SensorReading { name: "Fan".to_string(), kind: SensorKind::Fan, value: 1298.68 },
+            SensorReading { name: "CPU die temperature".to_string(), kind: SensorKind::Temperature, value: 47.81 },
+        ]);
+        let apple = parse_powermetrics_sensors("CPU 0 frequency: 972 MHz\nCPU Power: 1250 mW\nCombined Power (CPU + GPU + ANE): 1300 mW\n");
+        assert_eq!(apple.iter().map(|r| (r.name.as_str(), r.value)).collect::<Vec<_>>(), [("CPU Power", 1.25), ("Combined Power (CPU + GPU + ANE)", 1.3)]);
+    }
+}
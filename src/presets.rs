@@ -0,0 +1,173 @@
+// Filter presets: the '/' filter and the filter builder's fields saved together under a name,
+// kept in the config as `[[filter_presets]]`, and picked from a list ('B') to get the same view
+// back for a recurring investigation.
+use crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use serde::{Deserialize, Serialize};
+
+use crate::filter_builder::Filter;
+use crate::process_list::FilterMode;
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct FilterPreset {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>, // What '/' filters on
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fuzzy: bool, // `search` is matched fuzzily
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<Filter>, // Built with 'F'
+}
+
+impl FilterPreset {
+    // "python AND user = root", for the list.
+    fn summary(&self) -> String {
+        let search = self.search.as_ref().map(|s| if self.fuzzy { format!("~{}", s) } else { format!("\"{}\"", s) });
+        search.into_iter().chain(self.fields.as_ref().map(Filter::summary)).collect::<Vec<_>>().join(" AND ")
+    }
+}
+
+#[derive(Default)]
+pub struct PresetMenu {
+    selected: usize,
+    naming: Option<String>, // Name being typed for the current filter, after 's'
+}
+
+impl App {
+    pub fn open_presets(&mut self) {
+        self.preset_menu = PresetMenu::default();
+        self.input_mode = InputMode::Presets;
+    }
+
+    // The filters in force, under `name`, replacing a preset of that name.
+    fn save_preset(&mut self, name: String) {
+        if self.active_filter.is_none() && self.field_filter.is_none() {
+            self.message = Some("No filter to save: type one with / or build one with F".to_string());
+            return;
+        }
+        let preset = FilterPreset {
+            name: name.clone(),
+            search: self.active_filter.clone(),
+            fuzzy: self.filter_mode == FilterMode::Fuzzy,
+            fields: self.field_filter.clone(),
+        };
+        let presets = &mut self.config.filter_presets;
+        match presets.iter().position(|p| p.name == name) {
+            Some(i) => presets[i] = preset,
+            None => presets.push(preset),
+        }
+        self.preset_menu.selected = presets.iter().position(|p| p.name == name).unwrap_or(0);
+        self.message = Some(match self.config.save() {
+            Ok(()) => format!("Saved filter preset \"{}\"", name),
+            Err(e) => format!("Could not save filter preset: {}", e),
+        });
+    }
+
+    fn apply_preset(&mut self, i: usize) {
+        let Some(preset) = self.config.filter_presets.get(i).cloned() else { return };
+        self.input_mode = InputMode::Normal;
+        self.filter_mode = if preset.fuzzy { FilterMode::Fuzzy } else { FilterMode::Plain };
+        self.search_query = preset.search.clone().unwrap_or_default();
+        self.field_filter = preset.fields;
+        self.set_filter(preset.search);
+    }
+
+    fn delete_preset(&mut self, i: usize) {
+        if i >= self.config.filter_presets.len() { return; }
+        let preset = self.config.filter_presets.remove(i);
+        self.preset_menu.selected = i.min(self.config.filter_presets.len().saturating_sub(1));
+        self.message = Some(match self.config.save() {
+            Ok(()) => format!("Deleted filter preset \"{}\"", preset.name),
+            Err(e) => format!("Could not save filter presets: {}", e),
+        });
+    }
+
+    pub fn handle_presets_key(&mut self, code: KeyCode) {
+        if let Some(name) = &mut self.preset_menu.naming {
+            match code {
+                KeyCode::Esc => self.preset_menu.naming = None,
+                KeyCode::Enter => {
+                    let name = name.trim().to_string();
+                    self.preset_menu.naming = None;
+                    if !name.is_empty() { self.save_preset(name); }
+                }
+                KeyCode::Backspace => { name.pop(); }
+                KeyCode::Char(c) => name.push(c),
+                _ => {}
+            }
+            return;
+        }
+        let len = self.config.filter_presets.len();
+        let selected = self.preset_menu.selected;
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('B') => self.input_mode = InputMode::Normal,
+            KeyCode::Down if len > 0 => self.preset_menu.selected = (selected + 1).min(len - 1),
+            KeyCode::Up => self.preset_menu.selected = selected.saturating_sub(1),
+            KeyCode::Enter => self.apply_preset(selected),
+            KeyCode::Char('s') => self.preset_menu.naming = Some(String::new()),
+            KeyCode::Char('d') | KeyCode::Delete => self.delete_preset(selected),
+            _ => {}
+        }
+    }
+}
+
+pub fn draw_presets(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(60, 50, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Filter presets");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [list_area, hint_area] = Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(inner);
+
+    let presets = &app.config.filter_presets;
+    if presets.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("No presets yet. Filter the list with / or F, then press s here.", app.theme.dim)), list_area);
+    } else {
+        let width = presets.iter().map(|p| p.name.chars().count()).max().unwrap_or(0);
+        let items: Vec<ListItem> = presets.iter().map(|p| ListItem::new(Line::from(vec![
+            Span::raw(format!("{:<w$}  ", p.name, w = width)),
+            Span::styled(p.summary(), app.theme.dim),
+        ]))).collect();
+        let list = List::new(items).highlight_style(app.theme.selection).highlight_symbol(app.selection_marker(""));
+        f.render_stateful_widget(list, list_area, &mut ListState::default().with_selected(Some(app.preset_menu.selected)));
+    }
+    let hint = match &app.preset_menu.naming {
+        Some(name) => Line::from(vec![Span::raw("Save current filter as: "), Span::styled(format!("{}_", name), app.theme.input)]),
+        None => Line::from(Span::styled("Enter apply   s save current filter   d delete   Esc close", app.theme.dim)),
+    };
+    f.render_widget(Paragraph::new(vec![Line::default(), hint]), hint_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn a_saved_preset_brings_both_filters_back() {
+        let mut app = App::with_processes(&Config::default(), &[(1, 0, 0.0, "launchd"), (2, 0, 0.0, "python3 a.py"), (3, 0, 0.0, "python3 b.py")]);
+        for (pid, user) in [(1, "root"), (2, "me"), (3, "root")] {
+            app.processes.get_mut(&pid).unwrap().user = user.to_string();
+        }
+        // As `save_preset` would keep it, without writing the config out.
+        let fields = Filter { user: "root".to_string(), ..Default::default() };
+        let preset = FilterPreset { name: "root-python".to_string(), search: Some("python".to_string()), fuzzy: false, fields: Some(fields) };
+        app.config.filter_presets.push(preset.clone());
+        assert_eq!(preset.summary(), "\"python\" AND user = root");
+
+        assert_eq!(app.view.len(), 3);
+        app.state.select(Some(2));
+        app.apply_preset(0);
+        assert_eq!(app.view.iter().map(|&(_, pid)| pid).collect::<Vec<_>>(), [3]);
+        assert_eq!(app.state.selected(), Some(0));
+        assert_eq!(app.search_query, "python");
+
+        let text = toml::to_string(&preset).unwrap();
+        assert_eq!(toml::from_str::<FilterPreset>(&text).unwrap(), preset);
+    }
+}
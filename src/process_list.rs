@@ -0,0 +1,641 @@
+// The process table's data and the pure logic that orders it: sorting, the tree, and filtering.
+// Nothing here knows about the terminal, so it can be tested on hand-built processes.
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::ops::Range;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fuzzy;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn reversed(self) -> Self {
+        match self {
+            SortOrder::Asc => SortOrder::Desc,
+            SortOrder::Desc => SortOrder::Asc,
+        }
+    }
+}
+
+// Serialized with the same names as the matching columns.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum SortBy {
+    Pid,
+    User,
+    Cpu,
+    Mem,
+    Time,
+    #[serde(rename = "avg")]
+    CpuAvg,
+    Command,
+    Ports,
+    MemDelta,
+    Pgrp,
+    Sid,
+    DiskRead,
+    DiskWrite,
+}
+
+impl SortBy {
+    // Direction a column sorts in when first picked: names and IDs read naturally from the
+    // top, measurements are most interesting biggest first.
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            SortBy::Pid | SortBy::User | SortBy::Command | SortBy::Pgrp | SortBy::Sid => SortOrder::Asc,
+            _ => SortOrder::Desc,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    pub uid: Option<u32>,
+    pub user: String,
+    pub name: String, // Executable name, without arguments
+    pub status: String,
+    pub cpu: f32, // As shown and sorted by: the latest sample, or the average of `cpu_history`
+    pub cpu_history: VecDeque<f32>, // Recent CPU% samples, oldest first; see `push_cpu`
+    pub mem: f32,
+    pub virtual_mem: u64,
+    pub resident: u64, // RSS in bytes; `mem` is this as a share of physical memory
+    pub footprint: Option<u64>, // Physical footprint; only collected while its column is shown
+    pub ports: Option<u32>, // Mach ports held; likewise
+    pub pgid: Option<u32>, // Process group; only collected while PGRP or SID is shown
+    pub sid: Option<u32>, // Session; likewise
+    pub resident_history: VecDeque<u64>, // Recent `resident` samples, oldest first; see `push_resident`
+    pub mem_delta: Option<i64>, // RSS change across the ΔMEM window
+    pub leaking: bool, // RSS grew steadily over the leak window; see `push_resident`
+    pub cpu_time: Option<Duration>, // Total CPU time; None when the OS won't tell us
+    pub run_time: u64, // Seconds since the process started
+    pub cpu_avg: Option<f32>, // CPU time as a share of the process's lifetime, scaled like `cpu`
+    pub threads: Option<u32>,
+    pub disk_read: Option<u64>, // Bytes read over its lifetime; None where the OS won't tell us
+    pub disk_written: Option<u64>,
+    pub read_rate: Option<u64>, // Bytes read per second since the previous refresh; see `push_disk_io`
+    pub write_rate: Option<u64>,
+    pub command: String,
+    pub args_start: usize, // Byte offset of the arguments in `command`, past the program
+    pub exe: String, // Executable path; empty when the OS won't tell us
+    pub label: Option<String>, // "Python: manage.py", "Chrome: GPU"; see `labels::describe`
+    pub seen: bool, // Present in the latest sample; entries left false are dropped after a refresh
+}
+
+impl ProcessInfo {
+    // Records a CPU% sample, keeping the last `window`. `cpu` becomes their average when
+    // `smooth`, else the sample itself. A process seen fewer times averages what there is.
+    pub fn push_cpu(&mut self, sample: f32, window: usize, smooth: bool) {
+        while self.cpu_history.len() >= window.max(1) { self.cpu_history.pop_front(); }
+        self.cpu_history.push_back(sample);
+        self.cpu = if smooth { self.smoothed_cpu() } else { sample };
+    }
+
+    pub fn smoothed_cpu(&self) -> f32 {
+        if self.cpu_history.is_empty() { return self.cpu; }
+        self.cpu_history.iter().sum::<f32>() / self.cpu_history.len() as f32
+    }
+
+    // The latest sample, whatever `cpu` shows.
+    pub fn cpu_now(&self) -> f32 {
+        self.cpu_history.back().copied().unwrap_or(self.cpu)
+    }
+
+    // Records the current RSS, keeping the last `window` samples for ΔMEM and `leak_window` for
+    // the leak check. Both are only known once the process has been watched for the whole
+    // window: a process that just appeared would otherwise show its entire size as growth.
+    pub fn push_resident(&mut self, window: usize, leak_window: usize) {
+        let keep = window.max(leak_window);
+        if self.resident_history.capacity() == 0 { self.resident_history.reserve_exact(keep); }
+        if self.resident_history.len() == keep { self.resident_history.pop_front(); }
+        self.resident_history.push_back(self.resident);
+        let len = self.resident_history.len();
+        self.mem_delta = match (self.resident_history.get(len.wrapping_sub(window)), self.resident_history.back()) {
+            (Some(&first), Some(&last)) if window > 0 => Some(last as i64 - first as i64),
+            _ => None,
+        };
+        // A leak never gives memory back and grows more often than not. A single allocation
+        // followed by a plateau isn't one.
+        self.leaking = leak_window > 1 && len >= leak_window && {
+            let recent = self.resident_history.range(len - leak_window..);
+            let (mut grew, mut shrank) = (0, false);
+            for (a, b) in recent.clone().zip(recent.skip(1)) {
+                grew += (b > a) as usize;
+                shrank |= b < a;
+            }
+            !shrank && grew * 2 > leak_window - 1
+        };
+    }
+
+    // Records the lifetime I/O counters, `elapsed` seconds after the previous ones. The rates
+    // need two samples: a process seen for the first time, or whose counters went backwards (its
+    // PID was reused), has none yet.
+    pub fn push_disk_io(&mut self, read: Option<u64>, written: Option<u64>, elapsed: Option<f64>) {
+        let rate = |before: Option<u64>, now: Option<u64>| match (before, now, elapsed) {
+            (Some(before), Some(now), Some(e)) if now >= before && e > 0.0 => Some(((now - before) as f64 / e) as u64),
+            _ => None,
+        };
+        self.read_rate = rate(self.disk_read, read);
+        self.write_rate = rate(self.disk_written, written);
+        (self.disk_read, self.disk_written) = (read, written);
+    }
+}
+
+// The numbers behind htop's "Tasks: 97, 1493 thr, 128 kthr; 2 running" line.
+#[derive(Default)]
+pub struct TaskCounts {
+    pub total: usize,
+    pub threads: u64, // Threads of regular processes (where the OS lets us count them)
+    pub kernel_threads: u64,
+    pub running: usize,
+    pub zombies: usize,
+}
+
+impl TaskCounts {
+    pub fn from_processes<'a>(processes: impl Iterator<Item = &'a ProcessInfo>) -> Self {
+        let mut counts = TaskCounts::default();
+        for p in processes {
+            counts.total += 1;
+            if p.status == "R" { counts.running += 1; }
+            if p.status == "Z" { counts.zombies += 1; }
+            if p.pid == 0 {
+                // Every kernel thread lives inside kernel_task.
+                counts.kernel_threads += p.threads.unwrap_or(0) as u64;
+            } else {
+                counts.threads += p.threads.unwrap_or(0) as u64;
+            }
+        }
+        counts
+    }
+}
+
+// Sorts `pids` (all keys of `procs`) by one column. `by_elapsed` makes Time sort by wall-clock
+// age instead of CPU time, matching what the time column is showing.
+pub fn sort_pids(pids: &mut [u32], procs: &HashMap<u32, ProcessInfo>, sort_by: SortBy, order: SortOrder, by_elapsed: bool) {
+    pids.sort_by(|a, b| compare(&procs[a], &procs[b], sort_by, order, by_elapsed));
+}
+
+// Like `sort_pids`, but only the first `n` are put in order; the rest follow them unsorted.
+// On systems with thousands of processes, this is all a list capped by `top_n` needs.
+pub fn sort_top_pids(pids: &mut [u32], n: usize, procs: &HashMap<u32, ProcessInfo>, sort_by: SortBy, order: SortOrder, by_elapsed: bool) {
+    if n == 0 { return; }
+    if n >= pids.len() { return sort_pids(pids, procs, sort_by, order, by_elapsed); }
+    let cmp = |a: &u32, b: &u32| compare(&procs[a], &procs[b], sort_by, order, by_elapsed);
+    pids.select_nth_unstable_by(n - 1, cmp);
+    pids[..n].sort_by(cmp);
+}
+
+fn compare(a: &ProcessInfo, b: &ProcessInfo, sort_by: SortBy, order: SortOrder, by_elapsed: bool) -> Ordering {
+    let ordering = match sort_by {
+        SortBy::Pid => a.pid.cmp(&b.pid),
+        SortBy::User => a.user.cmp(&b.user),
+        SortBy::Cpu => a.cpu.total_cmp(&b.cpu),
+        SortBy::Mem => a.mem.total_cmp(&b.mem),
+        SortBy::Time if by_elapsed => a.run_time.cmp(&b.run_time),
+        SortBy::Time => a.cpu_time.cmp(&b.cpu_time),
+        SortBy::CpuAvg => a.cpu_avg.unwrap_or(-1.0).total_cmp(&b.cpu_avg.unwrap_or(-1.0)),
+        // Program name first, so "/usr/bin/python3 x.py" sits next to "python3 y.py".
+        SortBy::Command => command_basename(&a.command).cmp(command_basename(&b.command)).then_with(|| a.command.cmp(&b.command)),
+        SortBy::Ports => a.ports.cmp(&b.ports),
+        SortBy::MemDelta => a.mem_delta.cmp(&b.mem_delta),
+        // Members of a group or session stay together, in PID order.
+        SortBy::Pgrp => a.pgid.cmp(&b.pgid).then(a.pid.cmp(&b.pid)),
+        SortBy::Sid => a.sid.cmp(&b.sid).then(a.pid.cmp(&b.pid)),
+        SortBy::DiskRead => a.read_rate.cmp(&b.read_rate),
+        SortBy::DiskWrite => a.write_rate.cmp(&b.write_rate),
+    };
+    match order {
+        SortOrder::Asc => ordering,
+        SortOrder::Desc => ordering.reverse(),
+    }
+}
+
+// File name of the program in a command line: "/usr/bin/python3 -m http.server" -> "python3".
+fn command_basename(command: &str) -> &str {
+    let program = command.split_whitespace().next().unwrap_or("");
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+// Placeholder "PID" of the synthetic "(unknown parent)" row in tree view. It is not a process:
+// it can't be selected for actions and has no entry in the process map.
+pub const UNKNOWN_PARENT: u32 = u32::MAX;
+// Placeholder for the divider row tree view puts between two top-level trees. Never selectable.
+pub const TREE_SEPARATOR: u32 = u32::MAX - 1;
+
+// Depth-first (depth, PID) rows, like pstree: launchd (PID 1) first, then the other true roots
+// (parent 0, e.g. kernel_task) by PID. Processes whose parent we can't see (it exited, or is
+// hidden from us) are grouped under an `UNKNOWN_PARENT` row at the end. Siblings keep their order
+// in `sorted_pids`, so the busiest child comes first under CPU sort, or are ordered by PID when
+// `by_pid` is set. Consecutive top-level trees are divided by a `TREE_SEPARATOR` row. The
+// descendants of `collapsed` processes are left out.
+pub fn tree_order(procs: &HashMap<u32, ProcessInfo>, sorted_pids: &[u32], by_pid: bool, collapsed: &HashSet<u32>) -> Vec<(usize, u32)> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut roots = Vec::new();
+    let mut orphans = Vec::new();
+    for &pid in sorted_pids {
+        let Some(p) = procs.get(&pid) else { continue };
+        if p.ppid == 0 || p.ppid == pid {
+            roots.push(pid);
+        } else if !procs.contains_key(&p.ppid) {
+            orphans.push(pid);
+        } else {
+            children.entry(p.ppid).or_default().push(pid);
+        }
+    }
+    if by_pid {
+        roots.sort_unstable_by_key(|&pid| (pid != 1, pid));
+        orphans.sort_unstable();
+        for siblings in children.values_mut() { siblings.sort_unstable(); }
+    } else {
+        roots.sort_by_key(|&pid| pid != 1); // Stable: the others stay in sort order
+    }
+
+    let mut rows = Vec::with_capacity(sorted_pids.len() + 1);
+    let mut visited = HashSet::new();
+    let mut walk = |root: u32, depth: usize, rows: &mut Vec<(usize, u32)>| {
+        if visited.contains(&root) { return; }
+        if depth == 0 && !rows.is_empty() { rows.push((0, TREE_SEPARATOR)); }
+        let mut stack = vec![(depth, root)];
+        while let Some((depth, pid)) = stack.pop() {
+            if !visited.insert(pid) { continue; }
+            rows.push((depth, pid));
+            if collapsed.contains(&pid) {
+                // Still marked visited, so the stranded pass below doesn't bring them back.
+                let mut folded = children.get(&pid).cloned().unwrap_or_default();
+                while let Some(kid) = folded.pop() {
+                    if visited.insert(kid) { folded.extend(children.get(&kid).into_iter().flatten().copied()); }
+                }
+            } else if let Some(kids) = children.get(&pid) {
+                stack.extend(kids.iter().rev().map(|&kid| (depth + 1, kid)));
+            }
+        }
+    };
+    for root in roots { walk(root, 0, &mut rows); }
+    if !orphans.is_empty() {
+        if !rows.is_empty() { rows.push((0, TREE_SEPARATOR)); }
+        rows.push((0, UNKNOWN_PARENT));
+        for pid in orphans { walk(pid, 1, &mut rows); }
+    }
+    // Parent links that loop back on themselves (PID reuse can do that) never reach a root.
+    // Show those processes anyway, each loop starting from its lowest PID.
+    let mut stranded: Vec<u32> = sorted_pids.iter().copied().filter(|pid| procs.contains_key(pid)).collect();
+    stranded.sort_unstable();
+    for pid in stranded { walk(pid, 0, &mut rows); }
+    rows
+}
+
+// Whether the command (or, with `match_exe`, executable path) contains `query`, which must
+// already be lowercase.
+pub fn matches_query(p: &ProcessInfo, query: &str, match_exe: bool) -> bool {
+    p.command.to_lowercase().contains(query) || (match_exe && p.exe.to_lowercase().contains(query))
+}
+
+// How the filter text is matched against commands.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    Plain, // Substring, ignoring case
+    Fuzzy, // Subsequence, best matches first; see `fuzzy`
+}
+
+// Rows of `sorted_pids` matching `filter` (see `matches_query`), ignoring case. No filter keeps
+// everything. Plain matches keep the sort order; fuzzy ones are ranked by score, the sort only
+// breaking ties.
+pub fn filter_pids(procs: &HashMap<u32, ProcessInfo>, sorted_pids: &[u32], filter: Option<&str>, mode: FilterMode, match_exe: bool) -> Vec<(usize, u32)> {
+    let filter = filter.map(str::to_lowercase);
+    if let Some(f) = filter.as_deref().filter(|f| mode == FilterMode::Fuzzy && !f.is_empty()) {
+        return fuzzy_rank(procs, sorted_pids, f, match_exe);
+    }
+    sorted_pids.iter()
+        .filter(|pid| procs.get(pid).is_some_and(|p| filter.as_ref().is_none_or(|f| matches_query(p, f, match_exe))))
+        .map(|&pid| (0, pid))
+        .collect()
+}
+
+// Byte ranges of `text` that `query` (lowercase) matched: every occurrence for plain matching,
+// the matched characters for fuzzy. For highlighting what is on screen.
+pub fn match_ranges(text: &str, query: &str, mode: FilterMode) -> Vec<Range<usize>> {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let byte_range = |first: usize, last: usize| chars[first].0..chars[last].0 + chars[last].1.len_utf8();
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    match mode {
+        FilterMode::Plain => {
+            let needle: Vec<char> = query.chars().collect();
+            if needle.is_empty() { return ranges; }
+            let mut i = 0;
+            while i + needle.len() <= chars.len() {
+                if chars[i..i + needle.len()].iter().zip(&needle).all(|(&(_, c), &n)| lower(c) == n) {
+                    ranges.push(byte_range(i, i + needle.len() - 1));
+                    i += needle.len();
+                } else {
+                    i += 1;
+                }
+            }
+        }
+        FilterMode::Fuzzy => {
+            let plain: Vec<char> = chars.iter().map(|&(_, c)| c).collect();
+            for i in fuzzy::positions(query, &plain).unwrap_or_default() {
+                let range = byte_range(i, i);
+                // Runs of adjacent matches become one range.
+                match ranges.last_mut() {
+                    Some(last) if last.end == range.start => last.end = range.end,
+                    _ => ranges.push(range),
+                }
+            }
+        }
+    }
+    ranges
+}
+
+fn fuzzy_rank(procs: &HashMap<u32, ProcessInfo>, sorted_pids: &[u32], filter: &str, match_exe: bool) -> Vec<(usize, u32)> {
+    let mut scored: Vec<(i32, u32)> = sorted_pids.iter().filter_map(|&pid| {
+        let p = procs.get(&pid)?;
+        let exe = if match_exe { fuzzy::score(filter, &p.exe) } else { None };
+        Some((fuzzy::score(filter, &p.command).max(exe)?, pid))
+    }).collect();
+    scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score)); // Stable: equal scores keep the sort order
+    scored.into_iter().map(|(_, pid)| (0, pid)).collect()
+}
+
+// The `roots` still running and everything they spawned, however deep.
+pub fn with_descendants(procs: &HashMap<u32, ProcessInfo>, roots: &BTreeSet<u32>) -> HashSet<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for p in procs.values() { children.entry(p.ppid).or_default().push(p.pid); }
+    let mut found = HashSet::new();
+    let mut queue: Vec<u32> = roots.iter().copied().filter(|pid| procs.contains_key(pid)).collect();
+    while let Some(pid) = queue.pop() {
+        // `insert` also stops parent links that loop.
+        if found.insert(pid) { queue.extend(children.get(&pid).into_iter().flatten()); }
+    }
+    found
+}
+
+// `pid` and its known ancestors, oldest first. The walk stops at PID 0, at a parent we don't
+// have (it exited, or isn't visible to us), or where the links loop.
+pub fn parent_chain(procs: &HashMap<u32, ProcessInfo>, pid: u32) -> Vec<u32> {
+    let mut chain = Vec::new();
+    let mut next = procs.get(&pid).map(|p| p.pid);
+    while let Some(pid) = next {
+        if chain.contains(&pid) { break; }
+        chain.push(pid);
+        next = procs.get(&pid).map(|p| p.ppid).filter(|ppid| *ppid != 0 && procs.contains_key(ppid));
+    }
+    chain.reverse();
+    chain
+}
+
+// The App's tests build their table the same way `procs` does below.
+#[cfg(test)]
+impl crate::App {
+    // An App showing made-up processes, (pid, ppid, cpu, command), sorted as `config` says.
+    pub fn with_processes(config: &crate::config::Config, list: &[(u32, u32, f32, &str)]) -> Self {
+        let mut app = crate::App::new(config);
+        for &(pid, ppid, cpu, command) in list {
+            app.processes.insert(pid, ProcessInfo { pid, ppid, cpu, command: command.to_string(), ..Default::default() });
+        }
+        app.sort_processes();
+        app
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn procs(list: &[(u32, u32, &str)]) -> HashMap<u32, ProcessInfo> {
+        list.iter().map(|&(pid, ppid, command)| (pid, ProcessInfo { pid, ppid, command: command.to_string(), ..Default::default() })).collect()
+    }
+
+    fn pids(rows: &[(usize, u32)]) -> Vec<u32> {
+        rows.iter().map(|&(_, pid)| pid).collect()
+    }
+
+    #[test]
+    fn smoothed_cpu_averages_the_last_samples() {
+        let mut p = ProcessInfo::default();
+        p.push_cpu(90.0, 3, true);
+        assert_eq!(p.cpu, 90.0); // New: only the one sample to go on
+        p.push_cpu(0.0, 3, true);
+        p.push_cpu(30.0, 3, true);
+        assert_eq!(p.cpu, 40.0);
+        p.push_cpu(60.0, 3, true);
+        assert_eq!((p.cpu, p.cpu_now(), p.cpu_history.len()), (30.0, 60.0, 3));
+        p.push_cpu(12.0, 3, false);
+        assert_eq!(p.cpu, 12.0);
+    }
+
+    #[test]
+    fn tree_nests_children_under_parents_in_pid_order() {
+        let p = procs(&[(1, 0, "launchd"), (30, 1, "b"), (20, 1, "a"), (25, 20, "a-child")]);
+        assert_eq!(tree_order(&p, &[30, 25, 20, 1], true, &HashSet::new()), vec![(0, 1), (1, 20), (2, 25), (1, 30)]);
+    }
+
+    #[test]
+    fn tree_siblings_can_follow_the_sort() {
+        let p = procs(&[(0, 0, "kernel_task"), (1, 0, "launchd"), (30, 1, "b"), (20, 1, "a"), (25, 20, "a-child"), (40, 999, "orphan"), (60, 998, "hidden")]);
+        // As sorted by CPU: launchd still leads the roots, everything else keeps its place.
+        assert_eq!(tree_order(&p, &[60, 30, 0, 25, 20, 40, 1], false, &HashSet::new()), vec![
+            (0, 1), (1, 30), (1, 20), (2, 25), (0, TREE_SEPARATOR), (0, 0), (0, TREE_SEPARATOR), (0, UNKNOWN_PARENT), (1, 60), (1, 40),
+        ]);
+    }
+
+    #[test]
+    fn orphans_are_grouped_under_unknown_parent() {
+        // 40's parent exited and 60's is hidden from us; kernel_task stays a root of its own.
+        let p = procs(&[(0, 0, "kernel_task"), (1, 0, "launchd"), (40, 999, "orphan"), (41, 40, "child"), (60, 998, "hidden"), (50, 1, "other")]);
+        assert_eq!(tree_order(&p, &[50, 60, 41, 40, 1, 0], true, &HashSet::new()), vec![
+            (0, 1), (1, 50), (0, TREE_SEPARATOR), (0, 0), (0, TREE_SEPARATOR), (0, UNKNOWN_PARENT), (1, 40), (2, 41), (1, 60),
+        ]);
+    }
+
+    #[test]
+    fn no_unknown_parent_row_without_orphans() {
+        let p = procs(&[(1, 0, "launchd"), (2, 1, "a")]);
+        assert!(!pids(&tree_order(&p, &[1, 2], true, &HashSet::new())).contains(&UNKNOWN_PARENT));
+    }
+
+    #[test]
+    fn parent_loops_are_not_dropped() {
+        let p = procs(&[(1, 0, "launchd"), (7, 8, "x"), (8, 7, "y"), (9, 9, "self")]);
+        let rows = tree_order(&p, &[1, 7, 8, 9], true, &HashSet::new());
+        assert_eq!(pids(&rows), vec![1, TREE_SEPARATOR, 9, TREE_SEPARATOR, 7, 8]);
+        assert_eq!(rows[5], (1, 8));
+    }
+
+    #[test]
+    fn followed_pids_bring_their_descendants() {
+        let p = procs(&[(1, 0, "launchd"), (10, 1, "api"), (11, 10, "worker"), (12, 11, "helper"), (20, 1, "other"), (30, 31, "x"), (31, 30, "y")]);
+        let mut found: Vec<u32> = with_descendants(&p, &BTreeSet::from([10, 30, 999])).into_iter().collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![10, 11, 12, 30, 31]);
+    }
+
+    #[test]
+    fn parent_chain_walks_up_to_the_root() {
+        let p = procs(&[(1, 0, "launchd"), (503, 1, "Terminal"), (8842, 503, "zsh"), (9001, 8842, "cargo")]);
+        assert_eq!(parent_chain(&p, 9001), vec![1, 503, 8842, 9001]);
+        assert_eq!(parent_chain(&p, 1), vec![1]);
+        assert!(parent_chain(&p, 4242).is_empty());
+    }
+
+    #[test]
+    fn parent_chain_stops_at_broken_links_and_loops() {
+        // zsh's parent exited: the chain starts at zsh.
+        let p = procs(&[(1, 0, "launchd"), (8842, 503, "zsh"), (9001, 8842, "cargo"), (7, 8, "x"), (8, 7, "y")]);
+        assert_eq!(parent_chain(&p, 9001), vec![8842, 9001]);
+        assert_eq!(parent_chain(&p, 7), vec![8, 7]);
+    }
+
+    #[test]
+    fn filter_ignores_case_and_keeps_sort_order() {
+        let p = procs(&[(1, 0, "/sbin/launchd"), (2, 1, "Safari"), (3, 1, "safaridriver")]);
+        assert_eq!(pids(&filter_pids(&p, &[3, 1, 2], Some("SAFARI"), FilterMode::Plain, false)), vec![3, 2]);
+    }
+
+    #[test]
+    fn filter_edge_cases() {
+        let p = procs(&[(1, 0, "launchd"), (2, 1, "Ünïcode")]);
+        assert_eq!(pids(&filter_pids(&p, &[1, 2], None, FilterMode::Plain, false)), vec![1, 2]);
+        // An empty filter matches everything rather than nothing.
+        assert_eq!(pids(&filter_pids(&p, &[1, 2], Some(""), FilterMode::Plain, false)), vec![1, 2]);
+        assert!(filter_pids(&p, &[1, 2], Some("zsh"), FilterMode::Plain, false).is_empty());
+        assert_eq!(pids(&filter_pids(&p, &[1, 2], Some("üNÏ"), FilterMode::Plain, false)), vec![2]);
+        // PIDs that are no longer in the map are skipped.
+        assert_eq!(pids(&filter_pids(&p, &[1, 2, 3], None, FilterMode::Plain, false)), vec![1, 2]);
+    }
+
+    #[test]
+    fn fuzzy_filter_ranks_best_matches_first() {
+        let p = procs(&[(1, 0, "Google Chrome"), (2, 0, "/Applications/Google Chrome.app/Contents/Frameworks/Google Chrome Helper"), (3, 0, "zsh"), (4, 0, "xchromed")]);
+        assert_eq!(pids(&filter_pids(&p, &[1, 2, 3, 4], Some("chrmhlpr"), FilterMode::Fuzzy, false)), vec![2]);
+        // "Chrome" as a word beats it inside one, whatever the sort says; the sort breaks ties.
+        assert_eq!(pids(&filter_pids(&p, &[4, 2, 1, 3], Some("Chrome"), FilterMode::Fuzzy, false)), vec![2, 1, 4]);
+        // Plain matching doesn't reorder.
+        assert_eq!(pids(&filter_pids(&p, &[4, 2, 1, 3], Some("chrome"), FilterMode::Plain, false)), vec![4, 2, 1]);
+    }
+
+    #[test]
+    fn match_ranges_cover_every_occurrence() {
+        assert_eq!(match_ranges("node node_modules/.bin/Node", "node", FilterMode::Plain), vec![0..4, 5..9, 23..27]);
+        assert_eq!(match_ranges("Ünïcode ünï", "ünï", FilterMode::Plain), vec![0..5, 10..15]);
+        assert!(match_ranges("zsh", "bash", FilterMode::Plain).is_empty());
+        assert_eq!(match_ranges("Google Chrome Helper", "chrhl", FilterMode::Fuzzy), vec![7..10, 14..15, 16..17]);
+    }
+
+    #[test]
+    fn filter_matches_the_executable_path_only_when_asked() {
+        let mut p = procs(&[(1, 0, "python3 a.py"), (2, 0, "python3 b.py")]);
+        p.get_mut(&1).unwrap().exe = "/usr/bin/python3".to_string();
+        p.get_mut(&2).unwrap().exe = "/opt/homebrew/bin/python3".to_string();
+        assert_eq!(pids(&filter_pids(&p, &[1, 2], Some("homebrew"), FilterMode::Plain, true)), vec![2]);
+        assert!(filter_pids(&p, &[1, 2], Some("homebrew"), FilterMode::Plain, false).is_empty());
+    }
+
+    #[test]
+    fn command_sort_groups_by_program_name() {
+        let p = procs(&[(1, 0, "/usr/bin/python3 b.py"), (2, 0, "zsh"), (3, 0, "python3 a.py"), (4, 0, "/bin/zsh -l"), (5, 0, "")]);
+        let mut order = vec![1, 2, 3, 4, 5];
+        sort_pids(&mut order, &p, SortBy::Command, SortOrder::Asc, false);
+        assert_eq!(order, vec![5, 1, 3, 4, 2]);
+        assert_eq!(command_basename("/Applications/Safari.app/Contents/MacOS/Safari"), "Safari");
+        assert_eq!(command_basename("dir/"), "");
+    }
+
+    #[test]
+    fn mem_delta_waits_for_a_full_window() {
+        let mut p = ProcessInfo::default();
+        for rss in [100, 150, 120] {
+            p.resident = rss;
+            p.push_resident(3, 0);
+        }
+        assert_eq!(p.mem_delta, Some(20));
+        // The window slides: the oldest sample drops out and the history stays bounded.
+        p.resident = 400;
+        p.push_resident(3, 0);
+        assert_eq!((p.mem_delta, p.resident_history.len()), (Some(250), 3));
+
+        let mut young = ProcessInfo { resident: 1 << 30, ..Default::default() };
+        young.push_resident(3, 0);
+        young.push_resident(3, 0);
+        assert_eq!(young.mem_delta, None);
+    }
+
+    #[test]
+    fn io_rates_need_two_samples_and_sort_busiest_first() {
+        let mut p = ProcessInfo { pid: 1, ..Default::default() };
+        p.push_disk_io(Some(1000), Some(0), Some(2.0));
+        assert_eq!((p.read_rate, p.write_rate), (None, None));
+        p.push_disk_io(Some(5000), Some(300), Some(2.0));
+        assert_eq!((p.read_rate, p.write_rate), (Some(2000), Some(150)));
+        // A new process under a reused PID starts its counters over.
+        p.push_disk_io(Some(10), Some(300), Some(2.0));
+        assert_eq!((p.read_rate, p.write_rate), (None, Some(0)));
+        // Nothing to compare against without an interval, or where the OS won't tell.
+        p.push_disk_io(Some(20), None, None);
+        assert_eq!((p.read_rate, p.write_rate), (None, None));
+
+        let mut procs = HashMap::new();
+        for (pid, rate) in [(1, Some(10)), (2, None), (3, Some(4000))] {
+            procs.insert(pid, ProcessInfo { pid, read_rate: rate, ..Default::default() });
+        }
+        let mut order = vec![1, 2, 3];
+        sort_pids(&mut order, &procs, SortBy::DiskRead, SortBy::DiskRead.default_order(), false);
+        assert_eq!(order, [3, 1, 2]);
+    }
+
+    #[test]
+    fn leak_needs_steady_growth_over_the_whole_window() {
+        let watch = |samples: &[u64]| {
+            let mut p = ProcessInfo::default();
+            for &rss in samples {
+                p.resident = rss;
+                p.push_resident(2, 4);
+            }
+            p
+        };
+        let p = watch(&[10, 20, 20, 30, 40]);
+        assert!(p.leaking);
+        // ΔMEM still covers its own, shorter window.
+        assert_eq!((p.mem_delta, p.resident_history.len()), (Some(10), 4));
+        assert!(!watch(&[10, 20, 30]).leaking); // Not watched long enough
+        assert!(!watch(&[10, 20, 15, 30, 40]).leaking); // Gave memory back
+        assert!(!watch(&[10, 50, 50, 50, 50]).leaking); // One jump, then flat
+    }
+
+    #[test]
+    fn pgrp_sort_keeps_groups_together_in_pid_order() {
+        let mut p = procs(&[(1, 0, "a"), (2, 0, "b"), (3, 0, "c"), (4, 0, "d")]);
+        for (pid, pgid) in [(1, Some(3)), (2, None), (3, Some(3)), (4, Some(1))] { p.get_mut(&pid).unwrap().pgid = pgid; }
+        let mut order = vec![1, 2, 3, 4];
+        sort_pids(&mut order, &p, SortBy::Pgrp, SortOrder::Asc, false);
+        assert_eq!(order, vec![2, 4, 1, 3]);
+    }
+
+    #[test]
+    fn sort_by_cpu_descending_and_time_by_elapsed() {
+        let mut p = procs(&[(1, 0, "a"), (2, 0, "b"), (3, 0, "c")]);
+        for (pid, cpu, cpu_time, run_time) in [(1, 5.0, 9, 1), (2, 50.0, 1, 3), (3, 20.0, 5, 2)] {
+            let info = p.get_mut(&pid).unwrap();
+            (info.cpu, info.cpu_time, info.run_time) = (cpu, Some(Duration::from_secs(cpu_time)), run_time);
+        }
+        let mut order = vec![1, 2, 3];
+        sort_pids(&mut order, &p, SortBy::Cpu, SortOrder::Desc, false);
+        assert_eq!(order, vec![2, 3, 1]);
+        sort_pids(&mut order, &p, SortBy::Time, SortOrder::Asc, false);
+        assert_eq!(order, vec![2, 3, 1]);
+        sort_pids(&mut order, &p, SortBy::Time, SortOrder::Asc, true);
+        assert_eq!(order, vec![1, 3, 2]);
+        let mut top = vec![1, 2, 3];
+        sort_top_pids(&mut top, 1, &p, SortBy::Cpu, SortOrder::Desc, false);
+        assert_eq!(top[0], 2);
+    }
+}
@@ -0,0 +1,56 @@
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::platform::{launchd_service, restart_process};
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+impl App {
+    // 'R': restart the selected process through launchd, which is what a supervised service
+    // would do after a kill anyway, minus the crash-and-throttle dance. The job is looked up
+    // here, spawning `launchctl`, unless the detail pane has it already.
+    pub fn request_restart(&mut self) {
+        if self.read_only {
+            self.message = Some("read-only mode — actions disabled".to_string());
+            return;
+        }
+        let Some(pid) = self.selected_pid() else { return };
+        match self.known_service(pid).map(str::to_string).or_else(|| launchd_service(pid)) {
+            Some(service) => {
+                self.pending_restart = Some((pid, service));
+                self.input_mode = InputMode::ConfirmRestart;
+            }
+            None => self.message = Some(format!("{} isn't a launchd job we can see; use F9 to signal it", self.process_label(pid))),
+        }
+    }
+
+    pub fn confirm_restart(&mut self, confirmed: bool) {
+        self.input_mode = InputMode::Normal;
+        let Some((_, service)) = self.pending_restart.take() else { return };
+        self.message = Some(if !confirmed {
+            "Cancelled".to_string()
+        } else {
+            match restart_process(&service) {
+                Ok(()) => format!("Restarted {}", service),
+                Err(e) => format!("Could not restart {}: {}", service, e),
+            }
+        });
+    }
+}
+
+pub fn draw_restart_confirm(f: &mut Frame, app: &App, area: Rect) {
+    let Some((pid, service)) = &app.pending_restart else { return };
+    let text = vec![
+        Line::from(format!("Restart {} through launchd?", app.process_label(*pid))),
+        Line::from(""),
+        Line::from(format!("launchctl kickstart -k {}", service)),
+        Line::from(""),
+        Line::from(Span::styled("y = restart, any other key = cancel", app.theme.dim)),
+    ];
+    let area = centered_rect(50, 25, area);
+    let dialog = Paragraph::new(text).wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title("Restart service"));
+    clear_popup(f, app, area);
+    f.render_widget(dialog, area);
+}
@@ -0,0 +1,64 @@
+use crate::platform;
+use crate::App;
+
+impl App {
+    // 'f': select the process's executable in Finder, or its app bundle for an application.
+    pub fn reveal_in_finder(&mut self) {
+        let Some(pid) = self.selected_pid() else { return };
+        if self.replay.is_some() {
+            self.message = Some("A recording's paths are those of the machine it was made on".to_string());
+            return;
+        }
+        let Some(path) = self.executable_path(pid) else {
+            self.message = Some(format!("The executable of {} isn't known", self.process_label(pid)));
+            return;
+        };
+        let target = app_bundle(&path).unwrap_or(&path);
+        self.message = Some(match platform::open(&["-R", target]) {
+            Ok(()) => format!("Revealed {} in Finder", target),
+            Err(e) => format!("Could not reveal {}: {}", target, e),
+        });
+    }
+
+    // 'l': Console has no command line to pick a process, so it opens on the live stream and
+    // the message says what to search for.
+    pub fn open_console(&mut self) {
+        let Some(pid) = self.selected_pid() else { return };
+        let Some(name) = self.processes.get(&pid).map(|p| p.name.clone()) else { return };
+        self.message = Some(match platform::open(&["-a", "Console"]) {
+            Ok(()) => format!("Opened Console; search for \"process:{}\" to see its messages", name),
+            Err(e) => format!("Could not open Console: {}", e),
+        });
+    }
+
+    // What the OS reports as the executable, else the program in argv when that is an
+    // absolute path. A relative argv[0] depends on a working directory we don't know.
+    fn executable_path(&self, pid: u32) -> Option<String> {
+        let p = self.processes.get(&pid)?;
+        if !p.exe.is_empty() { return Some(p.exe.clone()); }
+        let program = p.command[..p.args_start].trim_end();
+        program.starts_with('/').then(|| program.to_string())
+    }
+}
+
+// "/Applications/Safari.app/Contents/MacOS/Safari" -> "/Applications/Safari.app". Helpers nested
+// in another bundle resolve to the innermost one.
+fn app_bundle(path: &str) -> Option<&str> {
+    let end = path.rfind(".app/Contents/")? + ".app".len();
+    Some(&path[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_bundle_is_the_innermost_app() {
+        assert_eq!(app_bundle("/Applications/Safari.app/Contents/MacOS/Safari"), Some("/Applications/Safari.app"));
+        assert_eq!(
+            app_bundle("/Applications/Slack.app/Contents/Frameworks/Slack Helper.app/Contents/MacOS/Slack Helper"),
+            Some("/Applications/Slack.app/Contents/Frameworks/Slack Helper.app"),
+        );
+        assert_eq!(app_bundle("/usr/bin/ssh"), None);
+    }
+}
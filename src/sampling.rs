@@ -0,0 +1,76 @@
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Instant;
+
+use crate::platform::{self, process_identity, ProcessIdentity};
+use crate::viewer::{save_text, TextViewer};
+use crate::{App, InputMode};
+
+// A stack sample being taken on a background thread. The table keeps refreshing meanwhile; the
+// result opens in the text viewer once the main screen is back in front, or goes to a file.
+pub struct Sampling {
+    pid: u32,
+    name: String,
+    identity: Option<ProcessIdentity>, // To tell afterwards whether the process exited meanwhile
+    started: Instant,
+    receiver: Receiver<Result<String, String>>,
+    result: Option<Result<String, String>>, // Arrived while a popup was open
+    to_file: bool, // Written to ~ without opening the viewer
+}
+
+impl App {
+    // 'S': stacks of every thread of the selected process, for "why is it hanging".
+    pub fn start_sampling(&mut self, to_file: bool) {
+        let Some(pid) = self.selected_pid() else { return };
+        if self.replay.is_some() {
+            self.message = Some("Processes in a recording can't be sampled".to_string());
+            return;
+        }
+        if let Some(sampling) = &self.sampling {
+            self.message = Some(format!("Still sampling {} (PID {})", sampling.name, sampling.pid));
+            return;
+        }
+        let name = self.processes.get(&pid).map_or_else(|| pid.to_string(), |p| p.name.clone());
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || { let _ = sender.send(platform::stack_sample(pid)); });
+        self.sampling = Some(Sampling { pid, name, identity: process_identity(pid), started: Instant::now(), receiver, result: None, to_file });
+    }
+
+    // Called from the UI loop. True when the footer or the screen needs redrawing: the
+    // progress counts seconds, so it redraws while a sample is running.
+    pub fn poll_sampling(&mut self) -> bool {
+        let Some(sampling) = &mut self.sampling else { return false };
+        if sampling.result.is_none() { sampling.result = sampling.receiver.try_recv().ok(); }
+        // Only the viewer has to wait for the main screen; a file can be written any time.
+        if !sampling.to_file && self.input_mode != InputMode::Normal { return sampling.result.is_none(); }
+        let Some(result) = sampling.result.take() else { return true };
+        let sampling = self.sampling.take().unwrap();
+        let exited = process_identity(sampling.pid) != sampling.identity;
+        let label = format!("{} (PID {})", sampling.name, sampling.pid);
+        match result {
+            Ok(mut text) => {
+                if exited { text.insert_str(0, &format!("Note: {} exited during sampling; this is what was captured before.\n\n", label)); }
+                let stem = format!("sample-{}-{}", sampling.name.replace(['/', ' '], "_"), sampling.pid);
+                if !sampling.to_file {
+                    self.open_viewer(TextViewer::new(format!("Stack sample: {}", label), &text, stem));
+                    return true;
+                }
+                self.message = Some(match save_text(&stem, &text) {
+                    Ok(path) => format!("Stack sample of {} saved to {}", label, path.display()),
+                    Err(e) => format!("Stack sample of {} not saved: {}", label, e),
+                });
+            }
+            Err(_) if exited => self.message = Some(format!("{} exited before it could be sampled", label)),
+            Err(e) => self.message = Some(format!("Could not sample {}: {}", label, e)),
+        }
+        true
+    }
+
+    // Footer progress while sampling.
+    pub fn sampling_hint(&self) -> Option<String> {
+        let sampling = self.sampling.as_ref()?;
+        Some(match sampling.result {
+            Some(_) => format!("[Sample of {} ready]", sampling.name),
+            None => format!("[Sampling {} (PID {})… {}s]", sampling.name, sampling.pid, sampling.started.elapsed().as_secs()),
+        })
+    }
+}
@@ -0,0 +1,157 @@
+// Screens, as in htop 3: tabs over the process table, each with its own columns, sort and
+// filter, switched with Tab and Shift+Tab. The active screen's settings live in the App's usual
+// fields (`columns`, `sort_by`, ...), so the table code doesn't know about screens; switching
+// swaps them with the ones the screen being left keeps here.
+use ratatui::text::{Line, Span};
+use ratatui::widgets::TableState;
+use serde::{Deserialize, Serialize};
+
+use crate::columns::{Column, ColumnState};
+use crate::config::Config;
+use crate::process_list::{SortBy, SortOrder};
+use crate::App;
+
+// A screen as listed under `[[screens]]` in the config. The first screen, Main, isn't listed:
+// it is the top-level `columns`, `sort_by` and `tree_view`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ScreenConfig {
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub sort_by: SortBy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<SortOrder>, // The column's usual direction when not given
+    pub tree_view: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>, // Applied when the screen first shows, like one typed with '/'
+}
+
+impl Default for ScreenConfig {
+    fn default() -> Self {
+        ScreenConfig { name: "Screen".to_string(), columns: Column::DEFAULT.to_vec(), sort_by: SortBy::Cpu, sort_order: None, tree_view: false, filter: None }
+    }
+}
+
+impl ScreenConfig {
+    // What `screens` holds unless the config lists its own.
+    pub fn defaults() -> Vec<Self> {
+        vec![ScreenConfig {
+            name: "Memory".to_string(),
+            columns: vec![Column::Pid, Column::User, Column::Virt, Column::Footprint, Column::Mem, Column::MemDelta, Column::Command],
+            sort_by: SortBy::Mem,
+            ..Default::default()
+        }]
+    }
+}
+
+// What a screen keeps while another one is shown.
+pub struct Screen {
+    pub name: String,
+    columns: Vec<ColumnState>,
+    sort_by: SortBy,
+    sort_order: SortOrder,
+    filter: Option<String>,
+    tree_view: bool,
+    state: TableState, // Selected row and scroll offset
+    selected: Option<u32>, // PID of the selected row, followed in preference to its index
+}
+
+impl Screen {
+    // Main's settings are already in the App's fields; it only needs its name.
+    pub fn main() -> Self {
+        Screen { name: "Main".to_string(), columns: Vec::new(), sort_by: SortBy::Cpu, sort_order: SortOrder::Desc, filter: None, tree_view: false, state: TableState::default(), selected: None }
+    }
+
+    pub fn new(screen: &ScreenConfig, config: &Config) -> Self {
+        Screen {
+            name: screen.name.clone(),
+            columns: screen.columns.iter().map(|&c| ColumnState::new(c, &config.column_widths)).collect(),
+            sort_by: screen.sort_by,
+            sort_order: screen.sort_order.unwrap_or_else(|| config.sort_order_for(screen.sort_by)),
+            filter: screen.filter.clone().filter(|f| !f.is_empty()),
+            tree_view: screen.tree_view,
+            state: TableState::default(),
+            selected: None,
+        }
+    }
+}
+
+impl App {
+    pub fn next_screen(&mut self) {
+        self.switch_screen((self.screen + 1) % self.screens.len());
+    }
+
+    pub fn previous_screen(&mut self) {
+        self.switch_screen((self.screen + self.screens.len() - 1) % self.screens.len());
+    }
+
+    fn switch_screen(&mut self, to: usize) {
+        if to == self.screen { return; }
+        let selected = self.selected_pid();
+        let leaving = &mut self.screens[self.screen];
+        std::mem::swap(&mut leaving.columns, &mut self.columns);
+        (leaving.sort_by, leaving.sort_order, leaving.tree_view) = (self.sort_by, self.sort_order, self.tree_view);
+        leaving.filter = self.active_filter.take();
+        leaving.state = std::mem::take(&mut self.state);
+        leaving.selected = selected;
+
+        self.screen = to;
+        let entering = &mut self.screens[to];
+        std::mem::swap(&mut entering.columns, &mut self.columns);
+        (self.sort_by, self.sort_order, self.tree_view) = (entering.sort_by, entering.sort_order, entering.tree_view);
+        self.active_filter = entering.filter.clone();
+        self.state = entering.state.clone();
+        let selected = entering.selected;
+        self.search_query = self.active_filter.clone().unwrap_or_default();
+        self.cell_format.path_column = self.columns.iter().any(|c| c.column == Column::Path && !c.auto);
+        self.fit_columns();
+        self.sort_processes();
+        if let Some(pid) = selected { self.select_pid(pid); }
+    }
+
+    // Title of the process table: the screens' names with the active one highlighted, or just
+    // "Processes" when there is only the one.
+    pub fn screen_tabs(&self) -> Line<'_> {
+        if self.screens.len() < 2 { return Line::from("Processes"); }
+        let mut spans = Vec::with_capacity(self.screens.len() * 2);
+        for (i, screen) in self.screens.iter().enumerate() {
+            if i > 0 { spans.push(Span::styled("│", self.theme.dim)); }
+            let name = format!(" {} ", screen.name);
+            spans.push(if i == self.screen { Span::styled(name, self.theme.selection) } else { Span::raw(name) });
+        }
+        Line::from(spans)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_screen_keeps_its_own_sort_and_selection() {
+        let mut app = App::with_processes(&Config::default(), &[(1, 0, 5.0, "p1"), (2, 0, 50.0, "p2"), (3, 0, 20.0, "p3")]);
+        for (pid, mem) in [(1, 30.0), (2, 1.0), (3, 10.0)] {
+            app.processes.get_mut(&pid).unwrap().mem = mem;
+        }
+        app.select_pid(3);
+        app.set_filter(Some("p".to_string()));
+        app.select_pid(3);
+
+        app.next_screen();
+        assert_eq!(app.screen, 1);
+        assert!(app.sort_by == SortBy::Mem && app.active_filter.is_none());
+        assert!(app.columns.iter().any(|c| c.column == Column::Footprint));
+        assert_eq!(app.view.iter().map(|&(_, pid)| pid).collect::<Vec<_>>(), [1, 3, 2]);
+        app.select_pid(2);
+
+        app.previous_screen();
+        assert!(app.sort_by == SortBy::Cpu && app.active_filter.as_deref() == Some("p"));
+        assert_eq!(app.selected_pid(), Some(3));
+        assert!(!app.columns.iter().any(|c| c.column == Column::Footprint));
+        app.next_screen();
+        assert_eq!(app.selected_pid(), Some(2));
+        // Past the last screen comes Main again.
+        app.next_screen();
+        assert_eq!(app.screen, 0);
+    }
+}
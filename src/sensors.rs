@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use sysinfo::Components;
+
+use crate::platform::{self, SensorKind, SensorReading};
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// Temperatures drift slowly, and reading fans and power spawns powermetrics.
+const SENSOR_INTERVAL: Duration = Duration::from_secs(5);
+
+// One row of the sensors screen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sensor {
+    pub name: String,
+    pub kind: SensorKind,
+    pub value: f32,
+    pub min: f32, // Since launch
+    pub max: f32,
+}
+
+// The sensors screen ('H'). Unlike the other screens, it is fed from launch on, so its minimum
+// and maximum cover the whole session.
+#[derive(Default)]
+pub struct SensorScreen {
+    receiver: Option<Receiver<Vec<SensorReading>>>, // None with --replay
+    sensors: Option<Vec<Sensor>>, // None until the first readings are in
+    failed: HashSet<String>, // Sensors that once errored; they don't come back
+    selected: usize,
+}
+
+impl App {
+    // Lists the sensors once, on a thread of its own, then reads them every `SENSOR_INTERVAL`
+    // until the App goes away.
+    pub fn start_sensors(&mut self) {
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            let mut components = Components::new_with_refreshed_list();
+            loop {
+                let mut readings: Vec<SensorReading> = components.iter().map(|c| SensorReading {
+                    name: c.label().to_string(),
+                    kind: SensorKind::Temperature,
+                    value: c.temperature(),
+                }).collect();
+                readings.extend(platform::smc_sensors());
+                if sender.send(readings).is_err() { return; }
+                std::thread::sleep(SENSOR_INTERVAL);
+                components.refresh();
+            }
+        });
+        self.sensors.receiver = Some(receiver);
+    }
+
+    pub fn open_sensors(&mut self) {
+        self.input_mode = InputMode::Sensors;
+    }
+
+    // Called from the UI loop. True when the open screen has new values to show.
+    pub fn poll_sensors(&mut self) -> bool {
+        let Some(receiver) = &self.sensors.receiver else { return false };
+        let Some(readings) = receiver.try_iter().last() else { return false };
+        let screen = &mut self.sensors;
+        screen.sensors = Some(merge(screen.sensors.as_deref().unwrap_or_default(), readings, &mut screen.failed));
+        screen.selected = screen.selected.min(screen.sensors.as_ref().map_or(0, |s| s.len().saturating_sub(1)));
+        self.input_mode == InputMode::Sensors
+    }
+
+    pub fn handle_sensors_key(&mut self, code: KeyCode) {
+        let len = self.sensors.sensors.as_ref().map_or(0, Vec::len);
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('H') => self.input_mode = InputMode::Normal,
+            KeyCode::Down if len > 0 => self.sensors.selected = (self.sensors.selected + 1).min(len - 1),
+            KeyCode::Up => self.sensors.selected = self.sensors.selected.saturating_sub(1),
+            _ => {}
+        }
+    }
+}
+
+// This round's readings folded into the known sensors, temperatures first, then fans, then
+// power, by name within each. A sensor that fails to read (NaN, or a temperature of 0 °C, which
+// is what some report instead) is left out from then on rather than shown as zero.
+fn merge(previous: &[Sensor], readings: Vec<SensorReading>, failed: &mut HashSet<String>) -> Vec<Sensor> {
+    let mut sensors: Vec<Sensor> = Vec::with_capacity(readings.len());
+    for r in readings {
+        let broken = !r.value.is_finite() || (r.kind == SensorKind::Temperature && r.value <= 0.0);
+        if broken { failed.insert(r.name.clone()); }
+        if failed.contains(&r.name) || sensors.iter().any(|s| s.name == r.name) { continue; }
+        let (min, max) = match previous.iter().find(|s| s.name == r.name) {
+            Some(s) => (s.min.min(r.value), s.max.max(r.value)),
+            None => (r.value, r.value),
+        };
+        sensors.push(Sensor { name: r.name, kind: r.kind, value: r.value, min, max });
+    }
+    sensors.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+    sensors
+}
+
+fn format_value(kind: SensorKind, value: f32) -> String {
+    match kind {
+        SensorKind::Temperature => format!("{:.1} °C", value),
+        SensorKind::Fan => format!("{:.0} rpm", value),
+        SensorKind::Power => format!("{:.2} W", value),
+    }
+}
+
+pub fn draw_sensors(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(70, 70, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Sensors");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [list_area, hint_area] = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(inner);
+    f.render_widget(Paragraph::new(Span::styled("↑/↓ select   Esc close", app.theme.dim)), hint_area);
+
+    let sensors = match (&app.sensors.receiver, &app.sensors.sensors) {
+        (None, _) => return f.render_widget(Paragraph::new("Sensors aren't part of recordings"), list_area),
+        (Some(_), None) => return f.render_widget(Paragraph::new("Looking for sensors…"), list_area),
+        (Some(_), Some(sensors)) if sensors.is_empty() => {
+            let mut text = vec![Line::from("No sensors accessible without elevated privileges.")];
+            if !platform::is_root() {
+                text.push(Line::from(Span::styled("Fans and power are read with powermetrics, which needs root.", app.theme.dim)));
+            }
+            return f.render_widget(Paragraph::new(text), list_area);
+        }
+        (Some(_), Some(sensors)) => sensors,
+    };
+    let rows: Vec<Row> = sensors.iter().map(|s| Row::new([
+        Cell::from(s.name.as_str()),
+        Cell::from(format_value(s.kind, s.value)),
+        Cell::from(format_value(s.kind, s.min)),
+        Cell::from(format_value(s.kind, s.max)),
+    ])).collect();
+    let header = Row::new(["SENSOR", "NOW", "MIN", "MAX"]).style(app.theme.column_header);
+    let widths = [Constraint::Min(20), Constraint::Length(10), Constraint::Length(10), Constraint::Length(10)];
+    let table = Table::new(rows, widths).header(header).highlight_style(app.theme.selection).highlight_symbol(app.selection_marker(""));
+    let mut state = TableState::default().with_selected(Some(app.sensors.selected));
+    f.render_stateful_widget(table, list_area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(name: &str, kind: SensorKind, value: f32) -> SensorReading {
+        SensorReading { name: name.to_string(), kind, value }
+    }
+
+    #[test]
+    fn min_max_are_kept_and_failed_sensors_stay_out() {
+        let mut failed = HashSet::new();
+        let first = merge(&[], vec![
+            reading("Fan", SensorKind::Fan, 1200.0),
+            reading("CPU die", SensorKind::Temperature, 50.0),
+            reading("GPU die", SensorKind::Temperature, 0.0),
+        ], &mut failed);
+        assert_eq!(first.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["CPU die", "Fan"]);
+
+        let second = merge(&first, vec![
+            reading("Fan", SensorKind::Fan, 1500.0),
+            reading("CPU die", SensorKind::Temperature, 45.0),
+            reading("GPU die", SensorKind::Temperature, 40.0),
+        ], &mut failed);
+        assert_eq!(second[0], Sensor { name: "CPU die".to_string(), kind: SensorKind::Temperature, value: 45.0, min: 45.0, max: 50.0 });
+        assert_eq!((second[1].min, second[1].max), (1200.0, 1500.0));
+        // GPU die answered this time, but it failed once.
+        assert_eq!(second.len(), 2);
+        assert!(merge(&second, vec![reading("Fan", SensorKind::Fan, f32::NAN)], &mut failed).is_empty());
+        assert_eq!(format_value(SensorKind::Power, 1.25), "1.25 W");
+    }
+}
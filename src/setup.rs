@@ -0,0 +1,141 @@
+use crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::meters::Meter;
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// Panes of the setup screen, left to right.
+const AVAILABLE: usize = 0;
+const LEFT: usize = 1;
+const RIGHT: usize = 2;
+
+// Cursor state of the setup screen (F2).
+#[derive(Default)]
+pub struct SetupState {
+    pane: usize,
+    cursor: [usize; 3],
+}
+
+impl App {
+    pub fn open_setup(&mut self) {
+        self.setup = SetupState::default();
+        self.input_mode = InputMode::Setup;
+    }
+
+    fn setup_pane_len(&self, pane: usize) -> usize {
+        match pane {
+            LEFT => self.header_left.len(),
+            RIGHT => self.header_right.len(),
+            _ => Meter::ALL.len(),
+        }
+    }
+
+    fn setup_column(&mut self, pane: usize) -> &mut Vec<Meter> {
+        if pane == LEFT { &mut self.header_left } else { &mut self.header_right }
+    }
+
+    pub fn handle_setup_key(&mut self, code: KeyCode) {
+        let pane = self.setup.pane;
+        let cursor = self.setup.cursor[pane];
+        let len = self.setup_pane_len(pane);
+        match code {
+            KeyCode::Esc | KeyCode::F(2) | KeyCode::Char('q') => self.close_setup(),
+            KeyCode::Tab => self.setup.pane = (pane + 1) % 3,
+            KeyCode::BackTab => self.setup.pane = (pane + 2) % 3,
+            KeyCode::Down if len > 0 => self.setup.cursor[pane] = (cursor + 1) % len,
+            KeyCode::Up if len > 0 => self.setup.cursor[pane] = (cursor + len - 1) % len,
+            // Available meters: Left/Right append the meter to that header column.
+            KeyCode::Left if pane == AVAILABLE => self.header_left.push(Meter::ALL[cursor]),
+            KeyCode::Right if pane == AVAILABLE => self.header_right.push(Meter::ALL[cursor]),
+            // Configured meters: Left/Right move the meter across, '['/']' reorder, Delete removes.
+            KeyCode::Left | KeyCode::Right if cursor < len => {
+                let target = if code == KeyCode::Left { LEFT } else { RIGHT };
+                if target != pane {
+                    let meter = self.setup_column(pane).remove(cursor);
+                    self.setup_column(target).push(meter);
+                    self.setup.pane = target;
+                    self.setup.cursor[target] = self.setup_pane_len(target) - 1;
+                }
+            }
+            KeyCode::Char('[') if pane != AVAILABLE && cursor > 0 && cursor < len => {
+                self.setup_column(pane).swap(cursor, cursor - 1);
+                self.setup.cursor[pane] -= 1;
+            }
+            KeyCode::Char(']') if pane != AVAILABLE && cursor + 1 < len => {
+                self.setup_column(pane).swap(cursor, cursor + 1);
+                self.setup.cursor[pane] += 1;
+            }
+            KeyCode::Char('s') => self.toggle_cpu_smoothing(),
+            KeyCode::Delete | KeyCode::Backspace | KeyCode::Char('d') if pane != AVAILABLE && cursor < len => {
+                self.setup_column(pane).remove(cursor);
+                self.setup.cursor[pane] = cursor.min(len.saturating_sub(2));
+            }
+            _ => {}
+        }
+        // Keep the cursors valid after columns shrank.
+        for pane in [LEFT, RIGHT] {
+            let len = self.setup_pane_len(pane);
+            self.setup.cursor[pane] = self.setup.cursor[pane].min(len.saturating_sub(1));
+        }
+    }
+
+    // Takes effect at once, from the samples already kept; saved with the rest on leaving.
+    fn toggle_cpu_smoothing(&mut self) {
+        self.config.smooth_cpu = !self.config.smooth_cpu;
+        let smooth = self.config.smooth_cpu;
+        for p in self.processes.values_mut() { p.cpu = if smooth { p.smoothed_cpu() } else { p.cpu_now() }; }
+        self.sort_processes();
+    }
+
+    // Leaving setup persists the layout so it survives restarts.
+    fn close_setup(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.config.header_left = self.header_left.clone();
+        self.config.header_right = self.header_right.clone();
+        self.message = Some(match self.config.save() {
+            Ok(()) => "Setup saved".to_string(),
+            Err(e) => format!("Could not save setup: {}", e),
+        });
+    }
+}
+
+pub fn draw_setup(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(70, 60, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Setup: header meters");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default().direction(Direction::Vertical).constraints([Constraint::Min(3), Constraint::Length(2)]).split(inner);
+    let panes = Layout::default().direction(Direction::Horizontal).constraints([Constraint::Ratio(1, 3); 3]).split(rows[0]);
+    let lists: [(&str, Vec<Meter>); 3] = [
+        ("Available meters", Meter::ALL.to_vec()),
+        ("Left column", app.header_left.clone()),
+        ("Right column", app.header_right.clone()),
+    ];
+    for (pane, (title, meters)) in lists.into_iter().enumerate() {
+        let focused = pane == app.setup.pane;
+        let border_style = if focused { app.theme.input } else { Style::default() };
+        let items: Vec<ListItem> = meters.iter().map(|m| ListItem::new(m.name())).collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title).border_style(border_style))
+            .highlight_style(if focused { app.theme.selection } else { Style::default() })
+            .highlight_symbol(app.selection_marker(">> "));
+        let mut state = ListState::default().with_selected((!meters.is_empty()).then_some(app.setup.cursor[pane]));
+        f.render_stateful_widget(list, panes[pane], &mut state);
+    }
+
+    let hints = match app.setup.pane {
+        AVAILABLE => "←/→ add to left/right column   Tab next pane   Esc save and close",
+        _ => "←/→ move across   [ ] reorder   Del remove   Tab next pane   Esc save and close",
+    };
+    let smoothing = match app.config.smooth_cpu {
+        true => format!("s  CPU% smoothing: on, over {} samples", app.config.smooth_cpu_samples),
+        false => "s  CPU% smoothing: off".to_string(),
+    };
+    f.render_widget(Paragraph::new(vec![Line::from(smoothing), Line::from(hints)]).style(app.theme.dim), rows[1]);
+}
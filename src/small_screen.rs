@@ -0,0 +1,75 @@
+// Layout for small terminals, a tmux pane say. Below `COMPACT` the header shrinks to one summary
+// line and the table to the columns that matter most; below `MINIMUM` nothing is usable and the
+// screen just says so.
+use ratatui::layout::{Alignment, Rect};
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::columns::{Column, ColumnState};
+use crate::App;
+
+const MINIMUM: (u16, u16) = (40, 8); // Width, height
+const COMPACT: (u16, u16) = (80, 20);
+// What the compact table keeps, of the configured columns.
+const COMPACT_COLUMNS: [Column; 4] = [Column::Pid, Column::Cpu, Column::Mem, Column::Command];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Fit {
+    TooSmall,
+    Compact,
+    Full,
+}
+
+pub fn fit(size: Rect) -> Fit {
+    if size.width < MINIMUM.0 || size.height < MINIMUM.1 {
+        Fit::TooSmall
+    } else if size.width < COMPACT.0 || size.height < COMPACT.1 {
+        Fit::Compact
+    } else {
+        Fit::Full
+    }
+}
+
+impl App {
+    // The table's columns as drawn.
+    pub fn shown_columns(&self) -> impl Iterator<Item = &ColumnState> {
+        self.columns.iter().filter(|c| !self.compact || COMPACT_COLUMNS.contains(&c.column))
+    }
+}
+
+pub fn draw_too_small(f: &mut Frame, size: Rect) {
+    let text = format!("Window too small: {}×{}, needs {}×{}", size.width, size.height, MINIMUM.0, MINIMUM.1);
+    // Centered, as far as there is room to center it in.
+    let lines = (text.chars().count() as u16).div_ceil(size.width.max(1)).min(size.height);
+    let area = Rect { y: size.y + (size.height - lines) / 2, height: lines, ..size };
+    f.render_widget(Paragraph::new(text).alignment(Alignment::Center).wrap(Wrap { trim: true }), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use crate::config::Config;
+
+    #[test]
+    fn breakpoints_pick_the_layout_and_compact_keeps_four_columns() {
+        assert_eq!(fit(Rect::new(0, 0, 1, 1)), Fit::TooSmall);
+        assert_eq!(fit(Rect::new(0, 0, 200, 7)), Fit::TooSmall);
+        assert_eq!(fit(Rect::new(0, 0, 40, 8)), Fit::Compact);
+        assert_eq!(fit(Rect::new(0, 0, 60, 15)), Fit::Compact);
+        assert_eq!(fit(Rect::new(0, 0, 80, 20)), Fit::Full);
+
+        let mut app = App::new(&Config::default());
+        let all = app.shown_columns().count();
+        app.compact = true;
+        let shown: Vec<Column> = app.shown_columns().map(|c| c.column).collect();
+        assert_eq!(shown, COMPACT_COLUMNS);
+        assert!(all > shown.len());
+
+        for (width, height) in [(1, 1), (2, 1), (39, 8), (12, 3)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            terminal.draw(|f| draw_too_small(f, f.size())).unwrap();
+        }
+    }
+}
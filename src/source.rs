@@ -0,0 +1,655 @@
+// Where refresh data comes from. `update_data` only sees the `DataSource` trait, so the live
+// system and a recording made with --record can be swapped freely.
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Cpu, ProcessRefreshKind, ProcessStatus, System};
+
+use crate::format::format_timestamp;
+use crate::platform::{self, Battery, CodeSigning, CpuTicks, MemoryPressure, ProcessGroup, ProcessPaths, ResourceUsage, TaskInfo, ThreadInfo};
+
+// System-wide numbers for one refresh.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SystemSample {
+    pub uptime: u64,
+    pub load_avg: [f64; 3], // 1, 5 and 15 minutes
+    pub cpus: Vec<f32>, // Usage per core, 0-100
+    #[serde(default)] // Missing from older recordings
+    pub cpu_freqs: Vec<u64>, // Clock per core in MHz; empty where the OS won't tell
+    pub total_mem: u64,
+    pub used_mem: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    #[serde(default)]
+    pub memory_pressure: Option<MemoryPressure>, // None where the OS has no such notion
+    #[serde(default)]
+    pub cpu_split: Option<CpuSplit>, // Over the last refresh interval; None where the OS won't tell
+    #[serde(default)]
+    pub battery: Option<Battery>, // Only read while `Extras::battery` asks for it
+}
+
+// How all cores together spent the time between two samples, in percent, as `top` shows it.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+pub struct CpuSplit {
+    pub user: f32,
+    pub system: f32,
+    pub idle: f32,
+}
+
+impl CpuSplit {
+    // Rounded to tenths so that the three add up to exactly 100.0 as shown; idle takes up the
+    // rounding. None if no ticks went by.
+    pub fn between(before: CpuTicks, after: CpuTicks) -> Option<CpuSplit> {
+        let user = after.user.saturating_sub(before.user);
+        let system = after.system.saturating_sub(before.system);
+        let total = user + system + after.idle.saturating_sub(before.idle);
+        if total == 0 { return None; }
+        let tenths = |ticks: u64| (ticks as f64 * 1000.0 / total as f64).round() as u32;
+        let (user, system) = (tenths(user), tenths(system));
+        let idle = 1000u32.saturating_sub(user + system);
+        Some(CpuSplit { user: user as f32 / 10.0, system: system as f32 / 10.0, idle: idle as f32 / 10.0 })
+    }
+}
+
+// One process as sampled. Borrows from the source, so the live path doesn't allocate per process.
+pub struct ProcessSample<'a> {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub uid: Option<u32>,
+    pub name: &'a str,
+    pub cmd: &'a [String],
+    pub exe: Option<&'a str>, // Absolute path of the executable, where we may see it
+    pub status: Cow<'a, str>,
+    pub cpu_usage: f32, // Raw sysinfo value: 100 per fully used core
+    pub memory: u64,
+    pub virtual_memory: u64,
+    pub run_time: u64,
+    pub cpu_time: Option<Duration>, // Only collected when asked for; see `Extras`
+    pub threads: Option<u32>, // Likewise
+    pub disk_read: Option<u64>, // Bytes read from disk over the process's lifetime
+    pub disk_written: Option<u64>,
+    pub footprint: Option<u64>, // Only collected when asked for; see `Extras`
+    pub ports: Option<u32>, // Mach ports held; None where we may not look, or when not asked for
+    pub group: Option<ProcessGroup>, // Likewise only when asked for
+}
+
+// Per-process counters that cost a syscall per process, so `refresh` only collects those the
+// table shows.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct Extras {
+    pub footprint: bool,
+    pub ports: bool,
+    pub group: bool,
+    pub task: bool, // CPU time and thread count, which sysinfo reads but doesn't hand out
+    pub detail: Option<u32>, // Process in the detail pane; see `DetailSample`
+    pub battery: bool, // Not per process, but it spawns pmset
+}
+
+impl Extras {
+    fn any(self) -> bool {
+        self.footprint || self.ports || self.group || self.task
+    }
+}
+
+// What `Extras` collected for one process.
+#[derive(Default)]
+struct ExtraCounters {
+    footprint: Option<u64>,
+    ports: Option<u32>,
+    group: Option<ProcessGroup>,
+    task: Option<TaskInfo>,
+}
+
+// What the detail pane shows of one process beyond the table. Listing threads and paths are
+// syscalls, and code signing and the launchd job spawn `codesign` and `launchctl`; none of that
+// should happen under the App lock.
+#[derive(Clone)]
+pub struct DetailSample {
+    pub pid: u32,
+    pub threads: Result<Vec<ThreadInfo>, &'static str>, // Or why they can't be listed
+    pub paths: Option<ProcessPaths>, // None where the source can't look them up at all
+    pub signing: Option<CodeSigning>, // Looked up once per process: its code doesn't change
+    pub service: Option<String>, // launchd job; likewise looked up once
+}
+
+impl DetailSample {
+    // All a recording can say.
+    fn recorded(pid: u32) -> Self {
+        DetailSample { pid, threads: Err("Threads aren't part of recordings"), paths: None, signing: None, service: None }
+    }
+}
+
+pub trait DataSource {
+    // Take a new sample. Replays don't advance here; see `Replay::step`.
+    fn refresh(&mut self);
+    // Which `Extras` the following refreshes collect. Recordings don't carry them.
+    fn want_extras(&mut self, _extras: Extras) {}
+    fn system(&self) -> &SystemSample;
+    fn for_each_process(&mut self, f: &mut dyn FnMut(ProcessSample<'_>));
+    // Problems worth telling the user about, reported once.
+    fn take_error(&mut self) -> Option<String> { None }
+    // Extra counters for one process, asked for on demand rather than for every process.
+    // Recordings don't carry them.
+    fn resource_usage(&self, _pid: u32) -> Option<ResourceUsage> { None }
+    // The process of the application the user is looking at, if that can be found out.
+    fn frontmost_pid(&self) -> Option<u32> { None }
+    // Process group and session.
+    fn process_group(&self, _pid: u32) -> Option<ProcessGroup> { None }
+    // The detail pane's view of `pid`, as of the last refresh that collected it (see
+    // `Extras::detail`); None until then.
+    fn detail(&self, pid: u32) -> Option<DetailSample> { Some(DetailSample::recorded(pid)) }
+}
+
+// CPU% is the CPU time used between two process refreshes. Taking those two refreshes this far
+// apart, right before the sample is handed out, gives current usage rather than an average over
+// the whole display interval, which lags behind what the user is looking at.
+const CPU_SAMPLE_WINDOW: Duration = Duration::from_millis(200);
+
+// Core clocks change constantly but reading them spawns powermetrics on macOS.
+const FREQ_INTERVAL: Duration = Duration::from_secs(10);
+
+// Likewise for the battery, which changes even more slowly.
+const BATTERY_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct Live {
+    sys: System,
+    sample: SystemSample,
+    frontmost: platform::FrontApp, // Looked up in `refresh`: it spawns processes, which shouldn't happen under the App lock
+    freq_checked: Option<Instant>,
+    battery_checked: Option<Instant>,
+    ticks: Option<CpuTicks>, // As of the last refresh (or launch), for `CpuSplit`
+    extras: Extras,
+    extra: HashMap<u32, ExtraCounters>, // Collected in `refresh`, so no syscalls are made under the App lock
+    detail: Option<DetailSample>, // Likewise
+}
+
+impl Live {
+    pub fn new() -> Self {
+        Live { sys: System::new_all(), sample: SystemSample::default(), frontmost: platform::FrontApp::default(), freq_checked: None, battery_checked: None, ticks: platform::cpu_ticks(), extras: Extras::default(), extra: HashMap::new(), detail: None }
+    }
+}
+
+impl DataSource for Live {
+    fn refresh(&mut self) {
+        self.frontmost.refresh();
+        // The first pass only needs CPU times; the second one also picks up everything else.
+        self.sys.refresh_cpu();
+        self.sys.refresh_processes_specifics(ProcessRefreshKind::new().with_cpu());
+        std::thread::sleep(CPU_SAMPLE_WINDOW);
+        self.sys.refresh_all();
+
+        let load = System::load_average();
+        let s = &mut self.sample;
+        s.uptime = System::uptime();
+        s.load_avg = [load.one, load.five, load.fifteen];
+        s.cpus.clear();
+        s.cpus.extend(self.sys.cpus().iter().map(Cpu::cpu_usage));
+        if self.freq_checked.is_none_or(|t| t.elapsed() >= FREQ_INTERVAL) {
+            s.cpu_freqs = platform::cpu_frequencies().unwrap_or_default();
+            self.freq_checked = Some(Instant::now());
+        }
+        s.total_mem = self.sys.total_memory();
+        s.used_mem = self.sys.used_memory();
+        s.total_swap = self.sys.total_swap();
+        s.used_swap = self.sys.used_swap();
+        s.memory_pressure = platform::memory_pressure();
+        let ticks = platform::cpu_ticks();
+        s.cpu_split = self.ticks.zip(ticks).and_then(|(before, after)| CpuSplit::between(before, after));
+        self.ticks = ticks;
+        if !self.extras.battery {
+            (s.battery, self.battery_checked) = (None, None);
+        } else if self.battery_checked.is_none_or(|t| t.elapsed() >= BATTERY_INTERVAL) {
+            s.battery = platform::battery();
+            self.battery_checked = Some(Instant::now());
+        }
+
+        self.extra.clear();
+        if self.extras.any() {
+            let extras = self.extras;
+            for pid in self.sys.processes().keys() {
+                let pid = pid.as_u32();
+                let mut counters = ExtraCounters::default();
+                if extras.footprint { counters.footprint = platform::resource_usage(pid).map(|u| u.phys_footprint); }
+                if extras.ports { counters.ports = platform::port_count(pid); }
+                if extras.group { counters.group = platform::process_group(pid); }
+                if extras.task { counters.task = platform::task_info(pid); }
+                self.extra.insert(pid, counters);
+            }
+        }
+
+        self.detail = self.extras.detail.map(|pid| {
+            let (signing, service) = match self.detail.take() {
+                Some(detail) if detail.pid == pid => (detail.signing, detail.service),
+                _ => (platform::code_signing(pid), platform::launchd_service(pid)),
+            };
+            let threads = platform::threads(pid).ok_or("Permission denied — run as root to inspect threads");
+            DetailSample { pid, threads, paths: Some(platform::process_paths(pid)), signing, service }
+        });
+    }
+
+    fn want_extras(&mut self, extras: Extras) {
+        self.extras = extras;
+    }
+
+    fn system(&self) -> &SystemSample {
+        &self.sample
+    }
+
+    fn for_each_process(&mut self, f: &mut dyn FnMut(ProcessSample<'_>)) {
+        for p in self.sys.processes().values() {
+            let pid = p.pid().as_u32();
+            let extra = self.extra.get(&pid);
+            let task = extra.and_then(|e| e.task);
+            let io = p.disk_usage();
+            f(ProcessSample {
+                pid,
+                ppid: p.parent().map(|pid| pid.as_u32()),
+                uid: p.user_id().map(|uid| **uid),
+                name: p.name(),
+                cmd: p.cmd(),
+                exe: p.exe().and_then(|path| path.to_str()),
+                status: status_to_string(p.status()),
+                cpu_usage: p.cpu_usage(),
+                memory: p.memory(),
+                virtual_memory: p.virtual_memory(),
+                run_time: p.run_time(),
+                cpu_time: task.as_ref().map(|t| t.cpu_time),
+                threads: task.map(|t| t.threads),
+                disk_read: Some(io.total_read_bytes),
+                disk_written: Some(io.total_written_bytes),
+                footprint: extra.and_then(|e| e.footprint),
+                ports: extra.and_then(|e| e.ports),
+                group: extra.and_then(|e| e.group),
+            });
+        }
+    }
+
+    fn resource_usage(&self, pid: u32) -> Option<ResourceUsage> {
+        platform::resource_usage(pid)
+    }
+
+    fn frontmost_pid(&self) -> Option<u32> {
+        self.frontmost.pid()
+    }
+
+    fn process_group(&self, pid: u32) -> Option<ProcessGroup> {
+        platform::process_group(pid)
+    }
+
+    fn detail(&self, pid: u32) -> Option<DetailSample> {
+        self.detail.as_ref().filter(|detail| detail.pid == pid).cloned()
+    }
+}
+
+// The STATE column's letters, mostly as in htop and ps: (letter, what it means, sysinfo's name
+// for it). Recordings made before every status had a letter have sysinfo's name instead.
+pub const STATUSES: [(&str, &str, &str); 12] = [
+    ("R", "running", "Run"),
+    ("S", "sleeping", "Sleep"),
+    ("D", "uninterruptible wait, usually disk I/O", "UninterruptibleDiskSleep"),
+    ("I", "idle (on macOS: still being created)", "Idle"),
+    ("T", "stopped, by a signal or a debugger", "Stop"),
+    ("t", "stopped while being traced", "Tracing"),
+    ("Z", "zombie: exited, not yet reaped by its parent", "Zombie"),
+    ("X", "dead", "Dead"),
+    ("K", "being killed", "Wakekill"),
+    ("W", "waking up", "Waking"),
+    ("P", "parked", "Parked"),
+    ("L", "waiting on a lock", "LockBlocked"),
+];
+
+fn status_to_string(s: ProcessStatus) -> Cow<'static, str> {
+    match s {
+        ProcessStatus::Run => "R",
+        ProcessStatus::Sleep => "S",
+        ProcessStatus::UninterruptibleDiskSleep => "D",
+        ProcessStatus::Idle => "I",
+        ProcessStatus::Stop => "T",
+        ProcessStatus::Tracing => "t",
+        ProcessStatus::Zombie => "Z",
+        // What sysinfo makes of a process whose thread is in an uninterruptible wait.
+        #[cfg(target_os = "macos")]
+        ProcessStatus::Dead => "D",
+        #[cfg(not(target_os = "macos"))]
+        ProcessStatus::Dead => "X",
+        ProcessStatus::Wakekill => "K",
+        ProcessStatus::Waking => "W",
+        ProcessStatus::Parked => "P",
+        ProcessStatus::LockBlocked => "L",
+        ProcessStatus::Unknown(_) => "?",
+    }.into()
+}
+
+// "T" for a recording's "Stop", or the status as recorded.
+fn status_letter(recorded: &str) -> Cow<'_, str> {
+    match STATUSES.iter().find(|&&(_, _, name)| name == recorded) {
+        Some(&(letter, _, _)) => letter.into(),
+        None => recorded.into(),
+    }
+}
+
+// What a STATE letter means, for the detail pane.
+pub fn status_meaning(letter: &str) -> Option<&'static str> {
+    STATUSES.iter().find(|&&(l, _, _)| l == letter).map(|&(_, meaning, _)| meaning)
+}
+
+// Owned form of `ProcessSample`, as stored in a recording.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RecordedProcess {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub uid: Option<u32>,
+    pub name: String,
+    pub cmd: Vec<String>,
+    #[serde(default)] // Missing from older recordings
+    pub exe: Option<String>,
+    pub status: String,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub virtual_memory: u64,
+    pub run_time: u64,
+    pub cpu_time: Option<Duration>,
+    pub threads: Option<u32>,
+    #[serde(default)]
+    pub disk_read: Option<u64>,
+    #[serde(default)]
+    pub disk_written: Option<u64>,
+}
+
+impl RecordedProcess {
+    fn from_sample(p: &ProcessSample) -> Self {
+        RecordedProcess {
+            pid: p.pid,
+            ppid: p.ppid,
+            uid: p.uid,
+            name: p.name.to_string(),
+            cmd: p.cmd.to_vec(),
+            exe: p.exe.map(str::to_string),
+            status: p.status.to_string(),
+            cpu_usage: p.cpu_usage,
+            memory: p.memory,
+            virtual_memory: p.virtual_memory,
+            run_time: p.run_time,
+            cpu_time: p.cpu_time,
+            threads: p.threads,
+            disk_read: p.disk_read,
+            disk_written: p.disk_written,
+        }
+    }
+
+    fn sample(&self) -> ProcessSample<'_> {
+        ProcessSample {
+            pid: self.pid,
+            ppid: self.ppid,
+            uid: self.uid,
+            name: &self.name,
+            cmd: &self.cmd,
+            exe: self.exe.as_deref(),
+            status: status_letter(&self.status),
+            cpu_usage: self.cpu_usage,
+            memory: self.memory,
+            virtual_memory: self.virtual_memory,
+            run_time: self.run_time,
+            cpu_time: self.cpu_time,
+            threads: self.threads,
+            disk_read: self.disk_read,
+            disk_written: self.disk_written,
+            footprint: None,
+            ports: None,
+            group: None,
+        }
+    }
+}
+
+// One refresh. Recordings are JSON lines, one frame per line.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Frame {
+    pub system: SystemSample,
+    pub processes: Vec<RecordedProcess>,
+}
+
+// Snapshot files are named "htop-<date>-<time>-<number>.jsonl", so they sort oldest first.
+const SNAPSHOT_PREFIX: &str = "htop-";
+const SNAPSHOT_SUFFIX: &str = ".jsonl";
+
+// Where --record writes.
+pub enum RecordTo {
+    File(BufWriter<File>), // Every frame, one per line
+    // Given a directory: every `every`-th frame as a one-frame recording of its own, the oldest
+    // deleted past `keep` (0 keeps them all). For leaving running until something goes wrong.
+    Snapshots { dir: PathBuf, every: u32, keep: usize, seen: u32 },
+}
+
+impl RecordTo {
+    // Whether this refresh is written out at all.
+    fn due(&mut self) -> bool {
+        match self {
+            RecordTo::File(_) => true,
+            RecordTo::Snapshots { every, seen, .. } => {
+                *seen += 1;
+                (*seen - 1) % (*every).max(1) == 0
+            }
+        }
+    }
+
+    fn write_frame(&mut self, frame: &Frame) -> Result<(), String> {
+        match self {
+            RecordTo::File(out) => {
+                serde_json::to_writer(&mut *out, frame).map_err(|e| e.to_string())?;
+                out.write_all(b"\n").and_then(|_| out.flush()).map_err(|e| e.to_string())
+            }
+            RecordTo::Snapshots { dir, keep, seen, .. } => {
+                let name = format!("{}{}-{:06}{}", SNAPSHOT_PREFIX, format_timestamp(SystemTime::now()), seen, SNAPSHOT_SUFFIX);
+                let mut text = serde_json::to_string(frame).map_err(|e| e.to_string())?;
+                text.push('\n');
+                fs::write(dir.join(name), text).map_err(|e| e.to_string())?;
+                if *keep == 0 { return Ok(()); }
+                let snapshots = snapshots(dir)?;
+                for old in &snapshots[..snapshots.len().saturating_sub(*keep)] {
+                    fs::remove_file(old).map_err(|e| format!("{}: {}", old.display(), e))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// The snapshot files in `dir`, oldest first.
+fn snapshots(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(SNAPSHOT_PREFIX) && n.ends_with(SNAPSHOT_SUFFIX)))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+// Wraps another source and writes the samples it hands out to a file, or to a directory of
+// snapshots.
+pub struct Recorder<S> {
+    inner: S,
+    out: Option<RecordTo>, // None once writing failed
+    frame: Frame,
+    error: Option<String>,
+}
+
+impl Recorder<Live> {
+    // Creates the recording file, or checks the snapshot directory can be listed. Done up front,
+    // so a bad path is reported before the UI starts.
+    pub fn open(path: &str, every: u32, keep: usize) -> Result<RecordTo, String> {
+        if Path::new(path).is_dir() {
+            snapshots(Path::new(path))?;
+            return Ok(RecordTo::Snapshots { dir: PathBuf::from(path), every, keep, seen: 0 });
+        }
+        File::create(path).map(|file| RecordTo::File(BufWriter::new(file))).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+impl<S: DataSource> Recorder<S> {
+    pub fn new(inner: S, out: RecordTo) -> Self {
+        Recorder { inner, out: Some(out), frame: Frame::default(), error: None }
+    }
+}
+
+impl<S: DataSource> DataSource for Recorder<S> {
+    fn refresh(&mut self) {
+        self.inner.refresh();
+    }
+
+    fn want_extras(&mut self, extras: Extras) {
+        self.inner.want_extras(extras);
+    }
+
+    fn system(&self) -> &SystemSample {
+        self.inner.system()
+    }
+
+    fn for_each_process(&mut self, f: &mut dyn FnMut(ProcessSample<'_>)) {
+        let Some(out) = self.out.as_mut().and_then(|out| out.due().then_some(out)) else { return self.inner.for_each_process(f) };
+        self.frame.system.clone_from(self.inner.system());
+        self.frame.processes.clear();
+        let processes = &mut self.frame.processes;
+        self.inner.for_each_process(&mut |p| {
+            processes.push(RecordedProcess::from_sample(&p));
+            f(p);
+        });
+        // A failed write shouldn't take the live view down with it; stop recording instead.
+        if let Err(e) = out.write_frame(&self.frame) {
+            self.out = None;
+            self.error = Some(format!("Recording stopped: {}", e));
+        }
+    }
+
+    fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    fn resource_usage(&self, pid: u32) -> Option<ResourceUsage> {
+        self.inner.resource_usage(pid)
+    }
+
+    fn frontmost_pid(&self) -> Option<u32> {
+        self.inner.frontmost_pid()
+    }
+
+    fn process_group(&self, pid: u32) -> Option<ProcessGroup> {
+        self.inner.process_group(pid)
+    }
+
+    fn detail(&self, pid: u32) -> Option<DetailSample> {
+        self.inner.detail(pid)
+    }
+}
+
+fn read_frames(path: &str, frames: &mut Vec<Frame>) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("{}: {}", path, e))?;
+        if line.trim().is_empty() { continue; }
+        frames.push(serde_json::from_str(&line).map_err(|e| format!("{}:{}: {}", path, i + 1, e))?);
+    }
+    Ok(())
+}
+
+// Frames loaded from a recording, shown one at a time.
+pub struct Replay {
+    frames: Vec<Frame>,
+    pos: usize,
+}
+
+impl Replay {
+    // A recording, or a directory of snapshots, played one after the other.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut frames = Vec::new();
+        if Path::new(path).is_dir() {
+            for snapshot in snapshots(Path::new(path))? { read_frames(&snapshot.display().to_string(), &mut frames)?; }
+        } else {
+            read_frames(path, &mut frames)?;
+        }
+        Self::from_frames(frames).ok_or_else(|| format!("{}: no frames recorded", path))
+    }
+
+    pub fn from_frames(frames: Vec<Frame>) -> Option<Self> {
+        if frames.is_empty() { None } else { Some(Replay { frames, pos: 0 }) }
+    }
+
+    // Moves `delta` frames forward (or back), stopping at either end. Returns false if it didn't move.
+    pub fn step(&mut self, delta: isize) -> bool {
+        let pos = self.pos.saturating_add_signed(delta).min(self.frames.len() - 1);
+        let moved = pos != self.pos;
+        self.pos = pos;
+        moved
+    }
+
+    pub fn position(&self) -> (usize, usize) {
+        (self.pos + 1, self.frames.len())
+    }
+}
+
+impl DataSource for Replay {
+    fn refresh(&mut self) {}
+
+    fn system(&self) -> &SystemSample {
+        &self.frames[self.pos].system
+    }
+
+    fn for_each_process(&mut self, f: &mut dyn FnMut(ProcessSample<'_>)) {
+        for p in &self.frames[self.pos].processes {
+            f(p.sample());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_split_is_over_the_interval_and_adds_up() {
+        let before = CpuTicks { user: 1_000_000, system: 500_000, idle: 9_000_000 };
+        let after = CpuTicks { user: 1_000_001, system: 500_001, idle: 9_000_001 };
+        // Thirds each; idle absorbs the rounding.
+        assert_eq!(CpuSplit::between(before, after), Some(CpuSplit { user: 33.3, system: 33.3, idle: 33.4 }));
+        let after = CpuTicks { user: 1_000_123, system: 500_041, idle: 9_000_836 };
+        assert_eq!(CpuSplit::between(before, after), Some(CpuSplit { user: 12.3, system: 4.1, idle: 83.6 }));
+        assert_eq!(CpuSplit::between(before, before), None);
+    }
+
+    #[test]
+    fn snapshots_are_taken_every_so_often_and_the_oldest_dropped() {
+        let dir = std::env::temp_dir().join(format!("htop-macos-snapshots-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("notes.txt"), "kept").unwrap();
+        let out = RecordTo::Snapshots { dir: dir.clone(), every: 3, keep: 2, seen: 0 };
+        let mut recorder = Recorder::new(Replay::from_frames(vec![Frame::default()]).unwrap(), out);
+        for _ in 0..7 { recorder.for_each_process(&mut |_| {}); }
+        assert!(recorder.take_error().is_none());
+        // Refreshes 1, 4 and 7 were written; 1 is gone again.
+        let names: Vec<String> = snapshots(&dir).unwrap().iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names[0].ends_with("-000004.jsonl") && names[1].ends_with("-000007.jsonl"));
+        assert!(dir.join("notes.txt").exists());
+        assert_eq!(Replay::load(dir.to_str().unwrap()).unwrap().position(), (1, 2));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn every_status_gets_a_letter_and_old_recordings_are_translated() {
+        assert_eq!(status_to_string(ProcessStatus::Tracing), "t");
+        assert_eq!(status_to_string(ProcessStatus::Unknown(7)), "?");
+        assert_eq!(status_letter("Stop"), "T");
+        assert_eq!(status_letter("UninterruptibleDiskSleep"), "D");
+        assert_eq!(status_letter("R"), "R");
+        assert_eq!(status_meaning("t"), Some("stopped while being traced"));
+        // The letters are one character each, and none is taken twice.
+        let letters: std::collections::HashSet<&str> = STATUSES.iter().map(|&(letter, _, _)| letter).collect();
+        assert_eq!(letters.len(), STATUSES.len());
+        assert!(letters.iter().all(|l| l.chars().count() == 1));
+    }
+}
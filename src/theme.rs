@@ -0,0 +1,193 @@
+// Every color the UI uses, by what it means rather than what it looks like. Drawing code takes
+// styles from `App::theme` instead of naming colors, so a palette can be swapped in one place.
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+
+// Palettes to pick from with `theme` in the config.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    Monochrome, // Bold, dim and reverse video only, for terminals (or eyes) where color doesn't help
+    ColorBlind, // Blue and orange instead of green and red
+}
+
+// How many colors the terminal can show.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    Basic, // The 8 ANSI colors: no bright variants, so no gray
+    Ansi16,
+    Extended, // 256 colors or true color
+}
+
+impl ColorDepth {
+    // --colors 8|16|256
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "8" => Some(ColorDepth::Basic),
+            "16" => Some(ColorDepth::Ansi16),
+            "256" => Some(ColorDepth::Extended),
+            _ => None,
+        }
+    }
+
+    // Best guess from the environment, the same hints terminfo-less tools go by.
+    pub fn detect(term: Option<&str>, colorterm: Option<&str>) -> Self {
+        if colorterm.is_some_and(|c| c == "truecolor" || c == "24bit") {
+            return ColorDepth::Extended;
+        }
+        match term.unwrap_or("") {
+            t if t.contains("256color") || t.contains("direct") => ColorDepth::Extended,
+            t if t.ends_with("16color") => ColorDepth::Ansi16,
+            "" | "dumb" | "ansi" | "vt100" | "vt102" | "vt220" | "linux" | "cons25" => ColorDepth::Basic,
+            _ => ColorDepth::Ansi16,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub dim: Style, // Hints, separators, disabled actions
+    pub warning: Style, // Zombies, dangerous dialogs, values over a threshold
+    pub banner: Style, // Something is wrong with the data itself (STALE DATA)
+    pub label: Style, // Field names in the detail pane
+    pub column_header: Style, // Process table header cells
+    pub header_row: Style, // Background of the process table header
+    pub frontmost: Style, // Row of the frontmost application
+    pub input: Style, // Text being typed, focused pane borders
+    pub selection: Style, // Highlighted entry of menus and lists
+    pub filter_match: Style, // What the filter matched, inside commands
+    pub base_name: Style, // The program's name inside commands (`highlight_base_name`)
+    pub cpu: Style, // CPU gauges, running task count
+    pub memory: Style,
+    pub swap: Style,
+    pub mem_segments: [Color; 4], // Wired, active, inactive, compressed
+}
+
+impl Theme {
+    pub fn new(name: ThemeName, depth: ColorDepth) -> Self {
+        match name {
+            ThemeName::Default => Theme::for_depth(depth),
+            ThemeName::Monochrome => Theme::monochrome(),
+            ThemeName::ColorBlind => Theme::color_blind(depth),
+        }
+    }
+
+    fn for_depth(depth: ColorDepth) -> Self {
+        let default = Theme {
+            dim: Style::default().fg(Color::DarkGray),
+            warning: Style::default().fg(Color::Red),
+            banner: Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD),
+            label: Style::default().fg(Color::Cyan),
+            column_header: Style::default().fg(Color::Red),
+            header_row: Style::default().bg(Color::Blue),
+            frontmost: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            input: Style::default().fg(Color::Yellow),
+            selection: Style::default().bg(Color::Blue).fg(Color::White),
+            filter_match: Style::default().fg(Color::Yellow).add_modifier(Modifier::UNDERLINED),
+            base_name: Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            cpu: Style::default().fg(Color::Green),
+            memory: Style::default().fg(Color::Cyan),
+            swap: Style::default().fg(Color::Magenta),
+            mem_segments: [Color::Red, Color::Green, Color::Blue, Color::Magenta],
+        };
+        match depth {
+            // Named colors are the 16 ANSI ones, so 256-color terminals get the same palette.
+            ColorDepth::Ansi16 | ColorDepth::Extended => default,
+            // DarkGray and White are bright variants: dim with the attribute instead, and use
+            // the plain (light gray) white.
+            ColorDepth::Basic => Theme {
+                dim: Style::default().add_modifier(Modifier::DIM),
+                banner: Style::default().fg(Color::Gray).bg(Color::Red).add_modifier(Modifier::BOLD),
+                selection: Style::default().bg(Color::Blue).fg(Color::Gray),
+                ..default
+            },
+        }
+    }
+
+    // Okabe and Ito's palette, which stays distinguishable with red-green color blindness: no
+    // red or green anywhere, warnings in vermillion, gauges in blue. 256-color terminals get
+    // its nearest xterm entries; the others an approximation from the 8 basic colors.
+    fn color_blind(depth: ColorDepth) -> Self {
+        let default = Theme::for_depth(depth);
+        let fg = |color| Style::default().fg(color);
+        let (blue, sky, orange, vermillion, purple, yellow) = match depth {
+            ColorDepth::Extended => (Color::Indexed(32), Color::Indexed(74), Color::Indexed(214), Color::Indexed(166), Color::Indexed(175), Color::Indexed(227)),
+            ColorDepth::Ansi16 | ColorDepth::Basic => (Color::Blue, Color::Cyan, Color::Yellow, Color::Magenta, Color::Magenta, Color::Yellow),
+        };
+        Theme {
+            warning: fg(vermillion),
+            banner: default.banner.bg(vermillion),
+            label: fg(sky),
+            column_header: fg(orange),
+            frontmost: fg(sky).add_modifier(Modifier::BOLD),
+            input: fg(yellow),
+            filter_match: fg(yellow).add_modifier(Modifier::UNDERLINED),
+            base_name: fg(sky).add_modifier(Modifier::BOLD),
+            cpu: fg(if depth == ColorDepth::Extended { blue } else { sky }), // Plain blue is too dark to read as text
+            memory: fg(orange),
+            swap: fg(purple),
+            mem_segments: [vermillion, blue, sky, orange],
+            ..default
+        }
+    }
+
+    fn monochrome() -> Self {
+        let plain = Style::default();
+        Theme {
+            dim: plain.add_modifier(Modifier::DIM),
+            warning: plain.add_modifier(Modifier::BOLD),
+            banner: plain.add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            label: plain.add_modifier(Modifier::BOLD),
+            column_header: plain.add_modifier(Modifier::BOLD),
+            header_row: plain.add_modifier(Modifier::REVERSED),
+            frontmost: plain.add_modifier(Modifier::BOLD),
+            input: plain.add_modifier(Modifier::UNDERLINED),
+            selection: plain.add_modifier(Modifier::REVERSED),
+            filter_match: plain.add_modifier(Modifier::UNDERLINED),
+            base_name: plain.add_modifier(Modifier::BOLD),
+            cpu: plain,
+            memory: plain,
+            swap: plain,
+            mem_segments: [Color::Reset; 4],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_depth_from_the_environment() {
+        assert_eq!(ColorDepth::detect(Some("xterm-256color"), None), ColorDepth::Extended);
+        assert_eq!(ColorDepth::detect(Some("xterm"), Some("truecolor")), ColorDepth::Extended);
+        assert_eq!(ColorDepth::detect(Some("xterm"), None), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::detect(Some("vt100"), None), ColorDepth::Basic);
+        assert_eq!(ColorDepth::detect(None, None), ColorDepth::Basic);
+    }
+
+    #[test]
+    fn basic_palette_avoids_bright_colors() {
+        let theme = Theme::for_depth(ColorDepth::Basic);
+        let bright = [Color::DarkGray, Color::White, Color::LightRed, Color::LightGreen, Color::LightBlue];
+        for style in [theme.dim, theme.warning, theme.banner, theme.label, theme.column_header, theme.header_row,
+                      theme.frontmost, theme.input, theme.selection, theme.filter_match, theme.cpu, theme.memory, theme.swap] {
+            assert!(!style.fg.is_some_and(|c| bright.contains(&c)) && !style.bg.is_some_and(|c| bright.contains(&c)));
+        }
+    }
+
+    #[test]
+    fn color_blind_theme_has_no_red_or_green() {
+        let red_green = [Color::Red, Color::Green, Color::LightRed, Color::LightGreen];
+        for depth in [ColorDepth::Basic, ColorDepth::Ansi16, ColorDepth::Extended] {
+            let theme = Theme::new(ThemeName::ColorBlind, depth);
+            for style in [theme.dim, theme.warning, theme.banner, theme.label, theme.column_header, theme.header_row, theme.frontmost,
+                          theme.input, theme.selection, theme.filter_match, theme.base_name, theme.cpu, theme.memory, theme.swap] {
+                assert!(!style.fg.is_some_and(|c| red_green.contains(&c)) && !style.bg.is_some_and(|c| red_green.contains(&c)), "{:?}", depth);
+            }
+            assert!(theme.mem_segments.iter().all(|c| !red_green.contains(c)));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+use crate::platform;
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// Oldest lines are dropped past this; dtruss on a busy process writes thousands a second.
+const MAX_LINES: usize = 10_000;
+
+// Lines scrolled per PageUp/PageDown.
+const PAGE: usize = 20;
+
+// `dtruss -p` running as our child, its output streamed into a pane. Only when we are root:
+// otherwise it goes to a terminal window where sudo can ask for the password.
+pub struct Trace {
+    pid: u32,
+    name: String,
+    child: Option<Child>, // None once dtruss has exited
+    lines: Vec<String>,
+    receiver: Receiver<String>, // Fed by one reader thread per output stream
+    scroll: Option<usize>, // First line shown; None follows the end
+}
+
+impl App {
+    // 's': trace the selected process's system calls. Everything that would make dtrace fail
+    // with something cryptic is checked first.
+    pub fn start_trace(&mut self) {
+        let Some(pid) = self.selected_pid() else { return };
+        if self.replay.is_some() {
+            self.message = Some("Processes in a recording can't be traced".to_string());
+            return;
+        }
+        if let Err(why) = platform::dtrace_allowed() {
+            self.message = Some(why);
+            return;
+        }
+        let name = self.processes.get(&pid).map_or_else(|| pid.to_string(), |p| p.name.clone());
+        if platform::is_root() {
+            self.message = Some(match spawn_dtruss(pid) {
+                Ok((child, receiver)) => {
+                    self.trace = Some(Trace { pid, name, child: Some(child), lines: Vec::new(), receiver, scroll: None });
+                    self.input_mode = InputMode::Trace;
+                    return;
+                }
+                Err(e) => format!("Could not start dtruss: {}", e),
+            });
+            return;
+        }
+        let banner = shell_quote(&format!("Tracing {} (PID {}); Ctrl+C stops", name, pid));
+        let command = format!("echo {}; sudo dtruss -p {}", banner, pid);
+        let app = self.config.terminal_app.clone();
+        self.message = Some(match platform::run_in_terminal(&app, &command) {
+            Ok(()) => format!("dtruss needs root: enter your password in the {} window", app),
+            Err(e) => format!("Could not open {}: {}", app, e),
+        });
+    }
+
+    // Moves whatever dtruss wrote since the last call into the pane. True when there is
+    // something new to draw.
+    pub fn poll_trace(&mut self) -> bool {
+        let Some(trace) = &mut self.trace else { return false };
+        let before = trace.lines.len();
+        trace.lines.extend(trace.receiver.try_iter());
+        let mut changed = trace.lines.len() != before;
+        if let Some(Ok(Some(status))) = trace.child.as_mut().map(Child::try_wait) {
+            trace.lines.push(format!("— dtruss exited ({}) —", status));
+            trace.child = None;
+            changed = true;
+        }
+        if trace.lines.len() > MAX_LINES {
+            let excess = trace.lines.len() - MAX_LINES;
+            trace.lines.drain(..excess);
+            trace.scroll = trace.scroll.map(|s| s.saturating_sub(excess));
+        }
+        changed
+    }
+
+    // Also called on quit: dtruss runs as root and would otherwise outlive us.
+    pub fn stop_trace(&mut self) {
+        if let Some(trace) = &mut self.trace {
+            trace.stop(libc::SIGKILL);
+        }
+        self.trace = None;
+    }
+
+    pub fn handle_trace_key(&mut self, code: KeyCode) {
+        let Some(trace) = &mut self.trace else { return self.input_mode = InputMode::Normal };
+        let last = trace.lines.len().saturating_sub(1);
+        let at = trace.scroll.unwrap_or(last);
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.stop_trace();
+                self.input_mode = InputMode::Normal;
+            }
+            // dtrace prints its summary on SIGINT; the pane stays open to read it.
+            KeyCode::Char('s') => trace.stop(libc::SIGINT),
+            KeyCode::Up => trace.scroll = Some(at.saturating_sub(1)),
+            KeyCode::Down => trace.scroll = Some(at + 1).filter(|&s| s < last),
+            KeyCode::PageUp => trace.scroll = Some(at.saturating_sub(PAGE)),
+            KeyCode::PageDown => trace.scroll = Some(at + PAGE).filter(|&s| s < last),
+            KeyCode::Home => trace.scroll = Some(0),
+            KeyCode::End => trace.scroll = None,
+            _ => {}
+        }
+    }
+}
+
+impl Trace {
+    // dtruss is a script around dtrace; it runs in a process group of its own so the signal
+    // reaches dtrace too.
+    fn stop(&mut self, signal: i32) {
+        let Some(child) = &mut self.child else { return };
+        unsafe { libc::kill(-(child.id() as i32), signal) };
+        if signal == libc::SIGKILL {
+            let _ = child.wait();
+            self.child = None;
+        }
+    }
+}
+
+fn spawn_dtruss(pid: u32) -> std::io::Result<(Child, Receiver<String>)> {
+    let mut child = Command::new("dtruss").arg("-p").arg(pid.to_string())
+        .stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+    let (sender, receiver) = channel();
+    // The calls go to stderr, along with dtrace's own complaints; stdout is the traced
+    // process's, which it shares.
+    forward_lines(child.stderr.take(), sender.clone());
+    forward_lines(child.stdout.take(), sender);
+    Ok((child, receiver))
+}
+
+fn forward_lines(stream: Option<impl Read + Send + 'static>, sender: Sender<String>) {
+    let Some(stream) = stream else { return };
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() { break; }
+        }
+    });
+}
+
+// `text` as one word for sh, whatever it contains.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+pub fn draw_trace(f: &mut Frame, app: &App, area: Rect) {
+    let Some(trace) = &app.trace else { return };
+    let area = centered_rect(90, 80, area);
+    clear_popup(f, app, area);
+    let state = if trace.child.is_some() { "running" } else { "stopped" };
+    let title = format!(" dtruss: {} (PID {}), {} ", trace.name, trace.pid, state);
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let height = inner.height.saturating_sub(1) as usize;
+    // The scroll position is the line at the bottom of the view, so following the end and
+    // scrolling up from it look the same.
+    let bottom = trace.scroll.unwrap_or(trace.lines.len().saturating_sub(1));
+    let first = (bottom + 1).saturating_sub(height);
+    let mut lines: Vec<Line> = trace.lines[first.min(trace.lines.len())..].iter().take(height).map(|l| Line::from(l.as_str())).collect();
+    if trace.lines.is_empty() {
+        lines.push(Line::from(Span::styled("Waiting for system calls…", app.theme.dim)));
+    }
+    lines.resize(height, Line::default());
+    let follow = if trace.scroll.is_some() { "End follow" } else { "following" };
+    let stop = if trace.child.is_some() { "s stop   " } else { "" };
+    lines.push(Line::from(Span::styled(format!("↑/↓ PgUp/PgDn scroll   {}   {}Esc close", follow, stop), app.theme.dim)));
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_quote_survives_quotes() {
+        assert_eq!(shell_quote("Tracing node (PID 42)"), "'Tracing node (PID 42)'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+}
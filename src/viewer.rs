@@ -0,0 +1,214 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::Frame;
+
+use crate::format::format_timestamp;
+use crate::navigation;
+use crate::process_list::FilterMode;
+use crate::{centered_rect, clear_popup, highlight_matches, App, InputMode};
+
+// A page of read-only text in a popup, with scrolling, search ('/', n/N) and saving ('w').
+// Holds stack samples; anything else long and textual can go here too. Its search is its own:
+// the main table's filter is neither used nor touched.
+pub struct TextViewer {
+    title: String,
+    lines: Vec<String>,
+    file_stem: String, // Saved as ~/<stem>-<date>-<time>.txt
+    top: usize, // First line shown
+    pub height: usize, // Lines shown at the last draw, for paging
+    query: String, // Matches are highlighted while it is set
+    typing: bool, // The query is being edited
+    found: Option<usize>, // Line of the match last jumped to
+    status: Option<String>, // How the last search or save went
+}
+
+impl TextViewer {
+    pub fn new(title: String, text: &str, file_stem: String) -> Self {
+        let lines = text.lines().map(str::to_string).collect();
+        TextViewer { title, lines, file_stem, top: 0, height: 1, query: String::new(), typing: false, found: None, status: None }
+    }
+
+    // False once the viewer should close.
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if self.typing {
+            match code {
+                KeyCode::Enter => {
+                    self.typing = false;
+                    self.found = None;
+                    self.find(true);
+                }
+                KeyCode::Esc => (self.typing, self.query) = (false, String::new()),
+                KeyCode::Backspace => { self.query.pop(); }
+                KeyCode::Char(c) => self.query.push(c),
+                _ => {}
+            }
+            return true;
+        }
+        let last_top = self.lines.len().saturating_sub(self.height);
+        match code {
+            // A search in force is cleared first; the next Esc closes.
+            KeyCode::Esc if !self.query.is_empty() => (self.query, self.found, self.status) = (String::new(), None, None),
+            KeyCode::Esc | KeyCode::Char('q') => return false,
+            KeyCode::Up => self.top = self.top.saturating_sub(1),
+            KeyCode::Down => self.top = (self.top + 1).min(last_top),
+            KeyCode::PageUp => self.top = self.top.saturating_sub(self.height),
+            KeyCode::PageDown => self.top = (self.top + self.height).min(last_top),
+            KeyCode::Home => self.top = 0,
+            KeyCode::End => self.top = last_top,
+            KeyCode::Char('/') => (self.typing, self.query) = (true, String::new()),
+            KeyCode::Char('n') => self.find(true),
+            KeyCode::Char('N') => self.find(false),
+            KeyCode::Char('w') => self.save(),
+            _ => {}
+        }
+        true
+    }
+
+    // Case-insensitive, from the last match (or the top of the page), wrapping around.
+    fn find(&mut self, forward: bool) {
+        if self.query.is_empty() { return; }
+        let query = self.query.to_lowercase();
+        let from = self.found.or(self.top.checked_sub(1));
+        match navigation::find(from, self.lines.len(), forward, |i| self.lines[i].to_lowercase().contains(&query)) {
+            Some(i) => {
+                self.found = Some(i);
+                // Scrolled only when off screen, to a third of the way down.
+                if i < self.top || i >= self.top + self.height { self.top = i.saturating_sub(self.height / 3); }
+                self.status = None;
+            }
+            None => self.status = Some(format!("'{}' not found", self.query)),
+        }
+    }
+
+    fn save(&mut self) {
+        let mut text = self.lines.join("\n");
+        text.push('\n');
+        self.status = Some(match save_text(&self.file_stem, &text) {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(e) => format!("Not saved: {}", e),
+        });
+    }
+}
+
+// Writes `text` to ~/<stem>-<date>-<time>.txt, so saves never overwrite each other.
+pub fn save_text(file_stem: &str, text: &str) -> Result<PathBuf, String> {
+    let home = std::env::var_os("HOME").ok_or("HOME isn't set")?;
+    let path = PathBuf::from(home).join(format!("{}-{}.txt", file_stem, format_timestamp(SystemTime::now())));
+    std::fs::write(&path, text).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+impl App {
+    pub fn open_viewer(&mut self, viewer: TextViewer) {
+        self.viewer = Some(viewer);
+        self.input_mode = InputMode::Viewer;
+    }
+
+    pub fn handle_viewer_key(&mut self, code: KeyCode) {
+        if !self.viewer.as_mut().is_some_and(|v| v.handle_key(code)) {
+            self.viewer = None;
+            self.input_mode = InputMode::Normal;
+        }
+    }
+}
+
+// Returns the number of text lines that fit, for paging.
+pub fn draw_viewer(f: &mut Frame, app: &App, area: Rect) -> usize {
+    let Some(viewer) = &app.viewer else { return 1 };
+    let area = centered_rect(90, 85, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title(format!(" {} ", viewer.title));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let height = (inner.height.saturating_sub(1) as usize).max(1);
+    let query = viewer.query.to_lowercase();
+    let query = (!query.is_empty()).then_some(query.as_str());
+    let mut lines: Vec<Line> = viewer.lines.iter().enumerate().skip(viewer.top).take(height).map(|(i, text)| {
+        let line = Line::from(highlight_matches(text.into(), query, FilterMode::Plain, app.theme.filter_match));
+        if viewer.found == Some(i) { line.style(app.theme.selection) } else { line }
+    }).collect();
+    lines.resize(height, Line::default());
+    let shown = (viewer.top + height).min(viewer.lines.len());
+    lines.push(if viewer.typing {
+        Line::from(vec![Span::styled("Search: ", app.theme.label), Span::raw(viewer.query.as_str())])
+    } else {
+        let status = viewer.status.clone().unwrap_or_else(|| format!("{}-{} of {}", (viewer.top + 1).min(shown), shown, viewer.lines.len()));
+        let esc = if viewer.query.is_empty() { "close" } else { "clear search" };
+        Line::from(Span::styled(format!("{}   / search  n/N next/previous  w save  Esc {}", status, esc), app.theme.dim))
+    });
+    f.render_widget(Paragraph::new(lines), inner);
+    if viewer.typing {
+        f.set_cursor(inner.x + 8 + viewer.query.chars().count() as u16, inner.y + height as u16);
+    }
+    height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewer(text: &str) -> TextViewer {
+        let mut viewer = TextViewer::new("test".to_string(), text, "test".to_string());
+        viewer.height = 2;
+        viewer
+    }
+
+    fn search(viewer: &mut TextViewer, query: &str) {
+        viewer.handle_key(KeyCode::Char('/'));
+        for c in query.chars() { viewer.handle_key(KeyCode::Char(c)); }
+        viewer.handle_key(KeyCode::Enter);
+    }
+
+    #[test]
+    fn search_scrolls_to_matches_and_wraps() {
+        let mut v = viewer("Thread 1\n  main\n  read_nocancel\nThread 2\n  poll\n  READ_LOOP");
+        search(&mut v, "read_");
+        assert_eq!(v.found, Some(2));
+        assert_eq!(v.top, 2);
+        v.handle_key(KeyCode::Char('n'));
+        assert_eq!(v.found, Some(5));
+        v.handle_key(KeyCode::Char('n'));
+        assert_eq!(v.found, Some(2));
+        v.handle_key(KeyCode::Char('N'));
+        assert_eq!(v.found, Some(5));
+        search(&mut v, "mach_msg");
+        assert_eq!(v.found, None);
+        assert_eq!(v.status.as_deref(), Some("'mach_msg' not found"));
+        assert!(v.handle_key(KeyCode::Esc));
+        assert!(!v.handle_key(KeyCode::Esc));
+    }
+
+    #[test]
+    fn previous_wraps_to_the_bottom_and_esc_clears_first() {
+        let mut v = viewer("poll
+main
+read
+POLL
+write");
+        search(&mut v, "poll");
+        assert_eq!(v.found, Some(0));
+        v.handle_key(KeyCode::Char('N'));
+        assert_eq!(v.found, Some(3));
+        assert_eq!(v.top, 3);
+        v.handle_key(KeyCode::Char('N'));
+        assert_eq!(v.found, Some(0));
+        // A search that is dropped while being typed leaves nothing to highlight.
+        v.handle_key(KeyCode::Char('/'));
+        v.handle_key(KeyCode::Char('x'));
+        assert!(v.handle_key(KeyCode::Esc));
+        assert!(v.query.is_empty());
+        // Esc clears the search in force, then closes.
+        search(&mut v, "main");
+        assert_eq!(v.found, Some(1));
+        assert!(v.handle_key(KeyCode::Esc));
+        assert_eq!((v.query.as_str(), v.found), ("", None));
+        v.handle_key(KeyCode::Char('n'));
+        assert_eq!(v.found, None);
+        assert!(!v.handle_key(KeyCode::Esc));
+    }
+}
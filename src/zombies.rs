@@ -0,0 +1,121 @@
+// Zombies by the parent that has to reap them ('Z'): one supervisor that never waits on its
+// children is usually behind all of them, and killing it is what clears them.
+use crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::{centered_rect, clear_popup, App, InputMode};
+
+// Zombies listed by PID before the rest are only counted.
+const PIDS_LISTED: usize = 8;
+
+// One parent and the zombies it hasn't reaped.
+#[derive(Debug, PartialEq)]
+pub struct ZombieParent {
+    pub ppid: u32,
+    pub zombies: Vec<u32>,
+}
+
+impl App {
+    // As of the last refresh, the parents with the most zombies first.
+    pub fn zombie_parents(&self) -> Vec<ZombieParent> {
+        let mut parents: Vec<ZombieParent> = Vec::new();
+        for p in self.processes.values().filter(|p| p.status == "Z") {
+            match parents.iter_mut().find(|z| z.ppid == p.ppid) {
+                Some(parent) => parent.zombies.push(p.pid),
+                None => parents.push(ZombieParent { ppid: p.ppid, zombies: vec![p.pid] }),
+            }
+        }
+        for parent in &mut parents { parent.zombies.sort_unstable(); }
+        parents.sort_by(|a, b| b.zombies.len().cmp(&a.zombies.len()).then(a.ppid.cmp(&b.ppid)));
+        parents
+    }
+
+    // "reap by killing parent PID 412 (/usr/local/bin/supervisord -n)", or why that won't do.
+    pub fn reap_hint(&self, ppid: u32) -> String {
+        match self.processes.get(&ppid) {
+            // launchd reaps whatever it inherits; one lingering there is on its way out.
+            _ if ppid <= 1 => "orphaned, launchd will reap it".to_string(),
+            Some(parent) => format!("reap by killing parent PID {} ({})", ppid, parent.command),
+            None => format!("parent PID {} has exited, launchd will reap it", ppid),
+        }
+    }
+
+    pub fn open_zombies(&mut self) {
+        self.zombie_selected = 0;
+        self.input_mode = InputMode::Zombies;
+    }
+
+    pub fn handle_zombies_key(&mut self, code: KeyCode) {
+        let len = self.zombie_parents().len();
+        // Parents come and go between refreshes.
+        self.zombie_selected = self.zombie_selected.min(len.saturating_sub(1));
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Z') => self.input_mode = InputMode::Normal,
+            KeyCode::Down if len > 0 => self.zombie_selected = (self.zombie_selected + 1).min(len - 1),
+            KeyCode::Up => self.zombie_selected = self.zombie_selected.saturating_sub(1),
+            // Select the parent in the table, ready for F9.
+            KeyCode::Enter => {
+                let Some(ppid) = self.zombie_parents().get(self.zombie_selected).map(|z| z.ppid) else { return };
+                if !self.processes.contains_key(&ppid) { return; }
+                self.input_mode = InputMode::Normal;
+                self.show_pid(ppid);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn draw_zombies(f: &mut Frame, app: &App, area: Rect) {
+    let area = centered_rect(80, 60, area);
+    clear_popup(f, app, area);
+    let block = Block::default().borders(Borders::ALL).title("Zombies");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let [list_area, hint_area] = Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(inner);
+
+    let parents = app.zombie_parents();
+    if parents.is_empty() {
+        f.render_widget(Paragraph::new("No zombies."), list_area);
+    } else {
+        let items: Vec<ListItem> = parents.iter().map(|z| {
+            let mut pids = z.zombies.iter().take(PIDS_LISTED).map(u32::to_string).collect::<Vec<_>>().join(", ");
+            if z.zombies.len() > PIDS_LISTED { pids.push_str(&format!(" and {} more", z.zombies.len() - PIDS_LISTED)); }
+            let what = if z.zombies.len() == 1 { "Zombie" } else { "Zombies" };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} {}", what, pids), app.theme.warning),
+                Span::raw(format!(" — {}", app.reap_hint(z.ppid))),
+            ]))
+        }).collect();
+        let list = List::new(items).highlight_style(app.theme.selection).highlight_symbol(app.selection_marker(""));
+        let selected = app.zombie_selected.min(parents.len() - 1);
+        f.render_stateful_widget(list, list_area, &mut ListState::default().with_selected(Some(selected)));
+    }
+    let hint = Line::from(Span::styled("↑/↓ select   Enter select the parent   Esc close", app.theme.dim));
+    f.render_widget(Paragraph::new(vec![Line::default(), hint]), hint_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn zombies_are_grouped_under_the_parent_to_kill() {
+        let list = [(1, 0, 0.0, "/sbin/launchd"), (40, 1, 0.0, "supervisord -n"), (41, 40, 0.0, ""), (42, 40, 0.0, ""), (43, 40, 0.0, "worker"), (50, 77, 0.0, ""), (60, 1, 0.0, "")];
+        let mut app = App::with_processes(&Config::default(), &list);
+        for pid in [41, 42, 50, 60] {
+            app.processes.get_mut(&pid).unwrap().status = "Z".to_string();
+        }
+        assert_eq!(app.zombie_parents(), [
+            ZombieParent { ppid: 40, zombies: vec![41, 42] },
+            ZombieParent { ppid: 1, zombies: vec![60] },
+            ZombieParent { ppid: 77, zombies: vec![50] },
+        ]);
+        assert_eq!(app.reap_hint(40), "reap by killing parent PID 40 (supervisord -n)");
+        assert_eq!(app.reap_hint(1), "orphaned, launchd will reap it");
+        assert_eq!(app.reap_hint(77), "parent PID 77 has exited, launchd will reap it");
+    }
+}